@@ -6,12 +6,18 @@ pub mod types;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
 use crate::config::Backend;
-use crate::tasks::TaskCounts;
+use crate::tasks::{Task, TaskCounts};
 use state::App;
 
 pub const DEFAULT_STARTUP_MSG: &str = "Read .hive/workers/{lane}/WORKER.md if it exists. You are assigned to lane '{lane}'. Check your task backlog. If backlog is EMPTY, report 'No tasks in backlog for {lane}' and STOP - do NOT explore or look for other work. If tasks exist, claim ONE task and work on it. When finished, create a git branch, commit, push, and create a Pull Request.";
 
-pub const DEFAULT_NUDGE_MSG: &str = "FIRST: If you have uncommitted changes or an unpushed branch from a previous task, you MUST create a PR NOW using 'gh pr create' before starting anything new. You have {backlog_count} task(s) in your backlog for lane '{lane}'. Claim ONE task and work on it. REMINDER: When done, create a branch, commit, push, and run 'gh pr create' - do NOT stop until the PR URL is displayed.";
+pub const DEFAULT_NUDGE_MSG: &str = "FIRST: If you have uncommitted changes or an unpushed branch from a previous task, you MUST create a PR NOW using 'gh pr create' before starting anything new. You have {backlog_count} task(s) ready in your backlog for lane '{lane}'. Claim task '{next_task_id}' ({next_task_title}) and work on it. REMINDER: When done, create a branch, commit, push, and run 'gh pr create' - do NOT stop until the PR URL is displayed.";
+
+pub const DEFAULT_QUESTION_MSG: &str = "QUESTION from {worker} on task '{task_id}' ({task_title}) in lane '{lane}': {question} -- Reply with: hive task answer {task_id} \"your answer\"";
+
+pub const DEFAULT_ANSWER_MSG: &str = "ANSWER from the architect on task '{task_id}' ({task_title}): {answer} -- Continue the task with this in mind.";
+
+pub const DEFAULT_VERIFY_FAILED_MSG: &str = "VERIFICATION FAILED for task '{task_id}' ({task_title}): `{command}` did not pass, so the task is back in progress. Run 'hive task show {task_id}' to see the output, fix the problem, then complete the task again.";
 
 pub fn build_startup_message(config: &crate::config::HiveConfig, lane: &str) -> String {
     let template = config
@@ -28,6 +34,7 @@ pub fn build_nudge_message(
     config: &crate::config::HiveConfig,
     lane: &str,
     backlog_count: usize,
+    next_task: Option<&Task>,
     branch: &Option<crate::config::BranchConfig>,
 ) -> String {
     let template = config
@@ -39,7 +46,18 @@ pub fn build_nudge_message(
 
     let mut msg = template
         .replace("{lane}", lane)
-        .replace("{backlog_count}", &backlog_count.to_string());
+        .replace("{backlog_count}", &backlog_count.to_string())
+        .replace("{next_task_id}", next_task.map_or("", |t| t.id.as_str()))
+        .replace(
+            "{next_task_title}",
+            next_task.map_or("", |t| t.title.as_deref().unwrap_or(&t.id)),
+        )
+        .replace(
+            "{next_task_description}",
+            next_task
+                .and_then(|t| t.description.as_deref())
+                .unwrap_or(""),
+        );
 
     if let Some(branch) = branch {
         msg.push_str(&format!(
@@ -51,6 +69,75 @@ pub fn build_nudge_message(
     msg.split_whitespace().collect::<Vec<_>>().join(" ")
 }
 
+/// Prompt for the architect when `worker` sets a question on `task`
+pub fn build_question_message(
+    config: &crate::config::HiveConfig,
+    worker: &str,
+    lane: &str,
+    task: &Task,
+) -> String {
+    let template = config
+        .messages
+        .as_ref()
+        .and_then(|m| m.question.as_ref())
+        .map(|s| s.as_str())
+        .unwrap_or(DEFAULT_QUESTION_MSG);
+
+    let msg = template
+        .replace("{worker}", worker)
+        .replace("{lane}", lane)
+        .replace("{task_id}", &task.id)
+        .replace("{task_title}", task.title.as_deref().unwrap_or(&task.id))
+        .replace("{question}", task.question.as_deref().unwrap_or(""));
+
+    msg.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Prompt for the worker when the architect answers the question on `task`
+pub fn build_answer_message(config: &crate::config::HiveConfig, lane: &str, task: &Task) -> String {
+    let template = config
+        .messages
+        .as_ref()
+        .and_then(|m| m.answer.as_ref())
+        .map(|s| s.as_str())
+        .unwrap_or(DEFAULT_ANSWER_MSG);
+
+    let msg = template
+        .replace("{lane}", lane)
+        .replace("{task_id}", &task.id)
+        .replace("{task_title}", task.title.as_deref().unwrap_or(&task.id))
+        .replace("{question}", task.question.as_deref().unwrap_or(""))
+        .replace("{answer}", task.answer.as_deref().unwrap_or(""));
+
+    msg.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+pub fn build_verify_failed_message(
+    config: &crate::config::HiveConfig,
+    lane: &str,
+    task: &Task,
+) -> String {
+    let template = config
+        .messages
+        .as_ref()
+        .and_then(|m| m.verify_failed.as_ref())
+        .map(|s| s.as_str())
+        .unwrap_or(DEFAULT_VERIFY_FAILED_MSG);
+
+    let command = task
+        .verification
+        .as_ref()
+        .and_then(|v| v.command.as_deref())
+        .unwrap_or("");
+    let msg = template
+        .replace("{lane}", lane)
+        .replace("{task_id}", &task.id)
+        .replace("{task_title}", task.title.as_deref().unwrap_or(&task.id))
+        .replace("{command}", command);
+
+    msg.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
 pub fn key_to_bytes(key: KeyEvent) -> Vec<u8> {
     match key.code {
         KeyCode::Char(c) => {
@@ -20,6 +20,8 @@ pub enum PaletteAction {
     ToggleTaskQueue,
     NudgeAll,
     NudgeFocused,
+    RestartFocused,
+    ShowConfigChanges,
     ToggleHelp,
     Detach,
     Stop,
@@ -67,6 +69,10 @@ pub fn build_items(app: &App) -> Vec<PaletteItem> {
             label: "Nudge focused worker".to_string(),
             action: PaletteAction::NudgeFocused,
         },
+        PaletteItem {
+            label: "Restart focused pane".to_string(),
+            action: PaletteAction::RestartFocused,
+        },
         PaletteItem {
             label: "Toggle help".to_string(),
             action: PaletteAction::ToggleHelp,
@@ -81,6 +87,13 @@ pub fn build_items(app: &App) -> Vec<PaletteItem> {
         },
     ];
 
+    if !app.config_changes.is_empty() {
+        items.push(PaletteItem {
+            label: format!("Config changes ({} pending)", app.config_changes.len()),
+            action: PaletteAction::ShowConfigChanges,
+        });
+    }
+
     for (idx, pane) in app.panes.iter().enumerate() {
         let title = match &pane.pane_type {
             PaneType::Architect => "architect".to_string(),
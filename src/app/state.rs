@@ -1,15 +1,16 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::path::PathBuf;
+use std::time::Instant;
 
 use serde::{Deserialize, Serialize};
 
 use crate::app::sidebar::SidebarState;
-use crate::app::types::PaneType;
+use crate::app::types::{AgentState, PaneType};
 use crate::config::{Backend, BranchConfig};
-use crate::ipc::{AppState, PaneInfo, WindowInfo};
+use crate::ipc::{AppState, ConfigChange, PaneInfo, WindowInfo};
 use crate::projects::ProjectEntry;
 use crate::pty::output::OutputBuffer;
-use crate::tasks::TaskCounts;
+use crate::tasks::{StaleClaim, TaskCounts, TaskEvent, TasksFile};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum LayoutMode {
@@ -38,11 +39,39 @@ pub struct ClientPane {
     pub branch: Option<BranchConfig>,
     pub group: Option<String>,
     pub visible: bool,
+    /// What the agent is doing, from the server
+    pub agent_state: AgentState,
     /// Raw output history for tmux-style scrollback
     pub raw_history: std::collections::VecDeque<u8>,
     pub raw_history_max: usize,
 }
 
+/// How many task events the client keeps for display
+const TASK_EVENT_HISTORY: usize = 20;
+
+/// Fields of the task queue's add form, in tab order
+pub const TASK_FORM_FIELDS: [&str; 4] = ["Id", "Title", "Priority", "Description"];
+
+/// A task queue edit waiting for more input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TaskQueueEdit {
+    /// Add-task form for `lane`, one value per `TASK_FORM_FIELDS` entry
+    Add {
+        lane: String,
+        fields: [String; 4],
+        focus: usize,
+    },
+    /// Picking another lane for a task
+    MoveToLane {
+        id: String,
+        lane: String,
+        lanes: Vec<String>,
+        selection: usize,
+    },
+    /// Waiting for `y` before deleting a task
+    Delete { id: String, lane: String },
+}
+
 pub struct App {
     pub project_name: String,
     pub project_dir: PathBuf,
@@ -66,6 +95,12 @@ pub struct App {
     pub projects_message: Option<String>,
     pub running: bool,
     pub task_counts: HashMap<String, TaskCounts>,
+    /// Lint diagnostics for the tasks file, from the server
+    pub task_problems: Vec<String>,
+    /// Claims the server thinks were abandoned
+    pub stale_claims: Vec<StaleClaim>,
+    /// Recent task changes from the server, newest last
+    pub task_events: VecDeque<(Instant, TaskEvent)>,
     pub zoomed: bool,
     pub worker_page: usize,
     pub smart_mode: bool,
@@ -73,6 +108,23 @@ pub struct App {
     pub show_task_queue: bool,
     pub task_queue_selection: usize,
     pub task_queue_expanded: HashMap<String, bool>,
+    /// Task queue filter query, e.g. `l:bug s:backlog login`
+    pub task_queue_filter: String,
+    /// Whether keys are typed into the task queue filter
+    pub task_queue_filter_editing: bool,
+    /// Form or prompt open in the task queue
+    pub task_queue_edit: Option<TaskQueueEdit>,
+    /// Last error the server sent back, shown in the status bar
+    pub last_error: Option<(Instant, String)>,
+    /// Last notice from the server, e.g. which config sections were reloaded
+    pub last_notice: Option<(Instant, String)>,
+    /// Config file edits that wait for a restart, from the server
+    pub config_changes: Vec<ConfigChange>,
+    /// Why the edited config file couldn't be reloaded
+    pub config_error: Option<String>,
+    pub show_config_changes: bool,
+    /// Last task snapshot from the server, rendered by the task queue overlay
+    pub tasks: Option<TasksFile>,
     pub scroll_mode: bool,
     /// Temporary buffer for scroll mode (parsed from raw_history)
     pub scroll_buffer: Option<crate::pty::output::OutputBuffer>,
@@ -112,6 +164,9 @@ impl App {
             projects_message: None,
             running: true,
             task_counts: HashMap::new(),
+            task_problems: Vec::new(),
+            stale_claims: Vec::new(),
+            task_events: VecDeque::new(),
             zoomed: false,
             worker_page: 0,
             smart_mode: false,
@@ -119,6 +174,15 @@ impl App {
             show_task_queue: false,
             task_queue_selection: 0,
             task_queue_expanded: HashMap::new(),
+            task_queue_filter: String::new(),
+            task_queue_filter_editing: false,
+            task_queue_edit: None,
+            last_error: None,
+            last_notice: None,
+            config_changes: Vec::new(),
+            config_error: None,
+            show_config_changes: false,
+            tasks: None,
             scroll_mode: false,
             scroll_buffer: None,
             min_pane_width: crate::ui::layout::DEFAULT_MIN_PANE_WIDTH,
@@ -239,6 +303,17 @@ impl App {
         self.backend = state.backend;
         self.layout_mode = state.layout_mode;
         self.task_counts = state.task_counts;
+        self.task_problems = state.task_problems;
+        self.stale_claims = state.stale_claims;
+        self.tasks = state.tasks.map(|mut tasks| {
+            tasks.archived = state.archived_tasks;
+            tasks
+        });
+        self.config_changes = state.config_changes;
+        self.config_error = state.config_error;
+        if self.config_changes.is_empty() {
+            self.show_config_changes = false;
+        }
         self.architect_left = state.architect_left;
         self.min_pane_width = state.min_pane_width;
         self.min_pane_height = state.min_pane_height;
@@ -264,6 +339,33 @@ impl App {
         self.ensure_focus_visible();
     }
 
+    /// Remember task changes from the server, keeping the most recent few
+    pub fn push_task_events(&mut self, events: Vec<TaskEvent>) {
+        let now = Instant::now();
+        self.task_events
+            .extend(events.into_iter().map(|event| (now, event)));
+        while self.task_events.len() > TASK_EVENT_HISTORY {
+            self.task_events.pop_front();
+        }
+    }
+
+    /// Apply agent state changes from the server
+    pub fn apply_pane_states(&mut self, states: Vec<(String, AgentState)>) {
+        for (pane_id, agent_state) in states {
+            if let Some(pane) = self.panes.iter_mut().find(|pane| pane.id == pane_id) {
+                pane.agent_state = agent_state;
+            }
+        }
+    }
+
+    pub fn show_error(&mut self, message: String) {
+        self.last_error = Some((Instant::now(), message));
+    }
+
+    pub fn show_notice(&mut self, message: String) {
+        self.last_notice = Some((Instant::now(), message));
+    }
+
     pub fn ensure_focus_visible(&mut self) {
         if self.panes.is_empty() {
             return;
@@ -305,6 +407,7 @@ fn pane_info_to_client(
         branch: pane.branch,
         group: pane.group,
         visible: pane.visible,
+        agent_state: pane.agent_state,
         raw_history,
         raw_history_max: 500_000, // 500KB of history
     }
@@ -5,3 +5,31 @@ pub enum PaneType {
     Architect,
     Worker { lane: String },
 }
+
+/// What a pane's agent is doing, read from its output by the server
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum AgentState {
+    /// Printing output, or quiet but showing that it's working
+    #[default]
+    Busy,
+    /// Quiet at its input prompt
+    Idle,
+    /// Waiting on a permission or yes/no prompt
+    AwaitingApproval,
+    /// Showing an error, e.g. from the model's API
+    Errored,
+    /// Its process has exited
+    Exited,
+}
+
+impl AgentState {
+    pub fn label(self) -> &'static str {
+        match self {
+            AgentState::Busy => "busy",
+            AgentState::Idle => "idle",
+            AgentState::AwaitingApproval => "awaiting approval",
+            AgentState::Errored => "errored",
+            AgentState::Exited => "exited",
+        }
+    }
+}
@@ -4,7 +4,7 @@ use std::os::unix::net::UnixStream;
 use std::path::Path;
 use std::time::{Duration, Instant};
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use crossterm::{
     cursor,
     event::{self, Event, KeyCode, KeyEvent, KeyModifiers},
@@ -14,12 +14,13 @@ use crossterm::{
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
 use ratatui::{backend::CrosstermBackend, Terminal};
 
-use crate::app::state::{App, AppWindow, ClientPane};
+use crate::app::state::{App, AppWindow, ClientPane, TaskQueueEdit};
 use crate::app::{key_to_bytes, layout_visible_panes};
 use crate::config;
 use crate::ipc::{decode_server_message, ClientMessage, PaneSize, ServerMessage};
 use crate::projects;
 use crate::pty::output::{filter_alternate_screen, OutputBuffer};
+use crate::tasks::{Priority, Task, TaskState};
 use crate::ui;
 
 pub fn run(start_dir: &Path) -> Result<()> {
@@ -256,7 +257,7 @@ fn run_tui(
             match message {
                 ServerMessage::State { state } => {
                     log_line(log_path, "apply-state");
-                    app.apply_state(state);
+                    app.apply_state(*state);
 
                     // Immediately resize buffers to current terminal size before processing output
                     // This prevents replay from being processed at wrong size (24x80 default)
@@ -306,14 +307,24 @@ fn run_tui(
                 ServerMessage::PaneExited { pane_id } => {
                     log_line(log_path, &format!("pane-exited {}", pane_id));
                     if let Some(pane) = app.panes.iter_mut().find(|p| p.id == pane_id) {
-                        pane.output_buffer.push_bytes(b"\n[pane exited]");
+                        pane.output_buffer
+                            .push_bytes(b"\n[pane exited; restart it with `r` in the sidebar or `hive restart`]");
                     }
                 }
+                ServerMessage::TaskEvents { events } => {
+                    log_line(log_path, &format!("task-events {:?}", events));
+                    app.push_task_events(events);
+                }
                 ServerMessage::Error { message } => {
-                    log_line(log_path, "server-error");
-                    if let Some(pane) = app.panes.first_mut() {
-                        pane.output_buffer.push_bytes(message.as_bytes());
-                    }
+                    log_line(log_path, &format!("server-error {}", message));
+                    app.show_error(message);
+                }
+                ServerMessage::ConfigReloaded { applied } => {
+                    log_line(log_path, &format!("config-reloaded {:?}", applied));
+                    app.show_notice(format!("config reloaded: {}", applied.join(", ")));
+                }
+                ServerMessage::PaneStates { states } => {
+                    app.apply_pane_states(states);
                 }
             }
         }
@@ -367,12 +378,19 @@ fn handle_key_event(
         return Ok(false);
     }
 
+    if app.show_config_changes {
+        if matches!(key.code, KeyCode::Esc | KeyCode::Char('q')) {
+            app.show_config_changes = false;
+        }
+        return Ok(false);
+    }
+
     if app.show_projects {
         return handle_projects_key(app, key);
     }
 
     if app.show_task_queue {
-        return handle_task_queue_key(app, key);
+        return handle_task_queue_key(app, conn, key);
     }
 
     if app.scroll_mode {
@@ -453,6 +471,16 @@ fn handle_key_event(
                                     })?;
                                 }
                             }
+                            crate::app::palette::PaletteAction::RestartFocused => {
+                                if let Some(pane) = app.panes.get(app.focused_pane) {
+                                    conn.send(ClientMessage::RestartPane {
+                                        pane_id: pane.id.clone(),
+                                    })?;
+                                }
+                            }
+                            crate::app::palette::PaletteAction::ShowConfigChanges => {
+                                app.show_config_changes = true;
+                            }
                             crate::app::palette::PaletteAction::ToggleHelp => {
                                 app.show_help = !app.show_help;
                             }
@@ -521,6 +549,16 @@ fn handle_key_event(
                                     })?;
                                 }
                             }
+                            crate::app::palette::PaletteAction::RestartFocused => {
+                                if let Some(pane) = app.panes.get(app.focused_pane) {
+                                    conn.send(ClientMessage::RestartPane {
+                                        pane_id: pane.id.clone(),
+                                    })?;
+                                }
+                            }
+                            crate::app::palette::PaletteAction::ShowConfigChanges => {
+                                app.show_config_changes = true;
+                            }
                             crate::app::palette::PaletteAction::ToggleHelp => {
                                 app.show_help = !app.show_help;
                             }
@@ -606,6 +644,11 @@ fn handle_key_event(
                 }
                 app.ensure_focus_visible();
             }
+            KeyCode::Char('r') => {
+                if let Some(pane_id) = app.sidebar.selected_pane_id() {
+                    conn.send(ClientMessage::RestartPane { pane_id })?;
+                }
+            }
             _ => {}
         }
         return Ok(false);
@@ -852,13 +895,111 @@ fn handle_projects_key(app: &mut App, key: KeyEvent) -> Result<bool> {
     Ok(false)
 }
 
-fn handle_task_queue_key(app: &mut App, key: KeyEvent) -> Result<bool> {
+fn handle_task_queue_key(app: &mut App, conn: &mut ClientConn, key: KeyEvent) -> Result<bool> {
+    if let Some(edit) = app.task_queue_edit.take() {
+        app.task_queue_edit = handle_task_edit_key(app, conn, edit, key)?;
+        return Ok(false);
+    }
+
+    if app.task_queue_filter_editing {
+        match key.code {
+            KeyCode::Enter => app.task_queue_filter_editing = false,
+            KeyCode::Esc => {
+                app.task_queue_filter.clear();
+                app.task_queue_filter_editing = false;
+            }
+            KeyCode::Backspace => {
+                app.task_queue_filter.pop();
+            }
+            KeyCode::Char(c) => app.task_queue_filter.push(c),
+            _ => return Ok(false),
+        }
+        app.task_queue_selection = 0;
+        return Ok(false);
+    }
+
     let max_lines = crate::ui::task_queue::count_lines(app);
 
     match key.code {
+        KeyCode::Char('/') => {
+            app.task_queue_filter_editing = true;
+        }
+        // Esc clears an active filter before closing
+        KeyCode::Esc if !app.task_queue_filter.is_empty() => {
+            app.task_queue_filter.clear();
+            app.task_queue_selection = 0;
+        }
         KeyCode::Esc | KeyCode::Char('q') => {
             app.show_task_queue = false;
         }
+        KeyCode::Char('a') => {
+            let lane = crate::ui::task_queue::get_selection_lane(app).or_else(|| {
+                app.panes
+                    .get(app.focused_pane)
+                    .and_then(|pane| pane.lane.clone())
+                    .or_else(|| app.panes.iter().find_map(|pane| pane.lane.clone()))
+            });
+            match lane {
+                Some(lane) => {
+                    app.task_queue_edit = Some(TaskQueueEdit::Add {
+                        lane,
+                        fields: Default::default(),
+                        focus: 0,
+                    });
+                }
+                None => app.show_error("No lane to add a task to".to_string()),
+            }
+        }
+        KeyCode::Left | KeyCode::Char('h') | KeyCode::Right | KeyCode::Char('l') => {
+            if let Some((lane, task, state)) = crate::ui::task_queue::get_selected_task(app) {
+                let forward = matches!(key.code, KeyCode::Right | KeyCode::Char('l'));
+                let to_state = match (state, forward) {
+                    (TaskState::Backlog, true) | (TaskState::Done, false) => TaskState::InProgress,
+                    (TaskState::InProgress, true) => TaskState::Done,
+                    (TaskState::InProgress, false) => TaskState::Backlog,
+                    // Already at the end
+                    _ => return Ok(false),
+                };
+                conn.send(ClientMessage::MoveTask {
+                    id: task.id,
+                    lane,
+                    state: to_state,
+                    to_lane: None,
+                })?;
+            }
+        }
+        KeyCode::Char('J') | KeyCode::Char('K') => {
+            if let Some((lane, task, _)) = crate::ui::task_queue::get_selected_task(app) {
+                conn.send(ClientMessage::ReprioritizeTask {
+                    id: task.id,
+                    lane,
+                    up: key.code == KeyCode::Char('K'),
+                })?;
+            }
+        }
+        KeyCode::Char('m') => {
+            if let Some((lane, task, _)) = crate::ui::task_queue::get_selected_task(app) {
+                let lanes: Vec<String> = crate::ui::task_queue::known_lanes(app)
+                    .into_iter()
+                    .filter(|other| *other != lane)
+                    .collect();
+                if lanes.is_empty() {
+                    app.show_error("No other lane to move the task to".to_string());
+                } else {
+                    app.task_queue_edit = Some(TaskQueueEdit::MoveToLane {
+                        id: task.id,
+                        lane,
+                        lanes,
+                        selection: 0,
+                    });
+                }
+            }
+        }
+        KeyCode::Char('x') | KeyCode::Delete => {
+            if let Some((lane, task, _)) = crate::ui::task_queue::get_selected_task(app) {
+                app.task_queue_edit = Some(TaskQueueEdit::Delete { id: task.id, lane });
+            }
+        }
         KeyCode::Up | KeyCode::Char('k') => {
             if app.task_queue_selection > 0 {
                 app.task_queue_selection -= 1;
@@ -897,6 +1038,113 @@ fn handle_task_queue_key(app: &mut App, key: KeyEvent) -> Result<bool> {
     Ok(false)
 }
 
+/// Keys for an open task queue form or prompt. Returns the edit to keep
+/// open, or `None` once it's sent or cancelled.
+fn handle_task_edit_key(
+    app: &mut App,
+    conn: &mut ClientConn,
+    edit: TaskQueueEdit,
+    key: KeyEvent,
+) -> Result<Option<TaskQueueEdit>> {
+    match edit {
+        TaskQueueEdit::Add {
+            lane,
+            mut fields,
+            mut focus,
+        } => {
+            match key.code {
+                KeyCode::Esc => return Ok(None),
+                KeyCode::Tab | KeyCode::Down => focus = (focus + 1) % fields.len(),
+                KeyCode::BackTab | KeyCode::Up => focus = (focus + fields.len() - 1) % fields.len(),
+                KeyCode::Backspace => {
+                    fields[focus].pop();
+                }
+                KeyCode::Char(c) => fields[focus].push(c),
+                KeyCode::Enter => match task_from_form(&fields) {
+                    Ok(task) => {
+                        conn.send(ClientMessage::AddTask {
+                            lane,
+                            task: Box::new(task),
+                        })?;
+                        return Ok(None);
+                    }
+                    Err(err) => app.show_error(err.to_string()),
+                },
+                _ => {}
+            }
+            Ok(Some(TaskQueueEdit::Add {
+                lane,
+                fields,
+                focus,
+            }))
+        }
+        TaskQueueEdit::MoveToLane {
+            id,
+            lane,
+            lanes,
+            mut selection,
+        } => {
+            match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => return Ok(None),
+                KeyCode::Up | KeyCode::Char('k') => selection = selection.saturating_sub(1),
+                KeyCode::Down | KeyCode::Char('j') => {
+                    selection = (selection + 1).min(lanes.len().saturating_sub(1));
+                }
+                KeyCode::Enter => {
+                    if let Some(to_lane) = lanes.get(selection) {
+                        let state = crate::ui::task_queue::get_selected_task(app)
+                            .filter(|(_, task, _)| task.id == id)
+                            .map_or(TaskState::Backlog, |(_, _, state)| state);
+                        conn.send(ClientMessage::MoveTask {
+                            id,
+                            lane,
+                            state,
+                            to_lane: Some(to_lane.clone()),
+                        })?;
+                    }
+                    return Ok(None);
+                }
+                _ => {}
+            }
+            Ok(Some(TaskQueueEdit::MoveToLane {
+                id,
+                lane,
+                lanes,
+                selection,
+            }))
+        }
+        TaskQueueEdit::Delete { id, lane } => {
+            if matches!(key.code, KeyCode::Char('y') | KeyCode::Char('Y')) {
+                conn.send(ClientMessage::DeleteTask { id, lane })?;
+            }
+            Ok(None)
+        }
+    }
+}
+
+/// Backlog task from the add form's id, title, priority and description
+fn task_from_form(fields: &[String; 4]) -> Result<Task> {
+    let [id, title, priority, description] = fields.clone().map(|field| field.trim().to_string());
+    if id.is_empty() {
+        bail!("A task needs an id");
+    }
+    let priority = if priority.is_empty() {
+        None
+    } else {
+        Some(
+            Priority::parse(&priority)
+                .with_context(|| format!("'{}' is not a priority", priority))?,
+        )
+    };
+    Ok(Task {
+        id,
+        title: (!title.is_empty()).then_some(title),
+        description: (!description.is_empty()).then_some(description),
+        priority,
+        ..Default::default()
+    })
+}
+
 fn handle_scroll_mode_key(app: &mut App, key: KeyEvent) -> Result<bool> {
     match key.code {
         KeyCode::Esc | KeyCode::Char('q') => {
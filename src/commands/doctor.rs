@@ -125,9 +125,13 @@ fn run_workspace(workspace_dir: &Path) -> Result<()> {
     let mut issues = Vec::new();
     let mut fixes = Vec::new();
 
-    // Check tasks file
-    let tasks_path = workspace_dir.join("tasks.yaml");
-    if !tasks_path.exists() {
+    // Check tasks file (a GitHub project has none)
+    let tasks_path = config.tasks_file(workspace_dir);
+    let file_backed = !matches!(
+        config.tasks.as_ref().map(|tasks| &tasks.source),
+        Some(TaskSource::Github)
+    );
+    if file_backed && !tasks_path.exists() {
         issues.push(format!("Missing tasks file: {}", tasks_path.display()));
     }
 
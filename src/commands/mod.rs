@@ -6,9 +6,13 @@ pub mod layout;
 pub mod list;
 pub mod nudge;
 pub mod open;
+pub mod report;
+pub mod restart;
 pub mod role;
 pub mod serve;
 pub mod setup;
 pub mod status;
+pub mod task;
+pub mod worker;
 pub mod down;
 pub mod up;
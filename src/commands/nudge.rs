@@ -3,9 +3,9 @@ use std::path::Path;
 use anyhow::Result;
 
 use crate::app::build_nudge_message;
-use crate::config::{self, TaskSource};
+use crate::config;
 use crate::ipc::ClientMessage;
-use crate::tasks::{counts_for_lane, load_tasks};
+use crate::tasks::{counts_for_lane, open_backend};
 
 pub fn run(start_dir: &Path, specific_worker: Option<&str>) -> Result<()> {
     let config_path = config::find_config(start_dir)?;
@@ -25,38 +25,46 @@ pub fn run(start_dir: &Path, specific_worker: Option<&str>) -> Result<()> {
         return Ok(());
     }
 
-    if let TaskSource::Yaml = config.tasks.source {
-        let tasks_path = config::tasks_file_path(&config_path, &config);
-        let tasks = load_tasks(&tasks_path).unwrap_or_default();
+    let backend = open_backend(&config_path, &config)?;
+    let tasks = match backend.load() {
+        Ok(tasks) => tasks,
+        Err(e) => {
+            eprintln!("Failed to load tasks from {}: {:#}", backend.describe(), e);
+            Default::default()
+        }
+    };
 
-        for window in &config.windows {
-            for worker in &window.workers {
-                if let Some(target) = specific_worker {
-                    if worker.id != target {
-                        continue;
-                    }
+    for window in &config.windows {
+        for worker in &window.workers {
+            if let Some(target) = specific_worker {
+                if worker.id != target {
+                    continue;
                 }
+            }
 
-                let lane = worker.lane.clone().unwrap_or_else(|| worker.id.clone());
-                let counts = counts_for_lane(&tasks, &lane);
-
-                // For automatic nudges (all workers): only nudge if backlog AND not busy
-                // For manual nudges (specific worker): nudge if backlog, even if busy
-                let should_nudge = if specific_worker.is_some() {
-                    counts.backlog > 0
-                } else {
-                    counts.backlog > 0 && counts.in_progress == 0
-                };
-
-                if should_nudge {
-                    let message =
-                        build_nudge_message(&config, &lane, counts.backlog, &worker.branch);
-                    println!("[{}] {}", worker.id, message);
-                }
+            let lane = worker.lane.clone().unwrap_or_else(|| worker.id.clone());
+            let counts = counts_for_lane(&tasks, &lane);
+            let next_task = tasks.next_task_for(&lane, &worker.id);
+
+            // For automatic nudges (all workers): only nudge if claimable backlog AND not busy
+            // For manual nudges (specific worker): nudge if claimable backlog, even if busy
+            let should_nudge = if specific_worker.is_some() {
+                next_task.is_some()
+            } else {
+                next_task.is_some() && counts.in_progress == 0
+            };
+
+            if should_nudge {
+                let message = build_nudge_message(
+                    &config,
+                    &lane,
+                    counts.claimable(),
+                    next_task,
+                    &worker.branch,
+                );
+                println!("[{}] {}", worker.id, message);
             }
         }
-    } else {
-        println!("GitHub task source nudging not implemented yet.");
     }
 
     Ok(())
@@ -0,0 +1,68 @@
+use std::path::Path;
+
+use anyhow::{bail, Result};
+
+use crate::config;
+use crate::tasks::{self, build_report, load_archive_files};
+use crate::workspace::config::expand_workers;
+use crate::workspace::resolve::find_workspace_for_path;
+
+use super::task::{task_source_for, tasks_file_for};
+
+pub fn run(start_dir: &Path, since: &str, format: &str) -> Result<()> {
+    let now = chrono::Utc::now();
+    let since = match since {
+        "all" => None,
+        value => Some(tasks::history::parse_since(value, now)?),
+    };
+
+    let tasks = task_source_for(start_dir)?.load()?;
+    // Sources other than tasks.yaml have no archive
+    let archived = tasks_file_for(start_dir)
+        .map(|path| load_archive_files(&path))
+        .unwrap_or_default();
+    let report = build_report(&tasks, &archived, &lane_projects(start_dir)?, since, now);
+
+    let output = match format {
+        "md" | "markdown" => report.to_markdown(),
+        "csv" => report.to_csv(),
+        "json" => report.to_json(),
+        other => bail!("Unknown report format '{}'. Use md, csv or json.", other),
+    };
+    print!("{}", output);
+    if !output.ends_with('\n') {
+        println!();
+    }
+    Ok(())
+}
+
+/// Configured lanes in order, each with the repo its worker runs in
+fn lane_projects(start_dir: &Path) -> Result<Vec<(String, Option<String>)>> {
+    if let Ok(Some(workspace)) = find_workspace_for_path(start_dir) {
+        return Ok(expand_workers(&workspace.config, &workspace.dir)
+            .into_iter()
+            .map(|worker| {
+                let project = worker
+                    .project_path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned());
+                (worker.lane, project)
+            })
+            .collect());
+    }
+
+    let config_path = config::find_config(start_dir)?;
+    let config = config::load_config(&config_path)?;
+    let project = config::project_dir(&config_path)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned());
+    Ok(config
+        .windows
+        .iter()
+        .flat_map(|window| &window.workers)
+        .map(|worker| {
+            let lane = worker.lane.clone().unwrap_or_else(|| worker.id.clone());
+            (lane, project.clone())
+        })
+        .collect())
+}
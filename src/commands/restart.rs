@@ -0,0 +1,49 @@
+use std::io::Write;
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+
+use crate::config;
+use crate::ipc::ClientMessage;
+use crate::workspace::expand_workers;
+use crate::workspace::resolve::find_workspace_for_path;
+
+pub fn run(start_dir: &Path, worker: &str) -> Result<()> {
+    let (socket_path, workers) = if let Ok(Some(workspace)) = find_workspace_for_path(start_dir) {
+        let workers: Vec<String> = expand_workers(&workspace.config, &workspace.dir)
+            .into_iter()
+            .map(|worker| worker.id)
+            .collect();
+        (workspace.dir.join("hive.sock"), workers)
+    } else {
+        let config_path = config::find_config(start_dir)?;
+        let config = config::load_config(&config_path)?;
+        let workers = config
+            .windows
+            .iter()
+            .flat_map(|window| window.workers.iter().map(|worker| worker.id.clone()))
+            .collect();
+        let project_dir = config::project_dir(&config_path);
+        (project_dir.join(".hive").join("hive.sock"), workers)
+    };
+
+    if worker != "architect" && !workers.iter().any(|id| id == worker) {
+        bail!(
+            "No worker named '{}'. Workers: architect, {}",
+            worker,
+            workers.join(", ")
+        );
+    }
+
+    let mut stream = UnixStream::connect(&socket_path)
+        .with_context(|| format!("Failed to connect to {}", socket_path.display()))?;
+
+    let line = serde_json::to_string(&ClientMessage::RestartPane {
+        pane_id: worker.to_string(),
+    })?;
+    writeln!(stream, "{}", line)?;
+
+    println!("Restart requested for {}", worker);
+    Ok(())
+}
@@ -94,7 +94,7 @@ pub fn run(start_dir: &Path, specific_worker: Option<&str>) -> Result<()> {
                 content.push_str("## Completing a Task Without PR\n");
                 content.push_str("If no PR is requested, simply:\n");
                 content.push_str("1. Commit your changes to the current branch\n");
-                content.push_str("2. Move the task to `done` in tasks.yaml\n\n");
+                content.push_str("2. Complete the task with `hive task complete <task-id> --summary \"...\"`\n\n");
             }
 
             if let Some(branch) = &worker.branch {
@@ -131,15 +131,43 @@ pub fn run(start_dir: &Path, specific_worker: Option<&str>) -> Result<()> {
                         rel_tasks.display()
                     ));
                     content.push_str(&format!("- Your lane: `{}`\n", lane));
-                    content.push_str("- Check the `backlog` section for pending tasks\n");
-                    content.push_str("- Move tasks to `in_progress` when you start\n");
-                    content.push_str("- Move tasks to `done` when complete\n\n");
+                    content.push_str(&format!(
+                        "- List your tasks: `hive task list --lane {}`\n",
+                        lane
+                    ));
+                    content.push_str(&format!(
+                        "- Claim a task when you start: `hive task claim <task-id> --by {}`\n",
+                        worker.id
+                    ));
+                    content.push_str("- Only claim tasks with no `assignee` or assigned to you\n");
+                    content.push_str("- Complete it when done: `hive task complete <task-id> --summary \"...\" --file <path>`\n");
+                    content.push_str("- If the task has `verify` commands, run them before completing; hive runs them again afterwards and sends the task back to you if one fails\n");
+                    content.push_str("- Unsure what a task needs? Ask the architect: `hive task ask <task-id> \"question\"` and wait; the answer is typed into your terminal\n");
+                    content.push_str("- Use these commands instead of editing the tasks file by hand\n\n");
                     content.push_str("## YAML Validation (CRITICAL)\n");
-                    content.push_str("When editing tasks.yaml, you MUST ensure valid YAML:\n");
+                    content.push_str("If you ever edit tasks.yaml by hand, you MUST ensure valid YAML:\n");
                     content.push_str("- Empty lists MUST use `[]`, never leave blank (e.g., `backlog: []` not `backlog:`)\n");
                     content.push_str("- After editing, validate with: `yq eval '.' tasks.yaml > /dev/null && echo 'Valid' || echo 'Invalid'`\n");
                     content.push_str("- If validation fails, fix the YAML before proceeding\n\n");
                 }
+                TaskSource::Markdown => {
+                    let rel_tasks = relative_tasks_path(&worker_dir, &tasks_file);
+                    content.push_str("## Task Source\n");
+                    content.push_str(&format!(
+                        "Tasks are checklist items under `## {}` in `{}` (relative to your working directory).\n",
+                        lane,
+                        rel_tasks.display()
+                    ));
+                    content.push_str(&format!(
+                        "- Claim a task when you start: `hive task claim <task-id> --by {}` (adds `(@{})` to the item)\n",
+                        worker.id, worker.id
+                    ));
+                    content.push_str("- Complete it when done: `hive task complete <task-id>` (checks the item)\n");
+                    content.push_str(&format!(
+                        "- Task ids are shown by `hive task list --lane {}`\n\n",
+                        lane
+                    ));
+                }
             }
 
             if let Some(instructions) = &config.worker_instructions {
@@ -217,7 +245,11 @@ fn generate_architect_role(
     content.push_str("Each task should include:\n");
     content.push_str("- **id**: Unique identifier (kebab-case)\n");
     content.push_str("- **description**: What needs to be implemented\n");
-    content.push_str("- **acceptance**: List of criteria for completion\n\n");
+    content.push_str("- **acceptance**: List of criteria for completion\n");
+    content.push_str("- **depends_on**: Optional task ids (any lane) that must be done before this one can be claimed\n");
+    content.push_str("- **labels**: Optional tags for filtering, e.g. `[bug, ui]`\n");
+    content.push_str("- **assignee**: Optional worker id; only that worker may claim the task, even in a shared lane\n");
+    content.push_str("- **verify**: Optional shell commands (e.g. `cargo test -p api`) hive runs in the worker's directory when the task is done; a failure sends it back to in_progress\n\n");
 
     match config.tasks.source {
         TaskSource::Github => {
@@ -241,17 +273,37 @@ fn generate_architect_role(
             ));
             content.push_str("### Adding a Task\n\n");
             content.push_str("```yaml\n<lane-name>:\n  backlog:\n    - id: my-task-id\n      title: Short title for the task\n      description: |\n        Detailed description of what needs to be done.\n      priority: high\n      acceptance:\n        - First acceptance criterion\n        - Second acceptance criterion\n```\n\n");
+            content.push_str("Or add it from the command line (the file is rewritten atomically):\n\n");
+            content.push_str("```bash\nhive task add <lane-name> my-task-id --title \"Short title\" --priority high --acceptance \"First criterion\"\n```\n\n");
+            content.push_str("Use `hive task list` and `hive task show <task-id>` to review progress. Run `hive task lint` after editing tasks.yaml by hand.\n\n");
+            content.push_str("### Global Backlog\n\n");
+            content.push_str("Not sure which lane should take a task? Add it under the top-level `global_backlog:` list. Hive moves it to the lane named by `lane:` or a label, otherwise to the least busy lane of its `project:` (or of any project), and nudges that worker.\n\n");
+            content.push_str("### Worker Questions\n\n");
+            content.push_str("When a worker asks a question it is typed into your terminal with the task id. Reply with `hive task answer <task-id> \"...\"`; the answer is typed into that worker's terminal.\n\n");
             content.push_str("### Task Lifecycle\n\n");
             content.push_str("1. **backlog** - Tasks waiting to be claimed\n");
             content
                 .push_str("2. **in_progress** - Worker is actively working (max 1 per worker)\n");
             content.push_str("3. **done** - Completed with summary\n\n");
             content.push_str("### YAML Validation (CRITICAL)\n\n");
-            content.push_str("When editing tasks.yaml, you MUST ensure valid YAML:\n");
+            content.push_str("If you edit tasks.yaml by hand, you MUST ensure valid YAML:\n");
             content.push_str("- Empty lists MUST use `[]`, never leave blank (e.g., `backlog: []` not `backlog:`)\n");
             content.push_str("- After editing, validate with: `yq eval '.' <tasks-file> > /dev/null && echo 'Valid' || echo 'Invalid'`\n");
             content.push_str("- If validation fails, fix the YAML before proceeding\n\n");
         }
+        TaskSource::Markdown => {
+            content.push_str("## Task Management\n\n");
+            content.push_str(&format!(
+                "Tasks are managed in `{}`, one `## <lane-name>` heading per lane.\n\n",
+                tasks_file.display()
+            ));
+            content.push_str("### Adding a Task\n\n");
+            content.push_str("```markdown\n## <lane-name>\n- [ ] `my-task-id` Short title for the task\n```\n\n");
+            content.push_str("### Task Lifecycle\n\n");
+            content.push_str("1. `- [ ]` - Backlog, waiting to be claimed\n");
+            content.push_str("2. `- [ ] ... (@worker-id)` - Claimed and in progress\n");
+            content.push_str("3. `- [x]` - Done\n\n");
+        }
     }
 
     // PR creation guidance for architect
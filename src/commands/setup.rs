@@ -606,6 +606,15 @@ fn create_workspace(state: &SetupState) -> Result<PathBuf> {
         },
         layout: crate::workspace::config::LayoutConfig::default(),
         workflow: crate::config::WorkflowConfig::default(),
+        messages: None,
+        watch: crate::workspace::config::WatchConfig::default(),
+        archive: crate::workspace::config::ArchiveConfig::default(),
+        stale_claims: crate::workspace::config::StaleClaimsConfig::default(),
+        task_templates: indexmap::IndexMap::new(),
+        recurring: Vec::new(),
+        restart_policy: crate::workspace::config::RestartPolicyConfig::default(),
+        worker_ids: indexmap::IndexMap::new(),
+        tasks: None,
     };
 
     // Add selected projects with their lanes
@@ -659,7 +668,7 @@ fn write_tasks(workspace_dir: &Path, config: &WorkspaceConfig) -> Result<()> {
 
         if project.lanes.len() > 1 {
             // Multi-lane project: nested structure
-            let mut lanes = std::collections::HashMap::new();
+            let mut lanes = indexmap::IndexMap::new();
             for lane in &project.lanes {
                 lanes.insert(lane.clone(), LaneTasks::default());
             }
@@ -682,7 +691,8 @@ fn write_tasks(workspace_dir: &Path, config: &WorkspaceConfig) -> Result<()> {
     Ok(())
 }
 
-fn write_architect_role(workspace_dir: &Path, config: &WorkspaceConfig) -> Result<()> {
+/// Write the workspace's ARCHITECT.md
+pub fn write_architect_role(workspace_dir: &Path, config: &WorkspaceConfig) -> Result<()> {
     let mut content = String::new();
     content.push_str("# Architect Role\n\n");
     content.push_str(
@@ -716,8 +726,25 @@ fn write_architect_role(workspace_dir: &Path, config: &WorkspaceConfig) -> Resul
     content.push_str("### Task Format\n\n");
     content.push_str("```yaml\n<lane-name>:\n  backlog:\n    - id: my-task-id\n      title: Short title for the task\n      description: |\n        Detailed description of what needs to be done.\n      priority: high\n```\n\n");
 
+    content.push_str("### Adding Tasks From the Command Line\n\n");
+    content.push_str("Prefer `hive task` over hand-editing; it rewrites the file atomically:\n\n");
+    content.push_str("```bash\nhive task add <lane-name> my-task-id --title \"Short title\" --priority high --acceptance \"First criterion\"\nhive task list\nhive task show my-task-id\nhive task lint\n```\n\n");
+
+    content.push_str("### Worker Questions\n\n");
+    content.push_str("When a worker asks a question it is typed into your terminal with the task id. Reply with `hive task answer <task-id> \"...\"`; the answer is typed into that worker's terminal.\n\n");
+
+    content.push_str("### Dependencies\n\n");
+    content.push_str("Use `depends_on` (or `--depends-on <task-id>`) to hold a task back until tasks in any lane are done. Workers are only nudged for tasks whose dependencies are all done.\n\n");
+
+    content.push_str("### Verification\n\n");
+    content.push_str("Give a task `verify:` shell commands (or `--verify \"cargo test -p api\"`) to check its acceptance criteria. Hive runs them in the worker's directory when the task is done; if one fails, the task goes back to in_progress with the output in its `verification` field and the worker is told.\n\n");
+
+    content.push_str("### Global Backlog\n\n");
+    content.push_str("If you don't know which lane should take a task, add it under the top-level `global_backlog:` list instead. Hive moves it to a lane's backlog and nudges that worker: the lane named by `lane:` or a label, otherwise the least busy lane of its `project:` (or of any project).\n\n");
+    content.push_str("```yaml\nglobal_backlog:\n  - id: my-task-id\n    title: Short title\n    project: backend\n```\n\n");
+
     content.push_str("### YAML Validation (CRITICAL)\n\n");
-    content.push_str("When editing tasks.yaml, you MUST ensure valid YAML:\n");
+    content.push_str("If you edit tasks.yaml by hand, you MUST ensure valid YAML:\n");
     content.push_str(
         "- Empty lists MUST use `[]`, never leave blank (e.g., `backlog: []` not `backlog:`)\n",
     );
@@ -744,145 +771,190 @@ fn write_architect_role(workspace_dir: &Path, config: &WorkspaceConfig) -> Resul
 }
 
 fn write_lane_roles(workspace_dir: &Path, config: &WorkspaceConfig) -> Result<()> {
-    let lanes_dir = workspace_dir.join("lanes");
-    std::fs::create_dir_all(&lanes_dir)?;
+    let workers = crate::workspace::expand_workers(config, workspace_dir);
 
     for project in &config.projects {
-        let project_slug = slug_from_path(&project.path);
-        let project_name = project
-            .path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("project");
-
         for lane in &project.lanes {
-            let lane_dir = lanes_dir.join(lane);
-            std::fs::create_dir_all(&lane_dir)?;
-
-            // Branch naming
-            let local_prefix = format!("{}-{}/{}", project_slug, lane, lane);
-            let remote_prefix = lane.clone();
-
-            let mut content = String::new();
-            content.push_str(&format!("# Worker Role: Lane {}\n\n", lane));
-            content.push_str(&format!(
-                "You are a worker assigned to the **{}** lane.\n\n",
-                lane
-            ));
-
-            content.push_str("## Your Project\n\n");
-            content.push_str(&format!(
-                "- {} ({})\n\n",
-                project_name,
-                project.path.display()
-            ));
-
-            content.push_str("## Branch Naming Convention\n\n");
-            content.push_str(&format!(
-                "- Create local branches with prefix: `{}/`\n",
-                local_prefix
-            ));
-            content.push_str(&format!("- Example: `{}/my-feature`\n", local_prefix));
-            content.push_str(&format!(
-                "- Push command: `git push origin {}/my-feature:{}/my-feature`\n\n",
-                local_prefix, remote_prefix
-            ));
-
-            content.push_str("## Task Management\n\n");
-            content.push_str(&format!(
-                "Tasks file: {}/tasks.yaml\n",
-                workspace_dir.display()
-            ));
-            content.push_str(&format!("Your lane: `{}`\n\n", lane));
-
-            content.push_str("## Workflow\n\n");
-            content.push_str("1. Check your lane's backlog for tasks\n");
-            content.push_str("2. Claim ONE task by moving it to `in_progress`\n");
-            content.push_str("3. Create a branch following the naming convention above\n");
-            content.push_str("4. Complete the task\n");
-            if config.workflow.auto_create_pr {
-                content.push_str("5. Create a PR with your changes\n");
-            } else {
-                content.push_str("5. Only create a PR if the task or architect requests it\n");
-            }
-            content.push_str("6. Move task to `done`, then claim the next task\n\n");
+            // Worker id and lane name as used in the tasks file
+            let (worker_id, full_lane) = workers
+                .iter()
+                .find(|w| {
+                    w.project_path == project.path
+                        && (w.lane == *lane || w.lane.ends_with(&format!("/{}", lane)))
+                })
+                .map(|w| (w.id.clone(), w.lane.clone()))
+                .unwrap_or_else(|| ("<worker-id>".to_string(), lane.clone()));
+
+            write_lane_role(workspace_dir, config, project, lane, &worker_id, &full_lane)?;
+        }
+    }
 
-            // Uncommitted changes handling
-            match config.workflow.uncommitted_changes.as_str() {
-                "commit" => {
-                    content.push_str("## Before Starting New Work\n\n");
-                    content.push_str("If you have uncommitted changes from a previous task, commit them first.\n\n");
-                }
-                "error" => {
-                    content.push_str("## Before Starting New Work\n\n");
-                    content.push_str("If you have uncommitted changes from a previous task, STOP and ask the architect for guidance.\n\n");
-                }
-                _ => {
-                    content.push_str("## Before Starting New Work\n\n");
-                    content.push_str("If you have uncommitted changes from a previous task, stash them (`git stash`) before starting new work.\n\n");
-                }
-            }
+    Ok(())
+}
 
-            if config.workflow.auto_create_pr {
-                content.push_str("## Creating a Pull Request (REQUIRED)\n\n");
-                content.push_str("After completing a task, you MUST follow these steps:\n");
-                content.push_str(&format!(
-                    "1. Create a branch: `git checkout -b {}/task-name`\n",
-                    local_prefix
-                ));
-                content.push_str("2. Stage changes: `git add -A`\n");
-                content.push_str("3. Commit: `git commit -m \"description of changes\"`\n");
-                content.push_str(&format!(
-                    "4. Push: `git push origin {}/task-name:{}/task-name`\n",
-                    local_prefix, remote_prefix
-                ));
-                content.push_str("5. Create PR: `gh pr create --fill`\n");
-                content.push_str("6. **Verify the PR URL is displayed before stopping**\n\n");
-            } else {
-                content.push_str("## Creating a Pull Request (When Requested)\n\n");
-                content.push_str("If the task or architect requests a PR, follow these steps:\n");
-                content.push_str(&format!(
-                    "1. Create a branch: `git checkout -b {}/task-name`\n",
-                    local_prefix
-                ));
-                content.push_str("2. Stage changes: `git add -A`\n");
-                content.push_str("3. Commit: `git commit -m \"description of changes\"`\n");
-                content.push_str(&format!(
-                    "4. Push: `git push origin {}/task-name:{}/task-name`\n",
-                    local_prefix, remote_prefix
-                ));
-                content.push_str("5. Create PR: `gh pr create --fill`\n\n");
-                content.push_str("## Completing a Task Without PR\n\n");
-                content.push_str("If no PR is requested, simply:\n");
-                content.push_str("1. Commit your changes to the current branch\n");
-                content.push_str("2. Move the task to `done` in tasks.yaml\n\n");
-            }
+/// Write `lanes/<lane>/WORKER.md` for one of `project`'s lanes, worked by
+/// `worker_id` as `full_lane` in the tasks file
+pub fn write_lane_role(
+    workspace_dir: &Path,
+    config: &WorkspaceConfig,
+    project: &WorkspaceProject,
+    lane: &str,
+    worker_id: &str,
+    full_lane: &str,
+) -> Result<()> {
+    let project_slug = slug_from_path(&project.path);
+    let project_name = project
+        .path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("project");
+
+    let lane_dir = workspace_dir.join("lanes").join(lane);
+    std::fs::create_dir_all(&lane_dir)?;
+
+    // Branch naming
+    let local_prefix = format!("{}-{}/{}", project_slug, lane, lane);
+    let remote_prefix = lane;
 
-            content.push_str("## When Backlog is Empty\n\n");
-            content.push_str("If your lane's backlog is empty, **STOP IMMEDIATELY**.\n");
-            content.push_str(&format!(
-                "- Report \"No tasks in backlog for lane {}\"\n",
-                lane
-            ));
-            content.push_str("- Do NOT look for other work\n");
-            content.push_str("- Do NOT explore the codebase\n");
-            content.push_str("- Simply wait for the architect to add tasks\n\n");
-
-            content.push_str("## YAML Validation (CRITICAL)\n\n");
-            content.push_str("When editing tasks.yaml, you MUST ensure valid YAML:\n");
-            content.push_str("- Empty lists MUST use `[]`, never leave blank (e.g., `backlog: []` not `backlog:`)\n");
-            content.push_str(&format!(
-                "- After editing, validate with: `yq eval '.' {}/tasks.yaml > /dev/null && echo 'Valid' || echo 'Invalid'`\n",
-                workspace_dir.display()
-            ));
-            content.push_str("- If validation fails, fix the YAML before proceeding\n");
-
-            let role_path = lane_dir.join("WORKER.md");
-            std::fs::write(&role_path, content)
-                .with_context(|| format!("Failed writing {}", role_path.display()))?;
+    let mut content = String::new();
+    content.push_str(&format!("# Worker Role: Lane {}\n\n", lane));
+    content.push_str(&format!(
+        "You are a worker assigned to the **{}** lane.\n\n",
+        lane
+    ));
+
+    content.push_str("## Your Project\n\n");
+    content.push_str(&format!(
+        "- {} ({})\n\n",
+        project_name,
+        project.path.display()
+    ));
+
+    content.push_str("## Branch Naming Convention\n\n");
+    content.push_str(&format!(
+        "- Create local branches with prefix: `{}/`\n",
+        local_prefix
+    ));
+    content.push_str(&format!("- Example: `{}/my-feature`\n", local_prefix));
+    content.push_str(&format!(
+        "- Push command: `git push origin {}/my-feature:{}/my-feature`\n\n",
+        local_prefix, remote_prefix
+    ));
+
+    content.push_str("## Task Management\n\n");
+    content.push_str(&format!(
+        "Tasks file: {}/tasks.yaml\n",
+        workspace_dir.display()
+    ));
+    content.push_str(&format!("Your lane: `{}`\n\n", full_lane));
+    content.push_str("Use `hive task` instead of editing the tasks file by hand:\n");
+    content.push_str(&format!(
+        "- List your tasks: `hive task list --lane {}`\n",
+        full_lane
+    ));
+    content.push_str(&format!(
+        "- Claim a task: `hive task claim <task-id> --by {}`\n",
+        worker_id
+    ));
+    content.push_str("- Only claim tasks with no `assignee` or assigned to you\n");
+    content.push_str(
+        "- Complete a task: `hive task complete <task-id> --summary \"...\" --file <path>`\n",
+    );
+    content.push_str("- If the task has `verify` commands, run them before completing; hive runs them again afterwards and sends the task back to you if one fails\n");
+    content.push_str("- Unsure what a task needs? Ask the architect: `hive task ask <task-id> \"question\"` and wait; the answer is typed into your terminal\n\n");
+
+    content.push_str("## Workflow\n\n");
+    content.push_str("1. Check your lane's backlog for tasks\n");
+    content.push_str("2. Claim ONE task with `hive task claim`\n");
+    content.push_str("3. Create a branch following the naming convention above\n");
+    content.push_str("4. Complete the task\n");
+    if config.workflow.auto_create_pr {
+        content.push_str("5. Create a PR with your changes\n");
+    } else {
+        content.push_str("5. Only create a PR if the task or architect requests it\n");
+    }
+    content
+        .push_str("6. Complete the task with `hive task complete`, then claim the next task\n\n");
+
+    // Uncommitted changes handling
+    match config.workflow.uncommitted_changes.as_str() {
+        "commit" => {
+            content.push_str("## Before Starting New Work\n\n");
+            content.push_str(
+                "If you have uncommitted changes from a previous task, commit them first.\n\n",
+            );
+        }
+        "error" => {
+            content.push_str("## Before Starting New Work\n\n");
+            content.push_str("If you have uncommitted changes from a previous task, STOP and ask the architect for guidance.\n\n");
         }
+        _ => {
+            content.push_str("## Before Starting New Work\n\n");
+            content.push_str("If you have uncommitted changes from a previous task, stash them (`git stash`) before starting new work.\n\n");
+        }
+    }
+
+    if config.workflow.auto_create_pr {
+        content.push_str("## Creating a Pull Request (REQUIRED)\n\n");
+        content.push_str("After completing a task, you MUST follow these steps:\n");
+        content.push_str(&format!(
+            "1. Create a branch: `git checkout -b {}/task-name`\n",
+            local_prefix
+        ));
+        content.push_str("2. Stage changes: `git add -A`\n");
+        content.push_str("3. Commit: `git commit -m \"description of changes\"`\n");
+        content.push_str(&format!(
+            "4. Push: `git push origin {}/task-name:{}/task-name`\n",
+            local_prefix, remote_prefix
+        ));
+        content.push_str("5. Create PR: `gh pr create --fill`\n");
+        content.push_str("6. **Verify the PR URL is displayed before stopping**\n\n");
+    } else {
+        content.push_str("## Creating a Pull Request (When Requested)\n\n");
+        content.push_str("If the task or architect requests a PR, follow these steps:\n");
+        content.push_str(&format!(
+            "1. Create a branch: `git checkout -b {}/task-name`\n",
+            local_prefix
+        ));
+        content.push_str("2. Stage changes: `git add -A`\n");
+        content.push_str("3. Commit: `git commit -m \"description of changes\"`\n");
+        content.push_str(&format!(
+            "4. Push: `git push origin {}/task-name:{}/task-name`\n",
+            local_prefix, remote_prefix
+        ));
+        content.push_str("5. Create PR: `gh pr create --fill`\n\n");
+        content.push_str("## Completing a Task Without PR\n\n");
+        content.push_str("If no PR is requested, simply:\n");
+        content.push_str("1. Commit your changes to the current branch\n");
+        content.push_str(
+            "2. Complete the task with `hive task complete <task-id> --summary \"...\"`\n\n",
+        );
     }
 
+    content.push_str("## When Backlog is Empty\n\n");
+    content.push_str("If your lane's backlog is empty, **STOP IMMEDIATELY**.\n");
+    content.push_str(&format!(
+        "- Report \"No tasks in backlog for lane {}\"\n",
+        lane
+    ));
+    content.push_str("- Do NOT look for other work\n");
+    content.push_str("- Do NOT explore the codebase\n");
+    content.push_str("- Simply wait for the architect to add tasks\n\n");
+
+    content.push_str("## YAML Validation (CRITICAL)\n\n");
+    content.push_str("If you ever edit tasks.yaml by hand, you MUST ensure valid YAML:\n");
+    content.push_str(
+        "- Empty lists MUST use `[]`, never leave blank (e.g., `backlog: []` not `backlog:`)\n",
+    );
+    content.push_str(&format!(
+        "- After editing, validate with: `yq eval '.' {}/tasks.yaml > /dev/null && echo 'Valid' || echo 'Invalid'`\n",
+        workspace_dir.display()
+    ));
+    content.push_str("- If validation fails, fix the YAML before proceeding\n");
+
+    let role_path = lane_dir.join("WORKER.md");
+    std::fs::write(&role_path, content)
+        .with_context(|| format!("Failed writing {}", role_path.display()))?;
     Ok(())
 }
 
@@ -2,8 +2,8 @@ use std::path::Path;
 
 use anyhow::Result;
 
-use crate::config::{self, TaskSource};
-use crate::tasks::{counts_for_lane, load_tasks};
+use crate::config;
+use crate::tasks::{counts_for_lane, open_backend};
 use crate::workspace::resolve::find_workspace_for_path;
 
 pub fn run(start_dir: &Path) -> Result<()> {
@@ -29,25 +29,27 @@ pub fn run(start_dir: &Path) -> Result<()> {
     println!("Task Source: {:?}", config.tasks.source);
     println!("Status: {}", status);
 
-    if let TaskSource::Yaml = config.tasks.source {
-        let tasks_path = config::tasks_file_path(&config_path, &config);
-        let tasks = load_tasks(&tasks_path).unwrap_or_default();
+    let backend = open_backend(&config_path, &config)?;
+    let tasks = match backend.load() {
+        Ok(tasks) => tasks,
+        Err(e) => {
+            eprintln!("Failed to load tasks from {}: {:#}", backend.describe(), e);
+            Default::default()
+        }
+    };
 
-        println!("\nWORKER              LANE            BACKLOG     IN_PROGRESS");
-        println!("------              ----            -------     -----------");
+    println!("\nWORKER              LANE            BACKLOG     BLOCKED     IN_PROGRESS");
+    println!("------              ----            -------     -------     -----------");
 
-        for window in &config.windows {
-            for worker in &window.workers {
-                let lane = worker.lane.clone().unwrap_or_else(|| worker.id.clone());
-                let counts = counts_for_lane(&tasks, &lane);
-                println!(
-                    "{:<18} {:<14} {:<10} {:<11}",
-                    worker.id, lane, counts.backlog, counts.in_progress
-                );
-            }
+    for window in &config.windows {
+        for worker in &window.workers {
+            let lane = worker.lane.clone().unwrap_or_else(|| worker.id.clone());
+            let counts = counts_for_lane(&tasks, &lane);
+            println!(
+                "{:<18} {:<14} {:<10} {:<10} {:<11}",
+                worker.id, lane, counts.backlog, counts.blocked, counts.in_progress
+            );
         }
-    } else {
-        println!("\nGitHub task source status not implemented yet.");
     }
 
     Ok(())
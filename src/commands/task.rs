@@ -0,0 +1,567 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use clap::Subcommand;
+
+use crate::config::{self, TaskSource};
+use crate::tasks::{
+    self, parse_vars, Completion, Priority, Severity, Task, TaskBackend, TaskState, TaskTemplate,
+};
+use crate::workspace::config::{expand_workers, slug_from_path};
+use crate::workspace::resolve::{find_workspace_for_path, WorkspaceMeta};
+
+#[derive(Subcommand)]
+pub enum TaskCommand {
+    /// Add a task to a lane's backlog
+    Add {
+        /// Lane name ("project/lane" for multi-lane projects)
+        lane: String,
+        /// Task id (kebab-case)
+        id: String,
+        #[arg(long)]
+        title: Option<String>,
+        #[arg(long)]
+        description: Option<String>,
+        #[arg(long)]
+        priority: Option<String>,
+        /// Acceptance criterion (repeatable)
+        #[arg(long = "acceptance", value_name = "criterion")]
+        acceptance: Vec<String>,
+        /// Task id that must be done first, in any lane (repeatable)
+        #[arg(long = "depends-on", value_name = "task-id")]
+        depends_on: Vec<String>,
+        /// Shell command that must pass once the task is done (repeatable)
+        #[arg(long = "verify", value_name = "command")]
+        verify: Vec<String>,
+        /// Label for filtering (repeatable)
+        #[arg(long = "label", value_name = "label")]
+        labels: Vec<String>,
+        /// Worker id that must claim the task, even in a shared lane
+        #[arg(long)]
+        assignee: Option<String>,
+        /// Start from a template in the workspace's task_templates; other flags override it
+        #[arg(long)]
+        template: Option<String>,
+        /// Template placeholder value (repeatable)
+        #[arg(long = "var", value_name = "name=value", requires = "template")]
+        vars: Vec<String>,
+        /// Start the lane if it's neither configured nor in the tasks file
+        #[arg(long)]
+        create_lane: bool,
+    },
+    /// Claim a backlog task (moves it to in_progress)
+    Claim {
+        id: String,
+        /// Worker claiming the task (e.g. worker-2)
+        #[arg(long)]
+        by: String,
+        #[arg(long)]
+        lane: Option<String>,
+    },
+    /// Complete an in-progress task (moves it to done)
+    Complete {
+        id: String,
+        #[arg(long)]
+        summary: Option<String>,
+        #[arg(long)]
+        pr_url: Option<String>,
+        #[arg(long)]
+        branch: Option<String>,
+        /// Changed file (repeatable)
+        #[arg(long = "file", value_name = "path")]
+        files: Vec<String>,
+        #[arg(long)]
+        lane: Option<String>,
+    },
+    /// Move an in-progress task back to backlog, e.g. when its worker died
+    Requeue {
+        id: String,
+        /// Why it's being requeued, kept in the task's `requeued_from` note
+        #[arg(long, default_value = "requeued by hand")]
+        reason: String,
+        #[arg(long)]
+        lane: Option<String>,
+    },
+    /// Move a task to another state and/or lane
+    Move {
+        id: String,
+        /// Target state: backlog, in_progress or done
+        state: String,
+        #[arg(long)]
+        lane: Option<String>,
+        /// Move the task into another lane
+        #[arg(long)]
+        to_lane: Option<String>,
+        /// Start `--to-lane` if it's neither configured nor in the tasks file
+        #[arg(long, requires = "to_lane")]
+        create_lane: bool,
+    },
+    /// Ask the architect a question about an in-progress task
+    Ask {
+        id: String,
+        question: String,
+        #[arg(long)]
+        lane: Option<String>,
+    },
+    /// Answer the question a worker asked on a task
+    Answer {
+        id: String,
+        answer: String,
+        #[arg(long)]
+        lane: Option<String>,
+    },
+    /// Show a single task
+    Show {
+        id: String,
+        #[arg(long)]
+        lane: Option<String>,
+    },
+    /// List tasks by lane
+    List {
+        #[arg(long)]
+        lane: Option<String>,
+        /// Only show tasks in this state
+        #[arg(long)]
+        state: Option<String>,
+    },
+    /// Check tasks.yaml for mistakes, with line and column for each problem
+    Lint,
+    /// Move done tasks to tasks.archive/<yyyy-mm>.yaml
+    Archive {
+        /// Only archive tasks completed at least this long ago (e.g. 7d, 12h)
+        #[arg(long)]
+        older_than: Option<String>,
+    },
+    /// Add backlog tasks for TODO/FIXME comments in a workspace project's tracked files
+    Import {
+        /// Workspace project to scan (its directory name)
+        #[arg(long = "from-code", value_name = "project")]
+        from_code: String,
+        /// Lane to add the tasks to (default: the project's first lane)
+        #[arg(long)]
+        lane: Option<String>,
+        /// Markers to look for, separated by |
+        #[arg(long, default_value = "TODO|FIXME")]
+        pattern: String,
+    },
+    /// Show the history of task state changes recorded by the server
+    Log {
+        #[arg(long)]
+        lane: Option<String>,
+        /// Only changes after this time: a duration ago (30m, 12h, 2d, 1w) or a date/time
+        #[arg(long)]
+        since: Option<String>,
+    },
+}
+
+pub fn run(start_dir: &Path, command: TaskCommand) -> Result<()> {
+    match command {
+        TaskCommand::Add {
+            lane,
+            id,
+            title,
+            description,
+            priority,
+            acceptance,
+            depends_on,
+            verify,
+            labels,
+            assignee,
+            template,
+            vars,
+            create_lane,
+        } => {
+            let base = match template {
+                Some(name) => {
+                    task_template_for(start_dir, &name)?.expand(&id, &parse_vars(&vars)?)?
+                }
+                None => Task {
+                    id: id.clone(),
+                    ..Default::default()
+                },
+            };
+            let priority = priority
+                .as_deref()
+                .map(|value| {
+                    Priority::parse(value).ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "Unknown priority '{}'. Use critical, high, medium, low or a number.",
+                            value
+                        )
+                    })
+                })
+                .transpose()?;
+            let task = Task {
+                title: title.or(base.title),
+                description: description.or(base.description),
+                priority: priority.or(base.priority),
+                acceptance: (!acceptance.is_empty())
+                    .then_some(acceptance)
+                    .or(base.acceptance),
+                depends_on: (!depends_on.is_empty())
+                    .then_some(depends_on)
+                    .or(base.depends_on),
+                verify: (!verify.is_empty()).then_some(verify).or(base.verify),
+                labels: (!labels.is_empty()).then_some(labels).or(base.labels),
+                assignee,
+                ..base
+            };
+            let source = task_source_for(start_dir)?;
+            if !create_lane {
+                check_lane(start_dir, source.as_ref(), &lane)?;
+            }
+            source.add(&lane, task)?;
+            println!("Added {} to {} backlog", id, lane);
+        }
+        TaskCommand::Claim { id, by, lane } => {
+            let location = task_source_for(start_dir)?.claim(&id, lane.as_deref(), &by)?;
+            println!("{} claimed {} ({})", by, id, location.lane);
+        }
+        TaskCommand::Complete {
+            id,
+            summary,
+            pr_url,
+            branch,
+            files,
+            lane,
+        } => {
+            let completion = Completion {
+                summary,
+                pr_url,
+                branch,
+                files_changed: files,
+            };
+            let location =
+                task_source_for(start_dir)?.complete(&id, lane.as_deref(), completion)?;
+            println!("Completed {} ({})", id, location.lane);
+        }
+        TaskCommand::Requeue { id, reason, lane } => {
+            let location = task_source_for(start_dir)?.requeue(&id, lane.as_deref(), &reason)?;
+            println!("Requeued {} to {} backlog", id, location.lane);
+        }
+        TaskCommand::Move {
+            id,
+            state,
+            lane,
+            to_lane,
+            create_lane,
+        } => {
+            let state = parse_state(&state)?;
+            let source = task_source_for(start_dir)?;
+            if let (Some(to_lane), false) = (&to_lane, create_lane) {
+                check_lane(start_dir, source.as_ref(), to_lane)?;
+            }
+            let location = source.move_to(&id, lane.as_deref(), state, to_lane.as_deref())?;
+            println!("Moved {} to {} ({})", id, location.state, location.lane);
+        }
+        TaskCommand::Ask { id, question, lane } => {
+            let location = task_source_for(start_dir)?.ask(&id, lane.as_deref(), &question)?;
+            println!(
+                "Asked the architect about {} ({}); the answer will be typed into your terminal",
+                id, location.lane
+            );
+        }
+        TaskCommand::Answer { id, answer, lane } => {
+            let location = task_source_for(start_dir)?.answer(&id, lane.as_deref(), &answer)?;
+            println!("Answered {} ({})", id, location.lane);
+        }
+        TaskCommand::Show { id, lane } => {
+            let file = task_source_for(start_dir)?.load()?;
+            let location = file.find_task(&id, lane.as_deref())?;
+            println!("lane: {}", location.lane);
+            println!("state: {}", location.state);
+            if let Some(task) = file.task(&location) {
+                print!("{}", serde_yaml::to_string(task)?);
+            }
+        }
+        TaskCommand::List { lane, state } => {
+            let state = state.as_deref().map(parse_state).transpose()?;
+            let file = task_source_for(start_dir)?.load()?;
+            let done_ids = file.done_ids();
+            for (lane_name, lane_tasks) in file.lanes() {
+                if lane.as_deref().is_some_and(|l| l != lane_name) {
+                    continue;
+                }
+                println!("{}", lane_name);
+                for task_state in TaskState::ALL {
+                    if state.is_some_and(|s| s != task_state) {
+                        continue;
+                    }
+                    let listed: Vec<&Task> = match task_state {
+                        TaskState::Backlog => lane_tasks.backlog_by_priority(),
+                        _ => lane_tasks.tasks(task_state).iter().collect(),
+                    };
+                    for task in listed {
+                        let title = task.title.as_deref().unwrap_or("");
+                        let claimed = match (&task.claimed_by, &task.assignee) {
+                            (Some(by), _) => format!(" [{}]", by),
+                            (None, Some(assignee)) => format!(" [for {}]", assignee),
+                            (None, None) => String::new(),
+                        };
+                        let blockers = task.blockers(&done_ids);
+                        let blocked = if task_state == TaskState::Backlog && !blockers.is_empty() {
+                            format!(" (blocked by {})", blockers.join(", "))
+                        } else {
+                            String::new()
+                        };
+                        println!(
+                            "  {:<12} {:<28} {}{}{}",
+                            task_state.as_str(),
+                            task.id,
+                            title,
+                            claimed,
+                            blocked
+                        );
+                    }
+                }
+            }
+        }
+        TaskCommand::Lint => {
+            let source = task_source_for(start_dir)?;
+            let diagnostics = source.lint(&configured_lanes(start_dir)?);
+            let path = source.describe();
+            if diagnostics.is_empty() {
+                println!("No problems found in {}", path);
+                return Ok(());
+            }
+            for diagnostic in &diagnostics {
+                println!("{}:{}", path, diagnostic);
+            }
+            let errors = diagnostics
+                .iter()
+                .filter(|d| d.severity == Severity::Error)
+                .count();
+            let warnings = diagnostics.len() - errors;
+            println!();
+            println!("{} error(s), {} warning(s)", errors, warnings);
+            if errors > 0 {
+                anyhow::bail!("{} has errors", path);
+            }
+        }
+        TaskCommand::Archive { older_than } => {
+            let older_than = older_than
+                .as_deref()
+                .map(tasks::parse_duration)
+                .transpose()?;
+            let source = task_source_for(start_dir)?;
+            let count = source.archive(older_than)?;
+            println!("Archived {} done task(s) from {}", count, source.describe());
+        }
+        TaskCommand::Import {
+            from_code,
+            lane,
+            pattern,
+        } => {
+            let Ok(Some(workspace)) = find_workspace_for_path(start_dir) else {
+                anyhow::bail!("hive task import needs a workspace; no workspace found here");
+            };
+            let (project_dir, lane) = import_target(&workspace, &from_code, lane.as_deref())?;
+            let markers: Vec<String> = pattern
+                .split('|')
+                .map(str::trim)
+                .filter(|marker| !marker.is_empty())
+                .map(String::from)
+                .collect();
+            if markers.is_empty() {
+                anyhow::bail!("--pattern needs at least one marker, e.g. TODO|FIXME");
+            }
+
+            let todos = tasks::scan_todos(&project_dir, &markers)?;
+            let (added, skipped) = task_source_for(start_dir)?.import_todos(&lane, &todos)?;
+            println!(
+                "Imported {} task(s) from {} into {} backlog ({} already imported)",
+                added, from_code, lane, skipped
+            );
+        }
+        TaskCommand::Log { lane, since } => {
+            let since = since
+                .as_deref()
+                .map(|value| tasks::history::parse_since(value, chrono::Utc::now()))
+                .transpose()?;
+            let path = history_file_for(start_dir)?;
+            let entries: Vec<_> = tasks::read_history(&path)?
+                .into_iter()
+                .filter(|entry| lane.as_deref().is_none_or(|l| l == entry.lane))
+                .filter(|entry| since.is_none_or(|since| entry.at().is_some_and(|at| at >= since)))
+                .collect();
+            if entries.is_empty() {
+                println!("No task history in {}", path.display());
+                return Ok(());
+            }
+            for entry in &entries {
+                let from = entry.from.map_or("(new)", TaskState::as_str);
+                let to = entry.to.map_or("(removed)", TaskState::as_str);
+                let lane = match &entry.from_lane {
+                    Some(from_lane) => format!("{} -> {}", from_lane, entry.lane),
+                    None => entry.lane.clone(),
+                };
+                let claimed = entry
+                    .claimed_by
+                    .as_deref()
+                    .map(|by| format!(" [{}]", by))
+                    .unwrap_or_default();
+                println!(
+                    "{}  {:<20} {:<28} {:>11} -> {}{}",
+                    entry.at, lane, entry.id, from, to, claimed
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// A workspace project's directory and the lane its imported tasks go to
+fn import_target(
+    workspace: &WorkspaceMeta,
+    project: &str,
+    lane: Option<&str>,
+) -> Result<(PathBuf, String)> {
+    let Some(found) = workspace
+        .config
+        .projects
+        .iter()
+        .find(|p| slug_from_path(&p.path) == project || p.path.ends_with(project))
+    else {
+        let names: Vec<String> = workspace
+            .config
+            .projects
+            .iter()
+            .map(|p| slug_from_path(&p.path))
+            .collect();
+        anyhow::bail!(
+            "No project '{}' in workspace {} (have: {})",
+            project,
+            workspace.name,
+            names.join(", ")
+        );
+    };
+    let lanes: Vec<String> = expand_workers(&workspace.config, &workspace.dir)
+        .into_iter()
+        .filter(|worker| worker.project_path == found.path)
+        .map(|worker| worker.lane)
+        .collect();
+    let lane = match lane {
+        // Accept the short lane name for multi-lane projects
+        Some(lane) => lanes
+            .iter()
+            .find(|l| *l == lane || l.rsplit('/').next() == Some(lane))
+            .cloned()
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Project '{}' has no lane '{}' (have: {})",
+                    project,
+                    lane,
+                    lanes.join(", ")
+                )
+            })?,
+        None => lanes
+            .first()
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Project '{}' has no lanes", project))?,
+    };
+    Ok((found.path.clone(), lane))
+}
+
+/// The history log written by the server for a workspace or legacy project
+fn history_file_for(start_dir: &Path) -> Result<PathBuf> {
+    if let Ok(Some(workspace)) = find_workspace_for_path(start_dir) {
+        return Ok(workspace.dir.join(tasks::HISTORY_FILE));
+    }
+
+    let config_path = config::find_config(start_dir)?;
+    Ok(config::project_dir(&config_path)
+        .join(".hive")
+        .join(tasks::HISTORY_FILE))
+}
+
+/// Refuse a lane that's neither configured nor in the tasks, unless the
+/// command was given `--create-lane`
+fn check_lane(start_dir: &Path, source: &dyn TaskBackend, lane: &str) -> Result<()> {
+    tasks::check_lane_known(&source.load()?, lane, &configured_lanes(start_dir)?)
+        .map_err(|e| anyhow::anyhow!("{}; pass --create-lane to start it", e))
+}
+
+/// The lanes configured for a workspace or legacy project
+fn configured_lanes(start_dir: &Path) -> Result<Vec<String>> {
+    if let Ok(Some(workspace)) = find_workspace_for_path(start_dir) {
+        return Ok(expand_workers(&workspace.config, &workspace.dir)
+            .into_iter()
+            .map(|worker| worker.lane)
+            .collect());
+    }
+
+    let config = config::load_config(&config::find_config(start_dir)?)?;
+    Ok(config
+        .windows
+        .iter()
+        .flat_map(|window| &window.workers)
+        .map(|worker| worker.lane.clone().unwrap_or_else(|| worker.id.clone()))
+        .collect())
+}
+
+/// A template from the workspace's `task_templates`
+fn task_template_for(start_dir: &Path, name: &str) -> Result<TaskTemplate> {
+    let Ok(Some(workspace)) = find_workspace_for_path(start_dir) else {
+        anyhow::bail!("Task templates are defined in a workspace config; no workspace found here");
+    };
+    let mut templates = workspace.config.task_templates;
+    let names = templates.keys().cloned().collect::<Vec<_>>().join(", ");
+    templates.shift_remove(name).ok_or_else(|| {
+        anyhow::anyhow!(
+            "No task template named '{}' (have: {})",
+            name,
+            if names.is_empty() { "none" } else { &names }
+        )
+    })
+}
+
+/// Open the task source for a workspace or legacy .hive.yaml project
+pub fn task_source_for(start_dir: &Path) -> Result<Box<dyn TaskBackend>> {
+    if let Ok(Some(workspace)) = find_workspace_for_path(start_dir) {
+        return tasks::open_workspace_backend(&workspace.dir, &workspace.config);
+    }
+
+    let config_path = config::find_config(start_dir)?;
+    let config = config::load_config(&config_path)?;
+    tasks::open_backend(&config_path, &config)
+}
+
+/// Locate the tasks.yaml for a workspace or legacy .hive.yaml project, for
+/// what lives beside it (the archive)
+pub fn tasks_file_for(start_dir: &Path) -> Result<PathBuf> {
+    let (source, path) = match find_workspace_for_path(start_dir) {
+        Ok(Some(workspace)) => {
+            let source = workspace
+                .config
+                .tasks
+                .as_ref()
+                .map_or(TaskSource::Yaml, |tasks| tasks.source.clone());
+            (source, workspace.config.tasks_file(&workspace.dir))
+        }
+        _ => {
+            let config_path = config::find_config(start_dir)?;
+            let config = config::load_config(&config_path)?;
+            let path = config::tasks_file_path(&config_path, &config);
+            (config.tasks.source, path)
+        }
+    };
+    match source {
+        TaskSource::Yaml => Ok(path),
+        TaskSource::Github | TaskSource::Markdown => {
+            anyhow::bail!(
+                "This command only works with the yaml task source; edit the {:?} source directly",
+                source
+            )
+        }
+    }
+}
+
+fn parse_state(value: &str) -> Result<TaskState> {
+    TaskState::parse(value).ok_or_else(|| {
+        anyhow::anyhow!(
+            "Unknown task state '{}'. Use backlog, in_progress or done.",
+            value
+        )
+    })
+}
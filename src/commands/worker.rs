@@ -0,0 +1,86 @@
+use std::io::Write;
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use clap::Subcommand;
+
+use crate::ipc::ClientMessage;
+use crate::workspace::config::{expand_workers, slug_from_path};
+use crate::workspace::resolve::find_workspace_for_path;
+
+#[derive(Subcommand)]
+pub enum WorkerCommand {
+    /// Give a project another lane and start a worker on it
+    Add {
+        /// Lane name within the project
+        lane: String,
+        /// Project path or name; needed when the workspace has several
+        #[arg(long)]
+        project: Option<String>,
+    },
+    /// Stop a worker and drop its lane from the workspace
+    Remove {
+        /// Worker id (e.g. worker-3)
+        worker: String,
+        /// Also remove the worker's git worktree; its branch is kept
+        #[arg(long)]
+        remove_worktree: bool,
+    },
+}
+
+pub fn run(start_dir: &Path, command: WorkerCommand) -> Result<()> {
+    let Some(workspace) = find_workspace_for_path(start_dir)? else {
+        bail!("hive worker only works in a workspace; for a .hive.yaml hive, edit its workers and restart it");
+    };
+
+    // The same checks the server makes, since it can't report back to this
+    // command once the message is sent
+    let (message, done) = match command {
+        WorkerCommand::Add { lane, project } => {
+            let project = match (project, workspace.config.projects.as_slice()) {
+                (Some(project), _) => project,
+                (None, [only]) => slug_from_path(&only.path),
+                (None, _) => bail!("The workspace has several projects; pick one with --project"),
+            };
+            workspace.config.clone().add_lane(&project, &lane)?;
+            let done = format!(
+                "Adding a worker for {}; it starts once its worktree and setup commands are done",
+                lane
+            );
+            (ClientMessage::AddWorker { project, lane }, done)
+        }
+        WorkerCommand::Remove {
+            worker,
+            remove_worktree,
+        } => {
+            if worker == "architect" {
+                bail!("The architect can't be removed");
+            }
+            if let Some(runtime) = expand_workers(&workspace.config, &workspace.dir)
+                .into_iter()
+                .find(|runtime| runtime.id == worker)
+            {
+                workspace.config.clone().remove_lane(&runtime.lane)?;
+            }
+            let done = format!("Removing {}", worker);
+            (
+                ClientMessage::RemoveWorker {
+                    pane_id: worker,
+                    remove_worktree,
+                },
+                done,
+            )
+        }
+    };
+
+    let socket_path = workspace.dir.join("hive.sock");
+    let mut stream = UnixStream::connect(&socket_path)
+        .with_context(|| format!("Failed to connect to {}", socket_path.display()))?;
+
+    let line = serde_json::to_string(&message)?;
+    writeln!(stream, "{}", line)?;
+
+    println!("{}", done);
+    Ok(())
+}
@@ -1,5 +1,6 @@
 pub mod parser;
 pub mod validation;
+pub mod watch;
 
 use std::path::{Path, PathBuf};
 
@@ -9,6 +10,7 @@ pub use parser::{
     find_config, load_config, ArchitectConfig, Backend, BranchConfig, HiveConfig, MessagesConfig,
     TaskSource, TasksConfig, WindowConfig, WorkerConfig, WorkersConfig, WorkflowConfig,
 };
+pub use watch::spawn_config_watcher;
 
 pub fn project_dir(config_path: &Path) -> PathBuf {
     config_path
@@ -19,7 +21,11 @@ pub fn project_dir(config_path: &Path) -> PathBuf {
 
 pub fn tasks_file_path(config_path: &Path, config: &parser::HiveConfig) -> PathBuf {
     let base = project_dir(config_path);
-    let tasks_file = config.tasks.file.as_deref().unwrap_or(".hive/tasks.yaml");
+    let default_file = match config.tasks.source {
+        parser::TaskSource::Markdown => "TODO.md",
+        _ => ".hive/tasks.yaml",
+    };
+    let tasks_file = config.tasks.file.as_deref().unwrap_or(default_file);
     base.join(tasks_file)
 }
 
@@ -37,7 +37,7 @@ pub struct WorkersConfig {
     pub symlink: Vec<String>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Copy)]
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum Backend {
     Claude,
@@ -53,6 +53,8 @@ pub struct TasksConfig {
     pub github_project_id: Option<String>,
     pub github_status_field_id: Option<String>,
     pub github_lane_field_id: Option<String>,
+    /// How often to poll the GitHub project for changes (default: 60s)
+    pub github_poll_secs: Option<u64>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -60,6 +62,8 @@ pub struct TasksConfig {
 pub enum TaskSource {
     Yaml,
     Github,
+    /// Checklist items under `## lane` headings in a Markdown file
+    Markdown,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -87,6 +91,12 @@ pub struct BranchConfig {
 pub struct MessagesConfig {
     pub startup: Option<String>,
     pub nudge: Option<String>,
+    /// Typed into the architect pane when a worker asks a question
+    pub question: Option<String>,
+    /// Typed into the worker pane when the architect answers
+    pub answer: Option<String>,
+    /// Typed into the worker pane when a task's `verify` commands fail
+    pub verify_failed: Option<String>,
 }
 
 /// Workflow configuration for workers
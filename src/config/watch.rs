@@ -0,0 +1,103 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Sender};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::Result;
+use notify::{RecursiveMode, Watcher};
+
+/// Quiet time after the last write before the config is reread, so an editor
+/// saving in several steps triggers one reload
+const SETTLE: Duration = Duration::from_millis(500);
+
+fn log_line(path: &Path, line: &str) {
+    if let Ok(mut file) = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+    {
+        let _ = writeln!(file, "{}", line);
+    }
+}
+
+/// Send on `changed_tx` whenever `config_file` is written. The receiver
+/// rereads and validates it.
+pub fn spawn_config_watcher(
+    config_file: PathBuf,
+    changed_tx: Sender<()>,
+    log_path: PathBuf,
+) -> Result<()> {
+    thread::spawn(move || {
+        let (tx, rx) = mpsc::channel();
+        let mut watcher = match notify::recommended_watcher(tx) {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                log_line(
+                    &log_path,
+                    &format!("config-watcher: failed to create: {}", e),
+                );
+                return;
+            }
+        };
+
+        // Like the tasks watcher, watch the directory so a save by rename
+        // doesn't orphan the watch
+        let watch_dir = match config_file.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        let file_name = config_file.file_name().map(|n| n.to_os_string());
+
+        if let Err(e) = watcher.watch(&watch_dir, RecursiveMode::NonRecursive) {
+            log_line(
+                &log_path,
+                &format!(
+                    "config-watcher: failed to watch {}: {}",
+                    watch_dir.display(),
+                    e
+                ),
+            );
+            return;
+        }
+        log_line(
+            &log_path,
+            &format!("config-watcher: watching {}", config_file.display()),
+        );
+
+        let mut pending = false;
+        loop {
+            let timeout = if pending {
+                SETTLE
+            } else {
+                Duration::from_secs(1)
+            };
+            match rx.recv_timeout(timeout) {
+                Ok(event) => {
+                    let touches_config = match &event {
+                        Ok(event) => event
+                            .paths
+                            .iter()
+                            .any(|p| p.file_name() == file_name.as_deref()),
+                        Err(_) => true,
+                    };
+                    pending |= touches_config;
+                }
+                Err(mpsc::RecvTimeoutError::Timeout) => {
+                    if pending {
+                        pending = false;
+                        if changed_tx.send(()).is_err() {
+                            break;
+                        }
+                    }
+                }
+                Err(mpsc::RecvTimeoutError::Disconnected) => {
+                    log_line(&log_path, "config-watcher: channel disconnected");
+                    break;
+                }
+            }
+        }
+    });
+
+    Ok(())
+}
@@ -3,9 +3,9 @@ use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 
 use crate::app::state::{LayoutKind, LayoutMode};
-use crate::app::types::PaneType;
+use crate::app::types::{AgentState, PaneType};
 use crate::config::{Backend, BranchConfig};
-use crate::tasks::TaskCounts;
+use crate::tasks::{ArchivedTasks, StaleClaim, Task, TaskCounts, TaskEvent, TaskState, TasksFile};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PaneInfo {
@@ -15,6 +15,8 @@ pub struct PaneInfo {
     pub branch: Option<BranchConfig>,
     pub group: Option<String>,
     pub visible: bool,
+    #[serde(default)]
+    pub agent_state: AgentState,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,6 +26,15 @@ pub struct WindowInfo {
     pub pane_indices: Vec<usize>,
 }
 
+/// A config file edit the server can't apply while the hive is running
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ConfigChange {
+    /// What changed, e.g. "lane 'api/billing' added"
+    pub change: String,
+    /// Panes that would be started, stopped or restarted to pick it up
+    pub panes: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppState {
     pub project_name: String,
@@ -32,6 +43,24 @@ pub struct AppState {
     pub panes: Vec<PaneInfo>,
     pub windows: Vec<WindowInfo>,
     pub task_counts: HashMap<String, TaskCounts>,
+    /// Lint diagnostics for the tasks file ("line:col: severity: message")
+    #[serde(default)]
+    pub task_problems: Vec<String>,
+    /// In-progress tasks whose worker pane exited or went quiet
+    #[serde(default)]
+    pub stale_claims: Vec<StaleClaim>,
+    /// The server's last load of the task source, for the task queue overlay
+    #[serde(default)]
+    pub tasks: Option<TasksFile>,
+    /// `tasks.archived`, which isn't part of the tasks file's own serialization
+    #[serde(default)]
+    pub archived_tasks: ArchivedTasks,
+    /// Edits to the config file that need a restart of the hive
+    #[serde(default)]
+    pub config_changes: Vec<ConfigChange>,
+    /// Why the config file's latest version was rejected
+    #[serde(default)]
+    pub config_error: Option<String>,
     #[serde(default)]
     pub architect_left: bool,
     #[serde(default = "default_min_pane_width")]
@@ -57,23 +86,96 @@ pub struct PaneSize {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ClientMessage {
-    Input { pane_id: String, data: Vec<u8> },
-    Resize { panes: Vec<PaneSize> },
-    Nudge { worker: Option<String> },
-    SetVisibility { pane_id: String, visible: bool },
-    ReorderPanes { pane_ids: Vec<String> },
-    SetArchitectLeft { left: bool },
-    Layout { mode: LayoutMode },
+    Input {
+        pane_id: String,
+        data: Vec<u8>,
+    },
+    Resize {
+        panes: Vec<PaneSize>,
+    },
+    Nudge {
+        worker: Option<String>,
+    },
+    SetVisibility {
+        pane_id: String,
+        visible: bool,
+    },
+    ReorderPanes {
+        pane_ids: Vec<String>,
+    },
+    SetArchitectLeft {
+        left: bool,
+    },
+    Layout {
+        mode: LayoutMode,
+    },
+    /// Task queue edits, applied by the server to the task source
+    AddTask {
+        lane: String,
+        task: Box<Task>,
+    },
+    MoveTask {
+        id: String,
+        lane: String,
+        state: TaskState,
+        to_lane: Option<String>,
+    },
+    ReprioritizeTask {
+        id: String,
+        lane: String,
+        up: bool,
+    },
+    DeleteTask {
+        id: String,
+        lane: String,
+    },
+    /// Start a fresh agent in a pane, killing the old one if it's still running
+    RestartPane {
+        pane_id: String,
+    },
+    /// Give a workspace project another lane and start a worker on it
+    AddWorker {
+        project: String,
+        lane: String,
+    },
+    /// Stop a worker and drop its lane from the workspace
+    RemoveWorker {
+        pane_id: String,
+        remove_worktree: bool,
+    },
     Detach,
     Shutdown,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ServerMessage {
-    State { state: AppState },
-    Output { pane_id: String, data: Vec<u8> },
-    PaneExited { pane_id: String },
-    Error { message: String },
+    State {
+        state: Box<AppState>,
+    },
+    Output {
+        pane_id: String,
+        data: Vec<u8>,
+    },
+    PaneExited {
+        pane_id: String,
+    },
+    /// Task changes seen by the tasks watcher, sent after the matching `State`
+    TaskEvents {
+        events: Vec<TaskEvent>,
+    },
+    /// Sent to the client whose request failed, e.g. a task edit
+    Error {
+        message: String,
+    },
+    /// Config file settings applied live after an edit
+    ConfigReloaded {
+        applied: Vec<String>,
+    },
+    /// New agent states of the panes whose state changed, sent instead of a
+    /// full `State` since agents go busy and idle all the time
+    PaneStates {
+        states: Vec<(String, AgentState)>,
+    },
 }
 
 pub fn encode_message(message: &ServerMessage) -> String {
@@ -87,3 +189,65 @@ pub fn decode_client_message(line: &str) -> Option<ClientMessage> {
 pub fn decode_server_message(line: &str) -> Option<ServerMessage> {
     serde_json::from_str(line).ok()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_task_snapshot_round_trips_through_json() {
+        let tasks: TasksFile = serde_yaml::from_str(
+            r#"
+api:
+  backlog:
+    - id: add-users
+      priority: 1
+      labels: [http]
+      owner_note: kept
+backend:
+  fixes:
+    in_progress:
+      - id: fix-login
+        claimed_by: backend-1
+"#,
+        )
+        .unwrap();
+        let mut archived = ArchivedTasks::default();
+        archived.ids.insert("old-task".to_string());
+        let state = AppState {
+            project_name: "hive".to_string(),
+            backend: Backend::Claude,
+            layout_mode: LayoutMode::Default,
+            panes: Vec::new(),
+            windows: Vec::new(),
+            task_counts: HashMap::new(),
+            task_problems: Vec::new(),
+            stale_claims: Vec::new(),
+            tasks: Some(tasks),
+            archived_tasks: archived,
+            config_changes: Vec::new(),
+            config_error: None,
+            architect_left: false,
+            min_pane_width: 100,
+            min_pane_height: 16,
+        };
+
+        let json = encode_message(&ServerMessage::State {
+            state: Box::new(state),
+        });
+        let Some(ServerMessage::State { state }) = decode_server_message(&json) else {
+            panic!("state didn't decode: {}", json);
+        };
+        let tasks = state.tasks.unwrap();
+        let task = &tasks.lane("api").unwrap().backlog[0];
+        assert_eq!(task.priority_rank(), 1);
+        assert_eq!(task.labels, Some(vec!["http".to_string()]));
+        assert!(task.extra.contains_key("owner_note"));
+        let fixes = tasks.lane("backend/fixes").unwrap();
+        assert_eq!(
+            fixes.in_progress[0].claimed_by.as_deref(),
+            Some("backend-1")
+        );
+        assert!(state.archived_tasks.ids.contains("old-task"));
+    }
+}
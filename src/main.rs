@@ -29,6 +29,30 @@ enum Commands {
     Status,
     /// Send nudge message to workers
     Nudge { worker: Option<String> },
+    /// Restart an agent pane with a fresh process
+    Restart {
+        /// Worker id, or "architect"
+        worker: String,
+    },
+    /// Add, claim, complete and inspect tasks
+    Task {
+        #[command(subcommand)]
+        command: Box<commands::task::TaskCommand>,
+    },
+    /// Add or remove workers while the hive is running
+    Worker {
+        #[command(subcommand)]
+        command: commands::worker::WorkerCommand,
+    },
+    /// Summarize completed, in-progress and queued work per lane
+    Report {
+        /// Only list tasks completed since then: a duration ago (24h, 2d) or a date, or "all"
+        #[arg(long, default_value = "24h")]
+        since: String,
+        /// Output format: md, csv or json
+        #[arg(long, default_value = "md")]
+        format: String,
+    },
     /// Regenerate role files
     Role { worker: Option<String> },
     /// Check and fix hive configuration
@@ -70,6 +94,10 @@ fn main() -> Result<()> {
         Commands::Down => commands::down::run(&cwd),
         Commands::Status => commands::status::run(&cwd),
         Commands::Nudge { worker } => commands::nudge::run(&cwd, worker.as_deref()),
+        Commands::Restart { worker } => commands::restart::run(&cwd, &worker),
+        Commands::Task { command } => commands::task::run(&cwd, *command),
+        Commands::Worker { command } => commands::worker::run(&cwd, command),
+        Commands::Report { since, format } => commands::report::run(&cwd, &since, &format),
         Commands::Role { worker } => commands::role::run(&cwd, worker.as_deref()),
         Commands::Doctor => commands::doctor::run(&cwd),
         Commands::Deinit => commands::deinit::run(&cwd),
@@ -0,0 +1,157 @@
+use std::time::Duration;
+
+use crate::app::types::AgentState;
+use crate::config::Backend;
+
+/// How long a pane has to be quiet before it can count as idle, awaiting
+/// approval or errored; agents keep redrawing a spinner while they work
+pub const QUIET_AFTER: Duration = Duration::from_secs(2);
+
+/// Non-blank lines at the bottom of the screen that are checked, since
+/// output further up can contain anything
+const PROMPT_LINES: usize = 12;
+
+/// Prompts any command-line tool might ask with
+const YES_NO_PROMPTS: &[&str] = &["(y/n)", "[y/n]"];
+
+/// Lowercase text an agent's screen shows in each state
+struct ScreenPatterns {
+    busy: &'static [&'static str],
+    approval: &'static [&'static str],
+    error: &'static [&'static str],
+}
+
+fn screen_patterns(backend: Backend) -> ScreenPatterns {
+    match backend {
+        Backend::Claude => ScreenPatterns {
+            busy: &["esc to interrupt"],
+            approval: &[
+                "do you want to proceed?",
+                "do you want to make this edit",
+                "do you want to create",
+                "yes, and don't ask again",
+            ],
+            error: &[
+                "api error",
+                "credit balance is too low",
+                "usage limit reached",
+            ],
+        },
+        Backend::Codex => ScreenPatterns {
+            busy: &["esc to interrupt"],
+            approval: &[
+                "allow command?",
+                "would you like to run the following command?",
+                "would you like to make the following edits?",
+                "yes, proceed",
+            ],
+            error: &[
+                "stream error",
+                "error sending request",
+                "you've hit your usage limit",
+            ],
+        },
+    }
+}
+
+/// The state of an agent from its screen (the rows of its `OutputBuffer`
+/// grid), how long its pane has been quiet, and whether it has exited
+pub fn detect_state(
+    backend: Backend,
+    screen: &[String],
+    quiet_for: Duration,
+    exited: bool,
+) -> AgentState {
+    if exited {
+        return AgentState::Exited;
+    }
+    if quiet_for < QUIET_AFTER {
+        return AgentState::Busy;
+    }
+
+    let bottom: Vec<String> = screen
+        .iter()
+        .rev()
+        .filter(|line| !line.trim().is_empty())
+        .take(PROMPT_LINES)
+        .map(|line| line.to_lowercase())
+        .collect();
+    let shows = |patterns: &[&str]| {
+        bottom
+            .iter()
+            .any(|line| patterns.iter().any(|pattern| line.contains(pattern)))
+    };
+
+    let patterns = screen_patterns(backend);
+    if shows(patterns.approval) || shows(YES_NO_PROMPTS) {
+        AgentState::AwaitingApproval
+    } else if shows(patterns.busy) {
+        AgentState::Busy
+    } else if shows(patterns.error) {
+        AgentState::Errored
+    } else {
+        AgentState::Idle
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn screen(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn test_detect_state_from_quiet_time_and_screen() {
+        let quiet = QUIET_AFTER * 2;
+        let prompt = screen(&["> ", "", "  ? for shortcuts", "", ""]);
+        assert_eq!(
+            detect_state(Backend::Claude, &prompt, Duration::ZERO, false),
+            AgentState::Busy
+        );
+        assert_eq!(
+            detect_state(Backend::Claude, &prompt, quiet, false),
+            AgentState::Idle
+        );
+        assert_eq!(
+            detect_state(Backend::Claude, &prompt, quiet, true),
+            AgentState::Exited
+        );
+
+        let working = screen(&["* Reticulating… (12s · esc to interrupt)", "> "]);
+        assert_eq!(
+            detect_state(Backend::Claude, &working, quiet, false),
+            AgentState::Busy
+        );
+
+        let approval = screen(&[
+            "Bash command",
+            "  rm -rf target",
+            "Do you want to proceed?",
+            "❯ 1. Yes",
+        ]);
+        assert_eq!(
+            detect_state(Backend::Claude, &approval, quiet, false),
+            AgentState::AwaitingApproval
+        );
+        let yes_no = screen(&["Overwrite Cargo.lock? (y/n)"]);
+        assert_eq!(
+            detect_state(Backend::Codex, &yes_no, quiet, false),
+            AgentState::AwaitingApproval
+        );
+
+        let error = screen(&["⎿ API Error: 529 overloaded", "> "]);
+        assert_eq!(
+            detect_state(Backend::Claude, &error, quiet, false),
+            AgentState::Errored
+        );
+        // Only the bottom of the screen counts
+        let mut scrolled = error.clone();
+        scrolled.extend((0..PROMPT_LINES).map(|n| format!("line {}", n)));
+        assert_eq!(
+            detect_state(Backend::Claude, &scrolled, quiet, false),
+            AgentState::Idle
+        );
+    }
+}
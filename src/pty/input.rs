@@ -0,0 +1,183 @@
+use std::io::Write;
+use std::sync::mpsc::{self, Sender};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+
+use super::send_bytes;
+use crate::config::Backend;
+
+/// Start and end of a bracketed paste; the terminal app takes everything in
+/// between as one paste instead of as keystrokes
+const PASTE_START: &[u8] = b"\x1b[200~";
+const PASTE_END: &[u8] = b"\x1b[201~";
+
+/// How messages are typed into an agent's terminal
+pub trait InputDriver: Send {
+    /// Name for the server log
+    fn name(&self) -> &'static str;
+
+    /// Write `message` and submit it with Enter. Runs on the pane's writer
+    /// thread, so it may sleep.
+    fn send_message(&self, writer: &mut dyn Write, message: &str) -> Result<()>;
+}
+
+/// Sends the whole message as one bracketed paste, then Enter once the app
+/// has taken it in
+pub struct BracketedPaste {
+    pub submit_delay: Duration,
+}
+
+impl InputDriver for BracketedPaste {
+    fn name(&self) -> &'static str {
+        "paste"
+    }
+
+    fn send_message(&self, writer: &mut dyn Write, message: &str) -> Result<()> {
+        // A paste end marker inside the message would end the paste early
+        let message = message.replace("\x1b[201~", "");
+        let mut bytes = Vec::with_capacity(message.len() + PASTE_START.len() + PASTE_END.len());
+        bytes.extend_from_slice(PASTE_START);
+        bytes.extend_from_slice(message.as_bytes());
+        bytes.extend_from_slice(PASTE_END);
+        send_bytes(writer, &bytes)?;
+        thread::sleep(self.submit_delay);
+        send_bytes(writer, b"\r")
+    }
+}
+
+/// Types the message one byte at a time, for apps that mishandle pasted or
+/// bulk input
+pub struct CharByChar {
+    pub char_delay: Duration,
+    pub submit_delay: Duration,
+}
+
+impl InputDriver for CharByChar {
+    fn name(&self) -> &'static str {
+        "char-by-char"
+    }
+
+    fn send_message(&self, writer: &mut dyn Write, message: &str) -> Result<()> {
+        for byte in message.bytes() {
+            send_bytes(writer, &[byte])?;
+            thread::sleep(self.char_delay);
+        }
+        thread::sleep(self.submit_delay);
+        send_bytes(writer, b"\r")
+    }
+}
+
+/// The driver messages to `backend`'s agents are typed with
+pub fn driver_for(backend: Backend) -> Box<dyn InputDriver> {
+    match backend {
+        Backend::Claude => Box::new(BracketedPaste {
+            submit_delay: Duration::from_millis(100),
+        }),
+        // Codex has dropped keys from bulk input; keep typing it
+        Backend::Codex => Box::new(CharByChar {
+            char_delay: Duration::from_millis(2),
+            submit_delay: Duration::from_millis(50),
+        }),
+    }
+}
+
+enum Input {
+    Bytes(Vec<u8>),
+    Message(String),
+}
+
+/// Queue of input for a pane's PTY, written in order on the pane's writer
+/// thread so slow typing never holds up the caller
+pub struct PaneInput {
+    tx: Sender<Input>,
+    driver: &'static str,
+}
+
+impl PaneInput {
+    /// Start the writer thread for `writer`. It stops when the `PaneInput`
+    /// is dropped or a write fails.
+    pub fn spawn(mut writer: Box<dyn Write + Send>, driver: Box<dyn InputDriver>) -> Self {
+        let (tx, rx) = mpsc::channel::<Input>();
+        let name = driver.name();
+        thread::spawn(move || {
+            while let Ok(input) = rx.recv() {
+                let result = match input {
+                    Input::Bytes(bytes) => send_bytes(&mut writer, &bytes),
+                    Input::Message(message) => driver.send_message(&mut writer, &message),
+                };
+                if result.is_err() {
+                    break;
+                }
+            }
+        });
+        Self { tx, driver: name }
+    }
+
+    /// Queue raw bytes, e.g. keystrokes from a client
+    pub fn send_bytes(&self, bytes: &[u8]) -> Result<()> {
+        self.queue(Input::Bytes(bytes.to_vec()))
+    }
+
+    /// Queue a message to be typed and submitted by the pane's driver
+    pub fn send_message(&self, message: &str) -> Result<()> {
+        self.queue(Input::Message(message.to_string()))
+    }
+
+    /// Name of the driver messages are typed with
+    pub fn driver(&self) -> &'static str {
+        self.driver
+    }
+
+    fn queue(&self, input: Input) -> Result<()> {
+        self.tx
+            .send(input)
+            .map_err(|_| anyhow!("The pane's terminal is closed"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_pane_input_writes_in_order_with_driver() {
+        let captured = Captured::default();
+        let driver = BracketedPaste {
+            submit_delay: Duration::ZERO,
+        };
+        let input = PaneInput::spawn(Box::new(captured.clone()), Box::new(driver));
+        input.send_bytes(b"x").unwrap();
+        input.send_message("do \x1b[201~it").unwrap();
+        input.send_bytes(b"y").unwrap();
+        drop(input);
+
+        let expected = b"x\x1b[200~do it\x1b[201~\ry";
+        for _ in 0..100 {
+            if captured.0.lock().unwrap().as_slice() == expected {
+                return;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(
+            String::from_utf8_lossy(&captured.0.lock().unwrap()),
+            String::from_utf8_lossy(expected)
+        );
+    }
+}
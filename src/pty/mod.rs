@@ -1,3 +1,5 @@
+pub mod activity;
+pub mod input;
 pub mod output;
 pub mod pane;
 
@@ -10,14 +12,25 @@ use portable_pty::{native_pty_system, CommandBuilder, PtySize};
 
 use crate::config::Backend;
 
-pub use crate::app::types::PaneType;
+pub use crate::app::types::{AgentState, PaneType};
+pub use input::{driver_for, InputDriver, PaneInput};
 pub use pane::Pane;
 
 #[derive(Debug)]
 pub enum PaneEvent {
-    Output { pane_id: String, data: Vec<u8> },
-    Exited { pane_id: String },
-    Error { pane_id: String, error: String },
+    Output {
+        pane_id: String,
+        data: Vec<u8>,
+    },
+    /// The reader for spawn `generation` of the pane hit end of file
+    Exited {
+        pane_id: String,
+        generation: u32,
+    },
+    Error {
+        pane_id: String,
+        error: String,
+    },
 }
 
 pub fn spawn_agent(
@@ -97,6 +110,7 @@ pub fn spawn_agent(
 
 pub fn spawn_reader_thread(
     pane_id: String,
+    generation: u32,
     mut reader: Box<dyn Read + Send>,
     tx: std::sync::mpsc::Sender<PaneEvent>,
 ) {
@@ -125,7 +139,10 @@ pub fn spawn_reader_thread(
                 }
             }
         }
-        let _ = tx.send(PaneEvent::Exited { pane_id });
+        let _ = tx.send(PaneEvent::Exited {
+            pane_id,
+            generation,
+        });
     });
 }
 
@@ -1,5 +1,6 @@
 use alacritty_terminal::event::VoidListener;
 use alacritty_terminal::grid::{Dimensions, Scroll};
+use alacritty_terminal::index::{Column, Line};
 use alacritty_terminal::term::{Config, RenderableContent, Term, TermMode};
 use alacritty_terminal::vte::ansi::Processor;
 
@@ -94,6 +95,18 @@ impl OutputBuffer {
         self.term.scroll_display(Scroll::Bottom);
     }
 
+    /// The text of each row on screen, without trailing spaces
+    pub fn screen_text(&self) -> Vec<String> {
+        let grid = self.term.grid();
+        (0..grid.screen_lines())
+            .map(|row| {
+                let line = &grid[Line(row as i32)];
+                let text: String = (0..grid.columns()).map(|col| line[Column(col)].c).collect();
+                text.trim_end().to_string()
+            })
+            .collect()
+    }
+
     /// Check if the terminal is using the alternate screen buffer
     /// Alternate screen has no scrollback (used by TUI apps like vim, codex)
     pub fn is_alternate_screen(&self) -> bool {
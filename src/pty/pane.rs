@@ -1,20 +1,24 @@
 use std::collections::VecDeque;
-use std::io::Write;
+use std::io::Read;
 use std::path::PathBuf;
+use std::time::Instant;
 
+use anyhow::{Context, Result};
 use portable_pty::{Child, MasterPty};
 
-use crate::app::types::PaneType;
+use crate::app::types::{AgentState, PaneType};
 use crate::config::{Backend, BranchConfig};
 
 use super::output::OutputBuffer;
+use super::{driver_for, spawn_agent, PaneInput};
 
 pub struct Pane {
     pub id: String,
     pub pane_type: PaneType,
     pub master: Box<dyn MasterPty + Send>,
     pub child: Box<dyn Child + Send>,
-    pub writer: Box<dyn Write + Send>,
+    /// Queue for typing into the PTY, written on the pane's writer thread
+    pub input: PaneInput,
     pub output_buffer: OutputBuffer,
     pub raw_history: VecDeque<u8>,
     pub raw_history_max: usize,
@@ -24,9 +28,60 @@ pub struct Pane {
     pub group: Option<String>,
     pub visible: bool,
     pub backend: Backend,
+    /// When the pane last printed anything, for spotting hung workers
+    pub last_output: Instant,
+    /// Set once the pane's process has exited
+    pub exited_at: Option<Instant>,
+    /// Message the agent was started with, reused on restart
+    pub startup_message: String,
+    pub skip_permissions: bool,
+    /// Bumped on each restart so events from the old reader can be ignored
+    pub generation: u32,
+    pub started_at: Instant,
+    /// Automatic restarts in a row, for the restart policy's backoff
+    pub restarts: u32,
+    /// When the restart policy will restart this exited pane
+    pub restart_at: Option<Instant>,
+    /// What the agent is doing, from `activity::detect_state`
+    pub agent_state: AgentState,
+    /// When the server last typed a message into the pane
+    pub last_typed: Instant,
 }
 
 impl Pane {
+    /// Replace the pane's process with a fresh agent started the same way,
+    /// killing the old one if it's still running. Returns a reader for the
+    /// new PTY, to be read on a thread tagged with the new `generation`.
+    pub fn respawn(&mut self) -> Result<Box<dyn Read + Send>> {
+        if matches!(self.child.try_wait(), Ok(None)) {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
+        let size = self.master.get_size().ok();
+        let (master, child, writer) = spawn_agent(
+            self.backend,
+            &self.startup_message,
+            &self.working_dir,
+            self.skip_permissions,
+        )?;
+        if let Some(size) = size {
+            let _ = master.resize(size);
+        }
+        let reader = master
+            .try_clone_reader()
+            .context("Failed to clone PTY reader")?;
+        self.master = master;
+        self.child = child;
+        self.input = PaneInput::spawn(writer, driver_for(self.backend));
+        self.generation += 1;
+        self.started_at = Instant::now();
+        self.last_output = Instant::now();
+        self.exited_at = None;
+        self.restart_at = None;
+        self.agent_state = AgentState::Busy;
+        Ok(reader)
+    }
+
     pub fn push_history(&mut self, data: &[u8]) {
         for byte in data {
             self.raw_history.push_back(*byte);
@@ -0,0 +1,41 @@
+use std::path::Path;
+use std::time::Duration;
+
+use crate::tasks::parse_duration;
+use crate::workspace::WorkspaceConfig;
+
+use super::{log_line, ServerState};
+
+/// How often the auto-archive policy is applied
+pub(super) const AUTO_ARCHIVE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Move old done tasks to the archive according to the workspace policy. The
+/// watcher picks up the rewritten tasks file and refreshes counts.
+pub(super) fn auto_archive(state: &ServerState) {
+    let (Some(task_backend), Some(older_than)) = (&state.task_backend, state.auto_archive) else {
+        return;
+    };
+    match task_backend.archive(Some(older_than)) {
+        Ok(0) => {}
+        Ok(count) => log_line(
+            &state.log_path,
+            &format!("auto-archive: archived {} task(s)", count),
+        ),
+        Err(e) => log_line(&state.log_path, &format!("auto-archive: {:#}", e)),
+    }
+}
+
+/// The workspace's `archive.older_than`, parsed
+pub(super) fn auto_archive_age(
+    config: &WorkspaceConfig,
+    log_path: &Path,
+) -> Option<chrono::Duration> {
+    match config.archive.older_than.as_deref().map(parse_duration) {
+        Some(Ok(age)) => Some(age),
+        Some(Err(e)) => {
+            log_line(log_path, &format!("auto-archive disabled: {}", e));
+            None
+        }
+        None => None,
+    }
+}
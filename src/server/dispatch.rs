@@ -0,0 +1,38 @@
+use crate::tasks::{TaskCounts, TasksFile};
+
+use super::{configured_lanes, log_line, ServerState};
+
+/// Route `global_backlog` tasks to lanes using the counts last loaded. Runs
+/// when the tasks change rather than on every count refresh; the watcher
+/// picks up the rewritten file and nudges the receiving lanes.
+pub(super) fn dispatch_global(state: &ServerState, tasks: &TasksFile) {
+    let Some(task_backend) = &state.task_backend else {
+        return;
+    };
+    if tasks
+        .global_backlog
+        .as_ref()
+        .is_none_or(|global| global.is_empty())
+    {
+        return;
+    }
+    let mut lanes: Vec<(String, TaskCounts)> = configured_lanes(state)
+        .into_iter()
+        .map(|lane| {
+            let counts = state.task_counts.get(&lane).copied().unwrap_or_default();
+            (lane, counts)
+        })
+        .collect();
+    match task_backend.dispatch(&mut lanes) {
+        Ok(dispatched) => {
+            for task in dispatched {
+                let line = match &task.held {
+                    Some(reason) => format!("dispatch: {} held: {}", task.id, reason),
+                    None => format!("dispatch: {} -> {}", task.id, task.lane),
+                };
+                log_line(&state.log_path, &line);
+            }
+        }
+        Err(e) => log_line(&state.log_path, &format!("dispatch: {:#}", e)),
+    }
+}
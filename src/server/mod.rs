@@ -1,4 +1,11 @@
-use std::collections::HashMap;
+mod archive;
+mod dispatch;
+mod recurring;
+mod restart;
+mod stale;
+mod verify;
+
+use std::collections::{HashMap, HashSet};
 use std::io::{BufRead, BufReader, Write};
 use std::os::unix::net::{UnixListener, UnixStream};
 use std::path::{Path, PathBuf};
@@ -8,20 +15,55 @@ use std::sync::Arc;
 use std::thread;
 use std::time::{Duration, Instant};
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 
 use crate::app::state::{AppWindow, LayoutKind, LayoutMode};
 use crate::app::types::PaneType;
-use crate::app::{build_nudge_message, build_startup_message};
+use crate::app::{
+    build_answer_message, build_nudge_message, build_question_message, build_startup_message,
+};
 use crate::config::{self, HiveConfig, TaskSource};
 use crate::ipc::{
-    decode_client_message, encode_message, AppState, ClientMessage, PaneInfo, PaneSize,
-    ServerMessage, WindowInfo,
+    decode_client_message, encode_message, AppState, ClientMessage, ConfigChange, PaneInfo,
+    PaneSize, ServerMessage, WindowInfo,
+};
+use crate::pty::activity::detect_state;
+use crate::pty::{
+    driver_for, spawn_agent, spawn_reader_thread, AgentState, Pane, PaneEvent, PaneInput,
+};
+use crate::tasks::{
+    append_history, check_lane_known, counts_for_lane, open_backend, open_workspace_backend,
+    timestamp_now, transitions, NudgeRequest, StaleClaim, StalePolicy, TaskBackend, TaskCounts,
+    TaskEvent, TasksFile, HISTORY_FILE,
 };
-use crate::pty::{spawn_agent, spawn_reader_thread, Pane, PaneEvent};
-use crate::tasks::{counts_for_lane, load_tasks, spawn_yaml_watcher, NudgeRequest};
 use crate::utils::{git, shell};
-use crate::workspace::{expand_workers, WorkspaceConfig};
+
+use crate::workspace::{
+    create_worktrees_with_symlinks, expand_workers, remove_worktree, RuntimeWorker,
+    WorkspaceConfig, WorkspaceProject,
+};
+
+use archive::{auto_archive, auto_archive_age, AUTO_ARCHIVE_INTERVAL};
+use dispatch::dispatch_global;
+use recurring::{add_recurring_tasks, current_minute, recurring_tasks, Recurring};
+use restart::{auto_restart, restart_policy, RestartPolicy};
+use stale::{check_stale_claims, stale_policy, STALE_CHECK_INTERVAL};
+use verify::{finish_verification, start_verifications};
+
+/// How often pane screens are read for their agents' state
+const AGENT_STATE_INTERVAL: Duration = Duration::from_millis(500);
+
+/// A worker that goes idle with tasks to claim is nudged, but no sooner than
+/// this after the last message typed into it, so an agent that won't take a
+/// task isn't nudged over and over
+const IDLE_NUDGE_COOLDOWN: Duration = Duration::from_secs(60);
+
+/// How long the event loop waits for a tasks file locked by a `hive task`
+/// command before reading the last snapshot instead
+const SERVER_LOCK_WAIT: Duration = Duration::from_millis(100);
+
+/// How long a removed worker's agent gets to exit before it's killed
+const AGENT_STOP_GRACE: Duration = Duration::from_secs(10);
 
 const ARCHITECT_MESSAGE: &str = "Read .hive/ARCHITECT.md. You are the architect - plan tasks but do NOT edit code. Add tasks to the tasks file for workers to pick up.";
 
@@ -65,7 +107,7 @@ fn run_workspace(config_path: &Path) -> Result<()> {
             .master
             .try_clone_reader()
             .context("Failed to clone PTY reader")?;
-        spawn_reader_thread(pane.id.clone(), reader, pane_tx.clone());
+        spawn_reader_thread(pane.id.clone(), pane.generation, reader, pane_tx.clone());
     }
 
     let (nudge_tx, nudge_rx) = mpsc::channel::<NudgeRequest>();
@@ -73,18 +115,20 @@ fn run_workspace(config_path: &Path) -> Result<()> {
     let log_path = workspace_dir.join("server.log");
     let _ = std::fs::write(&log_path, "");
 
-    // Watch tasks file
-    let tasks_path = workspace_dir.join("tasks.yaml");
-    if tasks_path.exists() {
-        spawn_yaml_watcher(
-            tasks_path.clone(),
-            nudge_tx.clone(),
-            Duration::from_secs(10),
-            Duration::from_secs(5),
-            log_path.clone(),
-        )
-        .ok();
-    }
+    // Watch the task source (file watcher for yaml, polling for GitHub)
+    let task_backend = match open_workspace_backend(&workspace_dir, &config) {
+        Ok(mut backend) => {
+            backend.set_lock_wait(SERVER_LOCK_WAIT);
+            if let Err(e) = backend.watch(nudge_tx.clone(), log_path.clone()) {
+                log_line(&log_path, &format!("task source: watch failed: {}", e));
+            }
+            Some(backend)
+        }
+        Err(e) => {
+            log_line(&log_path, &format!("task source: {}", e));
+            None
+        }
+    };
 
     let socket_path = workspace_dir.join("hive.sock");
     prepare_socket(&socket_path)?;
@@ -93,6 +137,11 @@ fn run_workspace(config_path: &Path) -> Result<()> {
         .with_context(|| format!("Failed to bind {}", socket_path.display()))?;
     listener.set_nonblocking(true)?;
 
+    let auto_archive = auto_archive_age(&config, &log_path);
+    let stale_policy = stale_policy(&config, &log_path);
+    let recurring = recurring_tasks(&config, &log_path);
+    let restart_policy = restart_policy(&config, &log_path);
+
     // Create a minimal HiveConfig for compatibility
     let compat_config = create_compat_config(&config, &workers);
 
@@ -103,11 +152,26 @@ fn run_workspace(config_path: &Path) -> Result<()> {
         windows,
         layout_mode,
         task_counts: HashMap::new(),
-        tasks_file: Some(tasks_path),
+        next_tasks: HashMap::new(),
+        task_problems: Vec::new(),
+        last_tasks: None,
+        history_path: workspace_dir.join(HISTORY_FILE),
+        auto_archive,
+        stale_policy,
+        stale_claims: Vec::new(),
+        verifying: HashSet::new(),
+        recurring,
+        restart_policy,
+        pane_tx,
+        task_backend,
         log_path,
         architect_left: ui_state.architect_left,
         min_pane_width: config.layout.min_pane_width,
         min_pane_height: config.layout.min_pane_height,
+        config_path: config_path.to_path_buf(),
+        config_changes: Vec::new(),
+        config_error: None,
+        workspace: Some(config),
     };
 
     write_workspace_pid(&workspace_dir)?;
@@ -150,7 +214,7 @@ fn run_legacy(config_path: &Path) -> Result<()> {
             .master
             .try_clone_reader()
             .context("Failed to clone PTY reader")?;
-        spawn_reader_thread(pane.id.clone(), reader, pane_tx.clone());
+        spawn_reader_thread(pane.id.clone(), pane.generation, reader, pane_tx.clone());
     }
 
     let (nudge_tx, nudge_rx) = mpsc::channel::<NudgeRequest>();
@@ -158,19 +222,19 @@ fn run_legacy(config_path: &Path) -> Result<()> {
     let log_path = project_dir.join(".hive").join("server.log");
     let _ = std::fs::write(&log_path, ""); // reset log
 
-    let tasks_file = if let TaskSource::Yaml = config.tasks.source {
-        let tasks_path = config::tasks_file_path(config_path, &config);
-        spawn_yaml_watcher(
-            tasks_path.clone(),
-            nudge_tx.clone(),
-            Duration::from_secs(10),
-            Duration::from_secs(5),
-            log_path.clone(),
-        )
-        .ok();
-        Some(tasks_path)
-    } else {
-        None
+    // Watch the task source (file watcher for yaml, polling for GitHub)
+    let task_backend = match open_backend(config_path, &config) {
+        Ok(mut backend) => {
+            backend.set_lock_wait(SERVER_LOCK_WAIT);
+            if let Err(e) = backend.watch(nudge_tx.clone(), log_path.clone()) {
+                log_line(&log_path, &format!("task source: watch failed: {}", e));
+            }
+            Some(backend)
+        }
+        Err(e) => {
+            log_line(&log_path, &format!("task source: {}", e));
+            None
+        }
     };
 
     let socket_path = socket_path(&project_dir);
@@ -180,6 +244,7 @@ fn run_legacy(config_path: &Path) -> Result<()> {
         .with_context(|| format!("Failed to bind {}", socket_path.display()))?;
     listener.set_nonblocking(true)?;
 
+    let history_path = project_dir.join(".hive").join(HISTORY_FILE);
     let state = ServerState {
         config,
         project_dir,
@@ -187,11 +252,26 @@ fn run_legacy(config_path: &Path) -> Result<()> {
         windows,
         layout_mode,
         task_counts: HashMap::new(),
-        tasks_file,
+        next_tasks: HashMap::new(),
+        task_problems: Vec::new(),
+        last_tasks: None,
+        history_path,
+        auto_archive: None,
+        stale_policy: StalePolicy::default(),
+        stale_claims: Vec::new(),
+        verifying: HashSet::new(),
+        recurring: Vec::new(),
+        restart_policy: RestartPolicy::default(),
+        pane_tx,
+        workspace: None,
+        task_backend,
         log_path,
         architect_left: ui_state.architect_left,
         min_pane_width: crate::ui::layout::DEFAULT_MIN_PANE_WIDTH,
         min_pane_height: crate::ui::layout::DEFAULT_MIN_PANE_HEIGHT,
+        config_path: config_path.to_path_buf(),
+        config_changes: Vec::new(),
+        config_error: None,
     };
 
     write_pid(&state.project_dir)?;
@@ -210,7 +290,38 @@ struct ServerState {
     windows: Vec<AppWindow>,
     layout_mode: LayoutMode,
     task_counts: HashMap<String, crate::tasks::TaskCounts>,
-    tasks_file: Option<PathBuf>,
+    /// Top claimable backlog task per worker id, for the nudge message
+    next_tasks: HashMap<String, crate::tasks::Task>,
+    /// Lint diagnostics for the tasks file, shown in the status bar
+    task_problems: Vec<String>,
+    /// Last successful load, diffed against the next one for the history log
+    last_tasks: Option<TasksFile>,
+    /// JSONL log of task state changes
+    history_path: PathBuf,
+    /// Archive done tasks older than this, checked every `AUTO_ARCHIVE_INTERVAL`
+    auto_archive: Option<chrono::Duration>,
+    /// When claims count as stale and when they're requeued
+    stale_policy: StalePolicy,
+    /// Claims flagged by the last stale check, shown in the TUI
+    stale_claims: Vec<StaleClaim>,
+    /// Ids of tasks whose `verify` commands are running
+    verifying: HashSet<String>,
+    /// Tasks added to lanes on a schedule
+    recurring: Vec<Recurring>,
+    /// When exited panes are restarted automatically
+    restart_policy: RestartPolicy,
+    /// For the reader threads of restarted panes
+    pane_tx: Sender<PaneEvent>,
+    /// The workspace's config, updated as workers are added and removed and
+    /// as live settings are reloaded; None for a legacy .hive.yaml hive
+    workspace: Option<WorkspaceConfig>,
+    /// workspace.yaml or .hive.yaml, reloaded when it's edited
+    config_path: PathBuf,
+    /// Edits to the config file that only take effect on a restart
+    config_changes: Vec<ConfigChange>,
+    /// Why the edited config file couldn't be loaded; the running config is kept
+    config_error: Option<String>,
+    task_backend: Option<Box<dyn TaskBackend>>,
     log_path: PathBuf,
     architect_left: bool,
     min_pane_width: u16,
@@ -231,6 +342,17 @@ enum ServerEvent {
     },
 }
 
+/// A worker added at runtime, back from the thread that created its worktree
+/// and ran `workers.setup` in it
+struct NewWorker {
+    client_id: usize,
+    /// Index of the project the lane was added to
+    project: usize,
+    lane: String,
+    worker: RuntimeWorker,
+    setup: Result<()>,
+}
+
 #[derive(Clone)]
 struct ClientHandle {
     id: usize,
@@ -247,8 +369,19 @@ fn event_loop(
 ) -> Result<()> {
     let client_counter = Arc::new(AtomicUsize::new(1));
     let mut clients: Vec<ClientHandle> = Vec::new();
+    let (verify_tx, verify_rx) = mpsc::channel();
+    let (worker_tx, worker_rx) = mpsc::channel();
+    let (config_tx, config_rx) = mpsc::channel();
 
-    refresh_task_counts(&mut state).ok();
+    if let Ok(Some(tasks)) = refresh_task_counts(&mut state) {
+        dispatch_global(&state, &tasks);
+    }
+
+    if let Err(e) =
+        config::spawn_config_watcher(state.config_path.clone(), config_tx, state.log_path.clone())
+    {
+        log_line(&state.log_path, &format!("config-watcher: {}", e));
+    }
 
     let accept_tx = event_tx.clone();
     let accept_counter = client_counter.clone();
@@ -275,23 +408,58 @@ fn event_loop(
     });
 
     let mut last_tick = Instant::now();
+    // Run the first auto-archive right away
+    let mut last_archive = Instant::now()
+        .checked_sub(AUTO_ARCHIVE_INTERVAL)
+        .unwrap_or_else(Instant::now);
+    let mut last_stale_check = Instant::now();
+    let mut last_state_check = Instant::now();
+    let mut last_recurring_minute = current_minute();
 
     loop {
         while let Ok(req) = nudge_rx.try_recv() {
             match req {
-                NudgeRequest::All => {
-                    log_line(&state.log_path, "nudge-triggered");
-                    refresh_task_counts(&mut state).ok();
-                    let nudged = nudge_workers(&mut state, None).unwrap_or_default();
+                NudgeRequest::Events(events) => {
+                    log_line(
+                        &state.log_path,
+                        &format!("nudge-triggered events={:?}", events),
+                    );
+                    let before = state.task_counts.clone();
+                    if let Ok(Some(tasks)) = refresh_task_counts(&mut state) {
+                        dispatch_global(&state, &tasks);
+                        relay_questions(&mut state, &events, &tasks);
+                        start_verifications(&mut state, &events, &tasks, &verify_tx);
+                    }
+                    let lanes = lanes_to_nudge(&events, &before, &state.task_counts);
+                    let nudged = nudge_workers(&mut state, None, Some(&lanes)).unwrap_or_default();
                     log_line(
                         &state.log_path,
-                        &format!("nudge-result workers={:?}", nudged),
+                        &format!("nudge-result lanes={:?} workers={:?}", lanes, nudged),
                     );
                     broadcast_state(&state, &mut clients);
+                    broadcast(&mut clients, ServerMessage::TaskEvents { events });
+                }
+                NudgeRequest::Refresh => {
+                    log_line(&state.log_path, "refresh-triggered");
+                    refresh_task_counts(&mut state).ok();
+                    broadcast_state(&state, &mut clients);
                 }
             }
         }
 
+        while let Ok(result) = verify_rx.try_recv() {
+            finish_verification(&mut state, result);
+        }
+
+        while let Ok(new_worker) = worker_rx.try_recv() {
+            finish_new_worker(&mut state, &mut clients, new_worker);
+        }
+
+        if config_rx.try_recv().is_ok() {
+            while config_rx.try_recv().is_ok() {}
+            reload_config(&mut state, &mut clients);
+        }
+
         while let Ok(event) = pane_rx.try_recv() {
             match event {
                 PaneEvent::Output { pane_id, data } => {
@@ -303,21 +471,33 @@ fn event_loop(
                     // Detect cursor position query (ESC[6n) and auto-respond
                     // This fixes codex which queries cursor position and times out
                     if crate::pty::contains_cursor_query(&data) {
-                        if let Some(pane) = state.panes.iter_mut().find(|p| p.id == pane_id) {
+                        if let Some(pane) = state.panes.iter().find(|p| p.id == pane_id) {
                             // Respond with cursor at position 1,1
-                            let _ = pane.writer.write_all(b"\x1b[1;1R");
-                            let _ = pane.writer.flush();
+                            let _ = pane.input.send_bytes(b"\x1b[1;1R");
                         }
                     }
 
                     if let Some(pane) = state.panes.iter_mut().find(|p| p.id == pane_id) {
                         pane.output_buffer.push_bytes(&data);
                         pane.push_history(&data);
+                        pane.last_output = Instant::now();
                     }
                     broadcast(&mut clients, ServerMessage::Output { pane_id, data });
                 }
-                PaneEvent::Exited { pane_id } => {
+                PaneEvent::Exited {
+                    pane_id,
+                    generation,
+                } => {
+                    // The reader of a pane that has since been restarted
+                    let Some(pane) = state
+                        .panes
+                        .iter_mut()
+                        .find(|p| p.id == pane_id && p.generation == generation)
+                    else {
+                        continue;
+                    };
                     log_line(&state.log_path, &format!("pane-exited {}", pane_id));
+                    pane.exited_at.get_or_insert_with(Instant::now);
                     broadcast(&mut clients, ServerMessage::PaneExited { pane_id });
                 }
                 PaneEvent::Error { pane_id, error } => {
@@ -353,7 +533,13 @@ fn event_loop(
                 }
                 ServerEvent::ClientMessage { client_id, message } => {
                     log_line(&state.log_path, &format!("client-message {}", client_id));
-                    if handle_client_message(&mut state, &mut clients, message) {
+                    if handle_client_message(
+                        &mut state,
+                        &mut clients,
+                        client_id,
+                        message,
+                        &worker_tx,
+                    ) {
                         log_line(&state.log_path, "shutdown-requested");
                         break;
                     }
@@ -373,6 +559,59 @@ fn event_loop(
         if last_tick.elapsed() >= Duration::from_secs(2) {
             last_tick = Instant::now();
         }
+
+        if state.auto_archive.is_some() && last_archive.elapsed() >= AUTO_ARCHIVE_INTERVAL {
+            last_archive = Instant::now();
+            auto_archive(&state);
+        }
+
+        auto_restart(&mut state, &mut clients);
+
+        if last_state_check.elapsed() >= AGENT_STATE_INTERVAL {
+            last_state_check = Instant::now();
+            let changed = update_agent_states(&mut state);
+            if !changed.is_empty() {
+                let lanes: HashSet<String> = state
+                    .panes
+                    .iter()
+                    .filter(|pane| {
+                        changed.contains(&pane.id)
+                            && pane.agent_state == AgentState::Idle
+                            && pane.last_typed.elapsed() >= IDLE_NUDGE_COOLDOWN
+                    })
+                    .filter_map(|pane| pane.lane.clone())
+                    .collect();
+                if !lanes.is_empty() {
+                    let nudged = nudge_workers(&mut state, None, Some(&lanes)).unwrap_or_default();
+                    log_line(
+                        &state.log_path,
+                        &format!("idle-nudge lanes={:?} workers={:?}", lanes, nudged),
+                    );
+                }
+                let states = state
+                    .panes
+                    .iter()
+                    .filter(|pane| changed.contains(&pane.id))
+                    .map(|pane| (pane.id.clone(), pane.agent_state))
+                    .collect();
+                broadcast(&mut clients, ServerMessage::PaneStates { states });
+            }
+        }
+
+        if last_stale_check.elapsed() >= STALE_CHECK_INTERVAL {
+            last_stale_check = Instant::now();
+            if check_stale_claims(&mut state) {
+                broadcast_state(&state, &mut clients);
+            }
+        }
+
+        if !state.recurring.is_empty() {
+            let minute = current_minute();
+            if minute > last_recurring_minute {
+                add_recurring_tasks(&state, &last_recurring_minute, &minute);
+                last_recurring_minute = minute;
+            }
+        }
     }
 
     Ok(())
@@ -429,12 +668,14 @@ fn handle_client(
 fn handle_client_message(
     state: &mut ServerState,
     clients: &mut Vec<ClientHandle>,
+    client_id: usize,
     message: ClientMessage,
+    worker_tx: &Sender<NewWorker>,
 ) -> bool {
     match message {
         ClientMessage::Input { pane_id, data } => {
-            if let Some(pane) = state.panes.iter_mut().find(|p| p.id == pane_id) {
-                let _ = crate::pty::send_bytes(&mut pane.writer, &data);
+            if let Some(pane) = state.panes.iter().find(|p| p.id == pane_id) {
+                let _ = pane.input.send_bytes(&data);
             }
         }
         ClientMessage::Resize { panes } => {
@@ -444,7 +685,7 @@ fn handle_client_message(
         }
         ClientMessage::Nudge { worker } => {
             refresh_task_counts(state).ok();
-            let _ = nudge_workers(state, worker.as_deref());
+            let _ = nudge_workers(state, worker.as_deref(), None);
             broadcast_state(state, clients);
         }
         ClientMessage::SetVisibility { pane_id, visible } => {
@@ -478,6 +719,95 @@ fn handle_client_message(
             let _ = write_layout_mode(&state.project_dir, mode);
             broadcast_state(state, clients);
         }
+        ClientMessage::AddTask { lane, task } => {
+            let id = task.id.clone();
+            let known_lanes = configured_lanes(state);
+            edit_tasks(state, clients, client_id, |backend| {
+                check_lane_known(&backend.load()?, &lane, &known_lanes)?;
+                backend.add(&lane, *task)?;
+                Ok(format!("add {} to {}", id, lane))
+            });
+        }
+        ClientMessage::MoveTask {
+            id,
+            lane,
+            state: to_state,
+            to_lane,
+        } => {
+            let known_lanes = configured_lanes(state);
+            edit_tasks(state, clients, client_id, |backend| {
+                if let Some(to_lane) = &to_lane {
+                    check_lane_known(&backend.load()?, to_lane, &known_lanes)?;
+                }
+                let location = backend.move_to(&id, Some(&lane), to_state, to_lane.as_deref())?;
+                Ok(format!(
+                    "move {} to {} ({})",
+                    id, location.state, location.lane
+                ))
+            });
+        }
+        ClientMessage::ReprioritizeTask { id, lane, up } => {
+            edit_tasks(state, clients, client_id, |backend| {
+                backend.reprioritize(&id, Some(&lane), up)?;
+                Ok(format!(
+                    "reprioritize {} ({})",
+                    id,
+                    if up { "up" } else { "down" }
+                ))
+            });
+        }
+        ClientMessage::DeleteTask { id, lane } => {
+            edit_tasks(state, clients, client_id, |backend| {
+                backend.delete(&id, Some(&lane))?;
+                Ok(format!("delete {} from {}", id, lane))
+            });
+        }
+        ClientMessage::RestartPane { pane_id } => {
+            if let Err(err) = restart_pane(state, clients, &pane_id, false) {
+                log_line(
+                    &state.log_path,
+                    &format!("restart-error {} {:#}", pane_id, err),
+                );
+                send_error(
+                    clients,
+                    client_id,
+                    format!("Failed to restart {}: {:#}", pane_id, err),
+                );
+            }
+        }
+        ClientMessage::AddWorker { project, lane } => {
+            if let Err(err) = add_worker(state, client_id, &project, &lane, worker_tx) {
+                log_line(
+                    &state.log_path,
+                    &format!("add-worker-error {} {:#}", lane, err),
+                );
+                send_error(
+                    clients,
+                    client_id,
+                    format!("Failed to add a worker for {}: {:#}", lane, err),
+                );
+            }
+        }
+        ClientMessage::RemoveWorker {
+            pane_id,
+            remove_worktree,
+        } => match remove_worker(state, &pane_id, remove_worktree) {
+            Ok(()) => {
+                refresh_task_counts(state).ok();
+                broadcast_state(state, clients);
+            }
+            Err(err) => {
+                log_line(
+                    &state.log_path,
+                    &format!("remove-worker-error {} {:#}", pane_id, err),
+                );
+                send_error(
+                    clients,
+                    client_id,
+                    format!("Failed to remove {}: {:#}", pane_id, err),
+                );
+            }
+        },
         ClientMessage::Detach => {}
         ClientMessage::Shutdown => {
             return true;
@@ -486,6 +816,742 @@ fn handle_client_message(
     false
 }
 
+/// Apply a task queue edit from a client and send every client the new
+/// counts. The watcher reports the change and nudges workers as it does for
+/// edits made with `hive task`; a failed edit goes back to the sender only.
+fn edit_tasks(
+    state: &mut ServerState,
+    clients: &mut Vec<ClientHandle>,
+    client_id: usize,
+    edit: impl FnOnce(&dyn TaskBackend) -> Result<String>,
+) {
+    let Some(task_backend) = &state.task_backend else {
+        return;
+    };
+    match edit(task_backend.as_ref()) {
+        Ok(done) => {
+            log_line(&state.log_path, &format!("task-edit {}", done));
+            refresh_task_counts(state).ok();
+            broadcast_state(state, clients);
+        }
+        Err(err) => {
+            log_line(&state.log_path, &format!("task-edit-error {:#}", err));
+            send_error(clients, client_id, format!("{:#}", err));
+        }
+    }
+}
+
+/// Report a failed request to the client that made it
+fn send_error(clients: &[ClientHandle], client_id: usize, message: String) {
+    if let Some(client) = clients.iter().find(|client| client.id == client_id) {
+        let _ = client.sender.send(ServerMessage::Error { message });
+    }
+}
+
+/// Add `lane` to a workspace project and create its worktree and run
+/// `workers.setup` on a background thread; `finish_new_worker` starts the
+/// agent once that's done. The lane is taken right away so it can't be
+/// added twice.
+fn add_worker(
+    state: &mut ServerState,
+    client_id: usize,
+    project: &str,
+    lane: &str,
+    worker_tx: &Sender<NewWorker>,
+) -> Result<()> {
+    let Some(config) = &mut state.workspace else {
+        bail!("Workers can only be added to a workspace hive");
+    };
+    let index = config.add_lane(project, lane)?;
+    let project = config.projects[index].clone();
+    let full_lane = project.lane_name(lane);
+    let worker = expand_workers(config, &state.project_dir)
+        .into_iter()
+        .find(|worker| worker.project_path == project.path && worker.lane == full_lane)
+        .with_context(|| format!("No worker for lane '{}'", full_lane))?;
+    log_line(
+        &state.log_path,
+        &format!(
+            "add-worker {} in {}",
+            full_lane,
+            worker.working_dir.display()
+        ),
+    );
+
+    let setup = config.workers.setup.clone();
+    let symlink = config.workers.symlink.clone();
+    let workspace_dir = state.project_dir.clone();
+    let worker_tx = worker_tx.clone();
+    let lane = lane.to_string();
+    thread::spawn(move || {
+        let setup =
+            create_worktrees_with_symlinks(&workspace_dir, &project, &symlink).and_then(|_| {
+                setup
+                    .iter()
+                    .try_for_each(|cmd| shell::run_shell_command(cmd, &worker.working_dir))
+            });
+        let _ = worker_tx.send(NewWorker {
+            client_id,
+            project: index,
+            lane,
+            worker,
+            setup,
+        });
+    });
+    Ok(())
+}
+
+/// Start the agent of a worker added at runtime and save the workspace, or
+/// give the lane back if its setup failed
+fn finish_new_worker(state: &mut ServerState, clients: &mut Vec<ClientHandle>, new: NewWorker) {
+    let full_lane = new.worker.lane.clone();
+    match new.setup.and_then(|()| start_new_worker(state, new.worker)) {
+        Ok(id) => {
+            log_line(
+                &state.log_path,
+                &format!("worker-added {} ({})", id, full_lane),
+            );
+            save_ui_state(&state.project_dir, state);
+            refresh_task_counts(state).ok();
+            broadcast_state(state, clients);
+        }
+        Err(err) => {
+            log_line(
+                &state.log_path,
+                &format!("add-worker-error {} {:#}", full_lane, err),
+            );
+            if let Some(config) = &mut state.workspace {
+                let project = &mut config.projects[new.project];
+                project.lanes.retain(|lane| *lane != new.lane);
+                project.workers = project.lanes.len();
+                let path = project.path.clone();
+                config.worker_ids.shift_remove(&full_lane);
+                if let Err(err) = edit_workspace_file(&state.project_dir, |on_disk| {
+                    if let Some(project) = on_disk.projects.iter_mut().find(|p| p.path == path) {
+                        project.lanes.retain(|lane| *lane != new.lane);
+                        project.workers = project.lanes.len();
+                    }
+                    on_disk.worker_ids.shift_remove(&full_lane);
+                    Ok(())
+                }) {
+                    log_line(&state.log_path, &format!("add-worker-error {:#}", err));
+                }
+            }
+            send_error(
+                clients,
+                new.client_id,
+                format!("Failed to add a worker for {}: {:#}", full_lane, err),
+            );
+        }
+    }
+}
+
+/// Save the workspace with the new lane and the worker ids, write its role
+/// file and spawn its pane. Returns the worker's id.
+fn start_new_worker(state: &mut ServerState, worker: RuntimeWorker) -> Result<String> {
+    let Some(config) = &state.workspace else {
+        bail!("Workers can only be added to a workspace hive");
+    };
+    let workspace_dir = &state.project_dir;
+    if let Some((project, lane)) = project_lane(config, &worker.lane) {
+        edit_workspace_file(workspace_dir, |on_disk| {
+            if project_lane(on_disk, &worker.lane).is_none() {
+                on_disk.add_lane(&project.path.to_string_lossy(), lane)?;
+            }
+            keep_worker_ids(on_disk, config);
+            Ok(())
+        })?;
+    }
+    if let Err(err) = write_worker_role(workspace_dir, config, &worker.id, &worker.lane) {
+        log_line(
+            &state.log_path,
+            &format!("add-worker {}: role file: {:#}", worker.id, err),
+        );
+    }
+
+    let pane = spawn_worker_pane(config, workspace_dir, &worker)?;
+    let reader = pane
+        .master
+        .try_clone_reader()
+        .context("Failed to clone PTY reader")?;
+    spawn_reader_thread(
+        pane.id.clone(),
+        pane.generation,
+        reader,
+        state.pane_tx.clone(),
+    );
+    state.panes.push(pane);
+    for window in &mut state.config.windows {
+        window.workers.push(compat_worker(&worker));
+    }
+    state.windows = workspace_windows(&state.panes);
+    Ok(worker.id)
+}
+
+/// The project with the lane a worker works as `full_lane`, and the lane's
+/// name within it
+fn project_lane<'a>(
+    config: &'a WorkspaceConfig,
+    full_lane: &str,
+) -> Option<(&'a WorkspaceProject, &'a str)> {
+    config.projects.iter().find_map(|project| {
+        let lane = project
+            .lanes
+            .iter()
+            .find(|lane| project.lane_name(lane) == full_lane)?;
+        Some((project, lane.as_str()))
+    })
+}
+
+/// Write the role file of the worker `worker_id`, which works `full_lane`
+fn write_worker_role(
+    workspace_dir: &Path,
+    config: &WorkspaceConfig,
+    worker_id: &str,
+    full_lane: &str,
+) -> Result<()> {
+    let (project, lane) = project_lane(config, full_lane)
+        .with_context(|| format!("No lane '{}' in the workspace", full_lane))?;
+    crate::commands::setup::write_lane_role(
+        workspace_dir,
+        config,
+        project,
+        lane,
+        worker_id,
+        full_lane,
+    )
+}
+
+/// Apply a lane edit to workspace.yaml as it is on disk instead of saving the
+/// running config over it, so edits that wait for a restart aren't lost
+fn edit_workspace_file(
+    workspace_dir: &Path,
+    edit: impl FnOnce(&mut WorkspaceConfig) -> Result<()>,
+) -> Result<()> {
+    let mut on_disk = WorkspaceConfig::load(workspace_dir)?;
+    edit(&mut on_disk)?;
+    on_disk.save(workspace_dir)
+}
+
+/// Give the lanes workspace.yaml shares with the running config the ids their
+/// workers run under, so a restart brings each back under the same id. Lanes
+/// only in the file are left to take free ids then.
+fn keep_worker_ids(on_disk: &mut WorkspaceConfig, running: &WorkspaceConfig) {
+    let ids = running
+        .worker_ids
+        .iter()
+        .filter(|(lane, _)| project_lane(on_disk, lane).is_some())
+        .map(|(lane, id)| (lane.clone(), id.clone()))
+        .collect();
+    on_disk.worker_ids = ids;
+}
+
+/// Drop a worker's lane from the workspace, take its pane out of the hive,
+/// and stop its agent in the background
+fn remove_worker(state: &mut ServerState, pane_id: &str, remove_worktree: bool) -> Result<()> {
+    let Some(config) = &mut state.workspace else {
+        bail!("Workers can only be removed from a workspace hive");
+    };
+    let index = state
+        .panes
+        .iter()
+        .position(|pane| pane.id == pane_id)
+        .with_context(|| format!("No worker named '{}'", pane_id))?;
+    let PaneType::Worker { lane } = &state.panes[index].pane_type else {
+        bail!("The architect can't be removed");
+    };
+    let worktree = expand_workers(config, &state.project_dir)
+        .into_iter()
+        .find(|worker| worker.lane == *lane && worker.is_worktree)
+        .map(|worker| (worker.project_path, worker.working_dir));
+
+    let mut updated = config.clone();
+    updated.remove_lane(lane)?;
+    edit_workspace_file(&state.project_dir, |on_disk| {
+        if project_lane(on_disk, lane).is_some() {
+            on_disk.remove_lane(lane)?;
+        }
+        keep_worker_ids(on_disk, &updated);
+        Ok(())
+    })?;
+    *config = updated;
+
+    let pane = state.panes.remove(index);
+    for window in &mut state.config.windows {
+        window.workers.retain(|worker| worker.id != pane_id);
+    }
+    state.windows = workspace_windows(&state.panes);
+    save_ui_state(&state.project_dir, state);
+    log_line(&state.log_path, &format!("worker-removed {}", pane_id));
+
+    let worktree = if remove_worktree { worktree } else { None };
+    stop_agent(pane, worktree, state.log_path.clone());
+    Ok(())
+}
+
+/// Ask a removed worker's agent to exit, kill it if it's still running after
+/// `AGENT_STOP_GRACE`, then remove its worktree if given one
+fn stop_agent(mut pane: Pane, worktree: Option<(PathBuf, PathBuf)>, log_path: PathBuf) {
+    thread::spawn(move || {
+        // Claude and Codex both exit on a second Ctrl+C
+        for _ in 0..2 {
+            let _ = pane.input.send_bytes(b"\x03");
+            thread::sleep(Duration::from_millis(300));
+        }
+        let deadline = Instant::now() + AGENT_STOP_GRACE;
+        while matches!(pane.child.try_wait(), Ok(None)) {
+            if Instant::now() >= deadline {
+                log_line(&log_path, &format!("stop-agent {}: killing", pane.id));
+                let _ = pane.child.kill();
+                let _ = pane.child.wait();
+                break;
+            }
+            thread::sleep(Duration::from_millis(200));
+        }
+        if let Some((repo, worktree)) = worktree {
+            match remove_worktree(&repo, &worktree) {
+                Ok(()) => log_line(
+                    &log_path,
+                    &format!("stop-agent {}: removed {}", pane.id, worktree.display()),
+                ),
+                Err(err) => log_line(&log_path, &format!("stop-agent {}: {:#}", pane.id, err)),
+            }
+        }
+    });
+}
+
+/// Start a fresh agent in `pane_id` the way it was first started and tell
+/// clients. `automatic` restarts count towards the restart policy's backoff;
+/// one asked for by hand starts the count over.
+fn restart_pane(
+    state: &mut ServerState,
+    clients: &mut Vec<ClientHandle>,
+    pane_id: &str,
+    automatic: bool,
+) -> Result<()> {
+    let pane = state
+        .panes
+        .iter_mut()
+        .find(|p| p.id == pane_id)
+        .with_context(|| format!("No pane named '{}'", pane_id))?;
+    let reader = pane.respawn()?;
+    spawn_reader_thread(
+        pane.id.clone(),
+        pane.generation,
+        reader,
+        state.pane_tx.clone(),
+    );
+    pane.restarts = if automatic { pane.restarts + 1 } else { 0 };
+
+    let notice = format!("\r\n[{} restarted]\r\n", pane_id).into_bytes();
+    pane.output_buffer.push_bytes(&notice);
+    pane.push_history(&notice);
+    log_line(
+        &state.log_path,
+        &format!(
+            "pane-restarted {} ({})",
+            pane_id,
+            if automatic { "policy" } else { "requested" }
+        ),
+    );
+    broadcast(
+        clients,
+        ServerMessage::Output {
+            pane_id: pane_id.to_string(),
+            data: notice,
+        },
+    );
+    Ok(())
+}
+
+/// Read each pane's screen for what its agent is doing. Returns the ids of
+/// the panes whose state changed.
+fn update_agent_states(state: &mut ServerState) -> Vec<String> {
+    let mut changed = Vec::new();
+    for pane in &mut state.panes {
+        let quiet_for = pane.last_output.max(pane.last_typed).elapsed();
+        let agent_state = detect_state(
+            pane.backend,
+            &pane.output_buffer.screen_text(),
+            quiet_for,
+            pane.exited_at.is_some(),
+        );
+        if agent_state != pane.agent_state {
+            log_line(
+                &state.log_path,
+                &format!(
+                    "agent-state {} {} -> {}",
+                    pane.id,
+                    pane.agent_state.label(),
+                    agent_state.label()
+                ),
+            );
+            pane.agent_state = agent_state;
+            changed.push(pane.id.clone());
+        }
+    }
+    changed
+}
+
+/// Reread the config file after it's edited: apply the settings that can
+/// change under running agents, and list the rest for the TUI until the hive
+/// is restarted. An invalid file is reported and the running config kept.
+fn reload_config(state: &mut ServerState, clients: &mut Vec<ClientHandle>) {
+    let result = if state.workspace.is_some() {
+        reload_workspace(state)
+    } else {
+        reload_legacy(state)
+    };
+    match result {
+        Ok(applied) => {
+            log_line(
+                &state.log_path,
+                &format!(
+                    "config-reloaded applied={:?} pending={:?}",
+                    applied,
+                    state
+                        .config_changes
+                        .iter()
+                        .map(|change| &change.change)
+                        .collect::<Vec<_>>()
+                ),
+            );
+            state.config_error = None;
+            broadcast_state(state, clients);
+            if !applied.is_empty() {
+                broadcast(clients, ServerMessage::ConfigReloaded { applied });
+            }
+        }
+        Err(err) => {
+            log_line(&state.log_path, &format!("config-error {:#}", err));
+            state.config_error = Some(format!("{:#}", err));
+            broadcast_state(state, clients);
+        }
+    }
+}
+
+/// Whether two config sections are the same, compared in their serialized form
+fn same<T: serde::Serialize>(a: &T, b: &T) -> bool {
+    serde_json::to_value(a).ok() == serde_json::to_value(b).ok()
+}
+
+/// Apply the live settings of an edited workspace.yaml and diff the rest
+/// against the running workspace. Returns the sections applied.
+fn reload_workspace(state: &mut ServerState) -> Result<Vec<String>> {
+    let new = WorkspaceConfig::load(&state.project_dir)?;
+    new.validate()?;
+    let Some(mut running) = state.workspace.take() else {
+        return Ok(Vec::new());
+    };
+
+    let mut applied = Vec::new();
+    if !same(&running.messages, &new.messages) {
+        running.messages = new.messages.clone();
+        state.config.messages = new.messages.clone();
+        applied.push("messages".to_string());
+    }
+    if !same(&running.layout, &new.layout) {
+        running.layout = new.layout.clone();
+        state.min_pane_width = new.layout.min_pane_width;
+        state.min_pane_height = new.layout.min_pane_height;
+        applied.push("layout".to_string());
+    }
+    if !same(&running.workflow, &new.workflow) {
+        running.workflow = new.workflow.clone();
+        state.config.workflow = new.workflow.clone();
+        applied.push("workflow".to_string());
+        if let Err(err) = write_workspace_roles(&state.project_dir, &running, &state.panes) {
+            log_line(
+                &state.log_path,
+                &format!("config-reload: role files: {:#}", err),
+            );
+        }
+    }
+    // Read when a worker is added or an agent started, so they only affect
+    // new panes
+    if running.workers.skip_permissions != new.workers.skip_permissions {
+        running.workers.skip_permissions = new.workers.skip_permissions;
+        state.config.workers.skip_permissions = new.workers.skip_permissions;
+        applied.push("workers.skip_permissions".to_string());
+    }
+    let workers: Vec<(String, String)> = state
+        .panes
+        .iter()
+        .filter_map(|pane| match &pane.pane_type {
+            PaneType::Worker { lane } => Some((pane.id.clone(), lane.clone())),
+            PaneType::Architect => None,
+        })
+        .collect();
+    state.config_changes = workspace_changes(&running, &new, &state.project_dir, &workers);
+    state.workspace = Some(running);
+    Ok(applied)
+}
+
+/// Rewrite ARCHITECT.md and the lane role files of the running workers
+fn write_workspace_roles(
+    workspace_dir: &Path,
+    config: &WorkspaceConfig,
+    panes: &[Pane],
+) -> Result<()> {
+    crate::commands::setup::write_architect_role(workspace_dir, config)?;
+    for pane in panes {
+        if let PaneType::Worker { lane } = &pane.pane_type {
+            write_worker_role(workspace_dir, config, &pane.id, lane)?;
+        }
+    }
+    Ok(())
+}
+
+/// What an edited workspace.yaml changes that needs a restart, with the panes
+/// each change would affect. `workers` are the running workers' pane ids and
+/// lanes.
+fn workspace_changes(
+    running: &WorkspaceConfig,
+    new: &WorkspaceConfig,
+    workspace_dir: &Path,
+    workers: &[(String, String)],
+) -> Vec<ConfigChange> {
+    let lane_panes = |full_lane: &str| -> Vec<String> {
+        workers
+            .iter()
+            .filter(|(_, lane)| lane == full_lane)
+            .map(|(id, _)| id.clone())
+            .collect()
+    };
+    let worker_panes: Vec<String> = workers.iter().map(|(id, _)| id.clone()).collect();
+
+    let mut changes = Vec::new();
+    let before = expand_workers(running, workspace_dir);
+    let after = expand_workers(new, workspace_dir);
+    for worker in &after {
+        match before.iter().find(|old| old.lane == worker.lane) {
+            None => changes.push(ConfigChange {
+                change: format!(
+                    "add lane {} in {}",
+                    worker.lane,
+                    worker.project_path.display()
+                ),
+                panes: Vec::new(),
+            }),
+            Some(old) if old.working_dir != worker.working_dir => changes.push(ConfigChange {
+                change: format!(
+                    "move lane {} to {}",
+                    worker.lane,
+                    worker.working_dir.display()
+                ),
+                panes: lane_panes(&worker.lane),
+            }),
+            Some(old) if old.id != worker.id => changes.push(ConfigChange {
+                change: format!("lane {} worker id {} -> {}", worker.lane, old.id, worker.id),
+                panes: lane_panes(&worker.lane),
+            }),
+            Some(_) => {}
+        }
+    }
+    for worker in &before {
+        if !after.iter().any(|new| new.lane == worker.lane) {
+            changes.push(ConfigChange {
+                change: format!("remove lane {}", worker.lane),
+                panes: lane_panes(&worker.lane),
+            });
+        }
+    }
+
+    if running.architect.backend != new.architect.backend {
+        changes.push(ConfigChange {
+            change: format!(
+                "architect backend {} -> {}",
+                crate::app::backend_label(running.architect.backend),
+                crate::app::backend_label(new.architect.backend)
+            ),
+            panes: vec!["architect".to_string()],
+        });
+    }
+    if running.workers.backend != new.workers.backend {
+        changes.push(ConfigChange {
+            change: format!(
+                "workers backend {} -> {}",
+                crate::app::backend_label(running.workers.backend),
+                crate::app::backend_label(new.workers.backend)
+            ),
+            panes: worker_panes,
+        });
+    }
+    if !same(&running.watch, &new.watch) {
+        changes.push(ConfigChange {
+            change: "tasks watcher timing".to_string(),
+            panes: Vec::new(),
+        });
+    }
+    if !same(&running.tasks, &new.tasks) {
+        changes.push(ConfigChange {
+            change: "task source".to_string(),
+            panes: Vec::new(),
+        });
+    }
+    // Read by the server at startup only
+    let sections = [
+        (
+            "workers.setup/symlink",
+            running.workers.setup != new.workers.setup
+                || running.workers.symlink != new.workers.symlink,
+        ),
+        (
+            "stale_claims",
+            !same(&running.stale_claims, &new.stale_claims),
+        ),
+        ("archive", !same(&running.archive, &new.archive)),
+        (
+            "restart_policy",
+            !same(&running.restart_policy, &new.restart_policy),
+        ),
+        (
+            "recurring",
+            !same(&running.recurring, &new.recurring)
+                || (!new.recurring.is_empty()
+                    && !same(&running.task_templates, &new.task_templates)),
+        ),
+    ];
+    for (section, changed) in sections {
+        if changed {
+            changes.push(ConfigChange {
+                change: section.to_string(),
+                panes: Vec::new(),
+            });
+        }
+    }
+    if running.name != new.name {
+        changes.push(ConfigChange {
+            change: format!("workspace name {} -> {}", running.name, new.name),
+            panes: Vec::new(),
+        });
+    }
+    changes
+}
+
+/// Apply the live settings of an edited .hive.yaml and diff the rest against
+/// the running config. Returns the sections applied.
+fn reload_legacy(state: &mut ServerState) -> Result<Vec<String>> {
+    let new = config::load_config(&state.config_path)?;
+    config::validate(&new)?;
+
+    let mut applied = Vec::new();
+    if !same(&state.config.messages, &new.messages) {
+        state.config.messages = new.messages.clone();
+        applied.push("messages".to_string());
+    }
+    let mut roles_changed = false;
+    if !same(&state.config.workflow, &new.workflow) {
+        state.config.workflow = new.workflow.clone();
+        applied.push("workflow".to_string());
+        roles_changed = true;
+    }
+    if state.config.worker_instructions != new.worker_instructions {
+        state.config.worker_instructions = new.worker_instructions.clone();
+        applied.push("worker_instructions".to_string());
+        roles_changed = true;
+    }
+    if roles_changed {
+        if let Err(err) = crate::commands::role::run(&state.project_dir, None) {
+            log_line(
+                &state.log_path,
+                &format!("config-reload: role files: {:#}", err),
+            );
+        }
+    }
+    if state.config.workers.skip_permissions != new.workers.skip_permissions {
+        state.config.workers.skip_permissions = new.workers.skip_permissions;
+        applied.push("workers.skip_permissions".to_string());
+    }
+
+    state.config_changes = legacy_changes(&state.config, &new);
+    Ok(applied)
+}
+
+/// What an edited .hive.yaml changes that needs a restart, with the panes
+/// each change would affect
+fn legacy_changes(running: &HiveConfig, new: &HiveConfig) -> Vec<ConfigChange> {
+    let workers = |config: &HiveConfig| -> Vec<crate::config::WorkerConfig> {
+        config
+            .windows
+            .iter()
+            .flat_map(|window| window.workers.iter().cloned())
+            .collect()
+    };
+    let before = workers(running);
+    let after = workers(new);
+
+    let mut changes = Vec::new();
+    for worker in &after {
+        match before.iter().find(|old| old.id == worker.id) {
+            None => changes.push(ConfigChange {
+                change: format!("add worker {}", worker.id),
+                panes: Vec::new(),
+            }),
+            Some(old) if !same(old, worker) => changes.push(ConfigChange {
+                change: format!("change worker {}", worker.id),
+                panes: vec![worker.id.clone()],
+            }),
+            Some(_) => {}
+        }
+    }
+    for worker in &before {
+        if !after.iter().any(|new| new.id == worker.id) {
+            changes.push(ConfigChange {
+                change: format!("remove worker {}", worker.id),
+                panes: vec![worker.id.clone()],
+            });
+        }
+    }
+
+    let strip_workers = |config: &HiveConfig| -> Vec<(String, Option<String>)> {
+        config
+            .windows
+            .iter()
+            .map(|window| (window.name.clone(), window.layout.clone()))
+            .collect()
+    };
+    if strip_workers(running) != strip_workers(new) {
+        changes.push(ConfigChange {
+            change: "windows".to_string(),
+            panes: Vec::new(),
+        });
+    }
+    if running.architect.backend != new.architect.backend {
+        changes.push(ConfigChange {
+            change: format!(
+                "architect backend {} -> {}",
+                crate::app::backend_label(running.architect.backend),
+                crate::app::backend_label(new.architect.backend)
+            ),
+            panes: vec!["architect".to_string()],
+        });
+    }
+    if running.workers.backend != new.workers.backend {
+        changes.push(ConfigChange {
+            change: format!(
+                "workers backend {} -> {}",
+                crate::app::backend_label(running.workers.backend),
+                crate::app::backend_label(new.workers.backend)
+            ),
+            panes: before.iter().map(|worker| worker.id.clone()).collect(),
+        });
+    }
+    if !same(&running.tasks, &new.tasks) {
+        changes.push(ConfigChange {
+            change: "tasks source".to_string(),
+            panes: Vec::new(),
+        });
+    }
+    if running.session != new.session {
+        changes.push(ConfigChange {
+            change: format!("session {} -> {}", running.session, new.session),
+            panes: Vec::new(),
+        });
+    }
+    changes
+}
+
 fn resize_pane(state: &mut ServerState, pane: PaneSize) {
     if let Some(target) = state.panes.iter_mut().find(|p| p.id == pane.pane_id) {
         let _ = target.master.resize(portable_pty::PtySize {
@@ -501,10 +1567,9 @@ fn resize_pane(state: &mut ServerState, pane: PaneSize) {
 fn spawn_workspace_panes(
     config: &WorkspaceConfig,
     workspace_dir: &Path,
-    workers: &[crate::workspace::RuntimeWorker],
+    workers: &[RuntimeWorker],
 ) -> Result<(Vec<Pane>, Vec<AppWindow>)> {
     let mut panes = Vec::new();
-    let mut windows = Vec::new();
 
     // Architect pane
     let architect_message = format!(
@@ -524,7 +1589,7 @@ fn spawn_workspace_panes(
         pane_type: PaneType::Architect,
         master: arch_master,
         child: arch_child,
-        writer: arch_writer,
+        input: PaneInput::spawn(arch_writer, driver_for(config.architect.backend)),
         output_buffer: crate::pty::output::OutputBuffer::new(24, 80, 2000),
         raw_history: std::collections::VecDeque::new(),
         raw_history_max: 200_000,
@@ -534,98 +1599,129 @@ fn spawn_workspace_panes(
         group: None,
         visible: true,
         backend: config.architect.backend,
-    });
-
-    windows.push(AppWindow {
-        name: "Architect".to_string(),
-        layout: LayoutKind::EvenHorizontal,
-        pane_indices: vec![0],
+        last_output: Instant::now(),
+        exited_at: None,
+        startup_message: architect_message,
+        skip_permissions: false,
+        generation: 0,
+        started_at: Instant::now(),
+        restarts: 0,
+        restart_at: None,
+        agent_state: AgentState::Busy,
+        last_typed: Instant::now(),
     });
 
     // Worker panes
-    let mut worker_pane_indices = Vec::new();
-
     for worker in workers {
         // Run setup commands in worker's directory
         for cmd in &config.workers.setup {
             shell::run_shell_command(cmd, &worker.working_dir)?;
         }
+        panes.push(spawn_worker_pane(config, workspace_dir, worker)?);
+    }
 
-        let lane_role_path = workspace_dir
-            .join("lanes")
-            .join(&worker.lane)
-            .join("WORKER.md");
-        let startup_message = format!(
-            "Read {}. Your lane is '{}'. Check {}/tasks.yaml for your tasks.",
-            lane_role_path.display(),
-            worker.lane,
-            workspace_dir.display()
-        );
+    let windows = workspace_windows(&panes);
+    Ok((panes, windows))
+}
 
-        // Group by project
-        let group = worker
-            .project_path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .map(|s| s.to_string());
-
-        let (master, child, writer) = spawn_agent(
-            config.workers.backend,
-            &startup_message,
-            &worker.working_dir,
-            config.workers.skip_permissions,
-        )?;
-
-        let pane = Pane {
-            id: worker.id.clone(),
-            pane_type: PaneType::Worker {
-                lane: worker.lane.clone(),
-            },
-            master,
-            child,
-            writer,
-            output_buffer: crate::pty::output::OutputBuffer::new(24, 80, 2000),
-            raw_history: std::collections::VecDeque::new(),
-            raw_history_max: 200_000,
-            lane: Some(worker.lane.clone()),
-            working_dir: worker.working_dir.clone(),
-            branch: None,
-            group,
-            visible: true,
-            backend: config.workers.backend,
-        };
+/// Start a workspace worker's agent in its directory. Its setup commands
+/// must already have run.
+fn spawn_worker_pane(
+    config: &WorkspaceConfig,
+    workspace_dir: &Path,
+    worker: &RuntimeWorker,
+) -> Result<Pane> {
+    let lane_role_path = workspace_dir
+        .join("lanes")
+        .join(&worker.lane)
+        .join("WORKER.md");
+    let tasks_location = match config.tasks.as_ref().map(|tasks| &tasks.source) {
+        Some(TaskSource::Github) => "the GitHub project (`hive task list`)".to_string(),
+        _ => config.tasks_file(workspace_dir).display().to_string(),
+    };
+    let startup_message = format!(
+        "Read {}. Your lane is '{}'. Check {} for your tasks.",
+        lane_role_path.display(),
+        worker.lane,
+        tasks_location
+    );
 
-        panes.push(pane);
-        worker_pane_indices.push(panes.len() - 1);
-    }
+    // Group by project
+    let group = worker
+        .project_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .map(|s| s.to_string());
 
-    windows.push(AppWindow {
-        name: "Workers".to_string(),
-        layout: LayoutKind::EvenHorizontal,
-        pane_indices: worker_pane_indices,
-    });
+    let (master, child, writer) = spawn_agent(
+        config.workers.backend,
+        &startup_message,
+        &worker.working_dir,
+        config.workers.skip_permissions,
+    )?;
 
-    Ok((panes, windows))
+    Ok(Pane {
+        id: worker.id.clone(),
+        pane_type: PaneType::Worker {
+            lane: worker.lane.clone(),
+        },
+        master,
+        child,
+        input: PaneInput::spawn(writer, driver_for(config.workers.backend)),
+        output_buffer: crate::pty::output::OutputBuffer::new(24, 80, 2000),
+        raw_history: std::collections::VecDeque::new(),
+        raw_history_max: 200_000,
+        lane: Some(worker.lane.clone()),
+        working_dir: worker.working_dir.clone(),
+        branch: None,
+        group,
+        visible: true,
+        backend: config.workers.backend,
+        last_output: Instant::now(),
+        exited_at: None,
+        startup_message,
+        skip_permissions: config.workers.skip_permissions,
+        generation: 0,
+        started_at: Instant::now(),
+        restarts: 0,
+        restart_at: None,
+        agent_state: AgentState::Busy,
+        last_typed: Instant::now(),
+    })
+}
+
+/// A workspace's windows: the architect, then every worker
+fn workspace_windows(panes: &[Pane]) -> Vec<AppWindow> {
+    let (architect, workers): (Vec<usize>, Vec<usize>) =
+        (0..panes.len()).partition(|&index| matches!(panes[index].pane_type, PaneType::Architect));
+    vec![
+        AppWindow {
+            name: "Architect".to_string(),
+            layout: LayoutKind::EvenHorizontal,
+            pane_indices: architect,
+        },
+        AppWindow {
+            name: "Workers".to_string(),
+            layout: LayoutKind::EvenHorizontal,
+            pane_indices: workers,
+        },
+    ]
+}
+
+fn compat_worker(worker: &RuntimeWorker) -> crate::config::WorkerConfig {
+    crate::config::WorkerConfig {
+        id: worker.id.clone(),
+        dir: Some(worker.working_dir.to_string_lossy().to_string()),
+        lane: Some(worker.lane.clone()),
+        branch: None,
+    }
 }
 
 /// Create a compatibility HiveConfig from WorkspaceConfig
-fn create_compat_config(
-    config: &WorkspaceConfig,
-    workers: &[crate::workspace::RuntimeWorker],
-) -> HiveConfig {
-    use crate::config::{
-        ArchitectConfig, TaskSource, TasksConfig, WindowConfig, WorkerConfig, WorkersConfig,
-    };
+fn create_compat_config(config: &WorkspaceConfig, workers: &[RuntimeWorker]) -> HiveConfig {
+    use crate::config::{ArchitectConfig, TasksConfig, WindowConfig, WorkerConfig, WorkersConfig};
 
-    let worker_configs: Vec<WorkerConfig> = workers
-        .iter()
-        .map(|w| WorkerConfig {
-            id: w.id.clone(),
-            dir: Some(w.working_dir.to_string_lossy().to_string()),
-            lane: Some(w.lane.clone()),
-            branch: None,
-        })
-        .collect();
+    let worker_configs: Vec<WorkerConfig> = workers.iter().map(compat_worker).collect();
 
     HiveConfig {
         architect: ArchitectConfig {
@@ -638,7 +1734,7 @@ fn create_compat_config(
             symlink: config.workers.symlink.clone(),
         },
         session: config.name.clone(),
-        tasks: TasksConfig {
+        tasks: config.tasks.clone().unwrap_or(TasksConfig {
             source: TaskSource::Yaml,
             file: Some("tasks.yaml".to_string()),
             github_org: None,
@@ -646,16 +1742,17 @@ fn create_compat_config(
             github_project_id: None,
             github_status_field_id: None,
             github_lane_field_id: None,
-        },
+            github_poll_secs: None,
+        }),
         windows: vec![WindowConfig {
             name: "Workers".to_string(),
             layout: Some("even-horizontal".to_string()),
             workers: worker_configs,
         }],
         setup: None,
-        messages: None,
+        messages: config.messages.clone(),
         worker_instructions: None,
-        workflow: crate::config::WorkflowConfig::default(),
+        workflow: config.workflow.clone(),
     }
 }
 
@@ -682,7 +1779,7 @@ fn spawn_panes(config: &HiveConfig, project_dir: &Path) -> Result<(Vec<Pane>, Ve
         pane_type: PaneType::Architect,
         master: arch_master,
         child: arch_child,
-        writer: arch_writer,
+        input: PaneInput::spawn(arch_writer, driver_for(config.architect.backend)),
         output_buffer: crate::pty::output::OutputBuffer::new(24, 80, 2000),
         raw_history: std::collections::VecDeque::new(),
         raw_history_max: 200_000,
@@ -692,6 +1789,16 @@ fn spawn_panes(config: &HiveConfig, project_dir: &Path) -> Result<(Vec<Pane>, Ve
         group: None,
         visible: true,
         backend: config.architect.backend,
+        last_output: Instant::now(),
+        exited_at: None,
+        startup_message: ARCHITECT_MESSAGE.to_string(),
+        skip_permissions: false,
+        generation: 0,
+        started_at: Instant::now(),
+        restarts: 0,
+        restart_at: None,
+        agent_state: AgentState::Busy,
+        last_typed: Instant::now(),
     });
 
     let architect_idx = 0;
@@ -722,7 +1829,7 @@ fn spawn_panes(config: &HiveConfig, project_dir: &Path) -> Result<(Vec<Pane>, Ve
                 pane_type: PaneType::Worker { lane: lane.clone() },
                 master,
                 child,
-                writer,
+                input: PaneInput::spawn(writer, driver_for(config.workers.backend)),
                 output_buffer: crate::pty::output::OutputBuffer::new(24, 80, 2000),
                 raw_history: std::collections::VecDeque::new(),
                 raw_history_max: 200_000,
@@ -732,6 +1839,16 @@ fn spawn_panes(config: &HiveConfig, project_dir: &Path) -> Result<(Vec<Pane>, Ve
                 group,
                 visible: true,
                 backend: config.workers.backend,
+                last_output: Instant::now(),
+                exited_at: None,
+                startup_message,
+                skip_permissions: config.workers.skip_permissions,
+                generation: 0,
+                started_at: Instant::now(),
+                restarts: 0,
+                restart_at: None,
+                agent_state: AgentState::Busy,
+                last_typed: Instant::now(),
             };
 
             panes.push(pane);
@@ -786,7 +1903,35 @@ fn group_name_for_dir(working_dir: &Path, project_dir: &Path) -> Option<String>
     }
 }
 
-fn nudge_workers(state: &mut ServerState, specific_worker: Option<&str>) -> Result<Vec<String>> {
+/// Lanes whose worker may have new work: those named by an event that frees
+/// or adds work, plus any lane whose claimable count went up (e.g. a task in
+/// another lane finished and unblocked one here).
+fn lanes_to_nudge(
+    events: &[TaskEvent],
+    before: &HashMap<String, TaskCounts>,
+    after: &HashMap<String, TaskCounts>,
+) -> HashSet<String> {
+    let mut lanes: HashSet<String> = events
+        .iter()
+        .filter(|event| event.nudges_lane())
+        .map(|event| event.lane().to_string())
+        .collect();
+    for (lane, counts) in after {
+        let previous = before.get(lane).map(TaskCounts::claimable).unwrap_or(0);
+        if counts.claimable() > previous {
+            lanes.insert(lane.clone());
+        }
+    }
+    lanes
+}
+
+/// Nudge idle workers with claimable tasks, or just `specific_worker`.
+/// `lanes` limits automatic nudges to the workers of those lanes.
+fn nudge_workers(
+    state: &mut ServerState,
+    specific_worker: Option<&str>,
+    lanes: Option<&HashSet<String>>,
+) -> Result<Vec<String>> {
     let mut nudged = Vec::new();
 
     log_line(
@@ -805,51 +1950,54 @@ fn nudge_workers(state: &mut ServerState, specific_worker: Option<&str>) -> Resu
                 continue;
             }
         }
+        if lanes.is_some_and(|lanes| !lanes.contains(&lane)) {
+            continue;
+        }
 
         let counts = state.task_counts.get(&lane).copied().unwrap_or_default();
+        // Nothing to claim if every claimable task is assigned to another worker
+        let next_task = state.next_tasks.get(&pane.id);
 
-        // For automatic nudges (all workers): only nudge if backlog AND not busy
-        // For manual nudges (specific worker): nudge if backlog, even if busy
+        // For automatic nudges (all workers): only nudge if claimable backlog AND
+        // the agent is idle at its prompt with no task of its own in progress,
+        // e.g. one waiting on an answer to its question
+        // For manual nudges (specific worker): nudge if claimable backlog, even if busy
         let should_nudge = if specific_worker.is_some() {
-            counts.backlog > 0
+            next_task.is_some()
         } else {
-            counts.backlog > 0 && counts.in_progress == 0
+            next_task.is_some()
+                && pane.agent_state == AgentState::Idle
+                && !holds_task(state.last_tasks.as_ref(), &pane.id)
         };
 
-        log_line(&state.log_path, &format!("nudge-check worker={} lane={} backlog={} in_progress={} should_nudge={} backend={:?}",
-            pane.id, lane, counts.backlog, counts.in_progress, should_nudge, pane.backend));
+        log_line(&state.log_path, &format!("nudge-check worker={} lane={} backlog={} blocked={} in_progress={} state={} should_nudge={} backend={:?}",
+            pane.id, lane, counts.backlog, counts.blocked, counts.in_progress, pane.agent_state.label(), should_nudge, pane.backend));
 
         if should_nudge {
-            let message = build_nudge_message(&state.config, &lane, counts.backlog, &pane.branch);
-
-            // For TUI apps like Codex/Claude, send message character by character
-            // to mimic actual typing. TUI apps process keystrokes one at a time
-            // and may not handle bulk input correctly.
-            //
-            // NOTE: If this still doesn't work, consider:
-            // - Codex: `codex exec resume --last "nudge message"`
-            // See: https://developers.openai.com/codex/cli/reference/
-
-            // Send each character individually, like actual typing
-            for byte in message.bytes() {
-                crate::pty::send_bytes(&mut pane.writer, &[byte])?;
-                // Small delay between characters to let TUI process
-                std::thread::sleep(std::time::Duration::from_millis(2));
-            }
-
-            // Longer delay before Enter to let TUI fully process
-            std::thread::sleep(std::time::Duration::from_millis(50));
+            let message = build_nudge_message(
+                &state.config,
+                &lane,
+                counts.claimable(),
+                next_task,
+                &pane.branch,
+            );
 
-            // Send Enter to submit (CR is what terminals send for Enter)
-            crate::pty::send_bytes(&mut pane.writer, b"\r")?;
+            if let Err(e) = type_message(pane, &message) {
+                log_line(
+                    &state.log_path,
+                    &format!("nudge-failed worker={}: {}", pane.id, e),
+                );
+                continue;
+            }
 
             log_line(
                 &state.log_path,
                 &format!(
-                    "nudge-sent worker={} backend={:?} message_len={} (char-by-char)",
+                    "nudge-sent worker={} backend={:?} message_len={} input={}",
                     pane.id,
                     pane.backend,
-                    message.len()
+                    message.len(),
+                    pane.input.driver()
                 ),
             );
 
@@ -860,18 +2008,128 @@ fn nudge_workers(state: &mut ServerState, specific_worker: Option<&str>) -> Resu
     Ok(nudged)
 }
 
-fn refresh_task_counts(state: &mut ServerState) -> Result<()> {
-    let Some(tasks_file) = &state.tasks_file else {
-        log_line(&state.log_path, "refresh_task_counts: no tasks_file");
-        return Ok(());
+/// Whether `worker_id` has claimed a task that's still in progress
+fn holds_task(tasks: Option<&TasksFile>, worker_id: &str) -> bool {
+    tasks.is_some_and(|tasks| {
+        tasks.lanes().iter().any(|(_, lane)| {
+            lane.in_progress
+                .iter()
+                .any(|task| task.claimed_by.as_deref() == Some(worker_id))
+        })
+    })
+}
+
+/// Type a message into a pane and submit it. The pane's input driver types
+/// it on the pane's writer thread (see `crate::pty::driver_for`), so this
+/// returns right away.
+///
+/// NOTE: If a backend still doesn't take typed messages, consider:
+/// - Codex: `codex exec resume --last "nudge message"`
+///
+/// See: https://developers.openai.com/codex/cli/reference/
+fn type_message(pane: &mut Pane, message: &str) -> Result<()> {
+    pane.input.send_message(message)?;
+    // Until its output says otherwise, the agent is taking the message in
+    pane.last_typed = Instant::now();
+    pane.agent_state = AgentState::Busy;
+    Ok(())
+}
+
+/// Type new questions into the architect pane and new answers into the
+/// pane of the worker that asked
+fn relay_questions(state: &mut ServerState, events: &[TaskEvent], tasks: &TasksFile) {
+    for event in events {
+        let Some(task) = tasks
+            .find_task(event.id(), Some(event.lane()))
+            .ok()
+            .and_then(|location| tasks.task(&location))
+        else {
+            continue;
+        };
+        let lane = event.lane();
+        let lane_worker = state
+            .panes
+            .iter()
+            .position(|pane| matches!(&pane.pane_type, PaneType::Worker { lane: l } if l == lane));
+        let worker = task
+            .claimed_by
+            .clone()
+            .or_else(|| lane_worker.map(|index| state.panes[index].id.clone()))
+            .unwrap_or_else(|| lane.to_string());
+
+        let (target, message) = match event {
+            TaskEvent::QuestionRaised { .. } if task.awaiting_answer() => (
+                state
+                    .panes
+                    .iter()
+                    .position(|pane| matches!(pane.pane_type, PaneType::Architect)),
+                build_question_message(&state.config, &worker, lane, task),
+            ),
+            TaskEvent::QuestionAnswered { .. } => (
+                state
+                    .panes
+                    .iter()
+                    .position(|pane| pane.id == worker)
+                    .or(lane_worker),
+                build_answer_message(&state.config, lane, task),
+            ),
+            _ => continue,
+        };
+
+        let Some(index) = target else {
+            log_line(
+                &state.log_path,
+                &format!("relay: no pane to receive {}", event),
+            );
+            continue;
+        };
+        let pane = &mut state.panes[index];
+        match type_message(pane, &message) {
+            Ok(()) => log_line(&state.log_path, &format!("relay: {} -> {}", event, pane.id)),
+            Err(e) => log_line(
+                &state.log_path,
+                &format!("relay: failed sending {} to {}: {}", event, pane.id, e),
+            ),
+        }
+    }
+}
+
+/// The pane working on a task: the worker named by `claimed_by`, else the lane's worker
+fn task_pane(panes: &[Pane], lane: &str, claimed_by: Option<&str>) -> Option<usize> {
+    claimed_by
+        .and_then(|worker| panes.iter().position(|pane| pane.id == worker))
+        .or_else(|| {
+            panes.iter().position(
+                |pane| matches!(&pane.pane_type, PaneType::Worker { lane: l } if l == lane),
+            )
+        })
+}
+
+/// Reload the task source into counts and next tasks, returning what was loaded
+fn refresh_task_counts(state: &mut ServerState) -> Result<Option<TasksFile>> {
+    let Some(task_backend) = &state.task_backend else {
+        log_line(&state.log_path, "refresh_task_counts: no task source");
+        return Ok(None);
     };
 
     log_line(
         &state.log_path,
-        &format!("refresh_task_counts: loading {}", tasks_file.display()),
+        &format!("refresh_task_counts: loading {}", task_backend.describe()),
     );
 
-    let tasks = match load_tasks(tasks_file) {
+    state.task_problems = task_backend
+        .lint(&configured_lanes(state))
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    if !state.task_problems.is_empty() {
+        log_line(
+            &state.log_path,
+            &format!("refresh_task_counts: problems={:?}", state.task_problems),
+        );
+    }
+
+    let tasks = match task_backend.load() {
         Ok(t) => t,
         Err(e) => {
             log_line(
@@ -891,10 +2149,14 @@ fn refresh_task_counts(state: &mut ServerState) -> Result<()> {
     );
 
     let mut counts = HashMap::new();
+    let mut next_tasks = HashMap::new();
 
     for window in &state.config.windows {
         for worker in &window.workers {
             let lane = worker.lane.clone().unwrap_or_else(|| worker.id.clone());
+            if let Some(task) = tasks.next_task_for(&lane, &worker.id) {
+                next_tasks.insert(worker.id.clone(), task.clone());
+            }
             let lane_counts = counts_for_lane(&tasks, &lane);
             log_line(
                 &state.log_path,
@@ -908,12 +2170,38 @@ fn refresh_task_counts(state: &mut ServerState) -> Result<()> {
     }
 
     state.task_counts = counts;
-    Ok(())
+    state.next_tasks = next_tasks;
+    record_history(state, &tasks);
+    Ok(Some(tasks))
+}
+
+/// Lanes of the configured workers, in config order
+fn configured_lanes(state: &ServerState) -> Vec<String> {
+    state
+        .config
+        .windows
+        .iter()
+        .flat_map(|window| &window.workers)
+        .map(|worker| worker.lane.clone().unwrap_or_else(|| worker.id.clone()))
+        .collect()
+}
+
+/// Append any task state changes since the last load to the history log
+fn record_history(state: &mut ServerState, tasks: &TasksFile) {
+    if let Some(previous) = &state.last_tasks {
+        let entries = transitions(previous, tasks, &timestamp_now());
+        if !entries.is_empty() {
+            if let Err(e) = append_history(&state.history_path, &entries) {
+                log_line(&state.log_path, &format!("history: {:#}", e));
+            }
+        }
+    }
+    state.last_tasks = Some(tasks.clone());
 }
 
 fn broadcast_state(state: &ServerState, clients: &mut Vec<ClientHandle>) {
     let message = ServerMessage::State {
-        state: build_state(state),
+        state: Box::new(build_state(state)),
     };
     broadcast(clients, message);
 }
@@ -944,6 +2232,7 @@ fn build_state(state: &ServerState) -> AppState {
                 branch: pane.branch.clone(),
                 group: pane.group.clone(),
                 visible: pane.visible,
+                agent_state: pane.agent_state,
             })
             .collect(),
         windows: state
@@ -956,6 +2245,16 @@ fn build_state(state: &ServerState) -> AppState {
             })
             .collect(),
         task_counts: state.task_counts.clone(),
+        task_problems: state.task_problems.clone(),
+        stale_claims: state.stale_claims.clone(),
+        tasks: state.last_tasks.clone(),
+        archived_tasks: state
+            .last_tasks
+            .as_ref()
+            .map(|tasks| tasks.archived.clone())
+            .unwrap_or_default(),
+        config_changes: state.config_changes.clone(),
+        config_error: state.config_error.clone(),
         architect_left: state.architect_left,
         min_pane_width: state.min_pane_width,
         min_pane_height: state.min_pane_height,
@@ -1097,3 +2396,75 @@ fn log_line(path: &Path, line: &str) {
         let _ = writeln!(file, "{}", line);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_workspace_changes_list_lanes_and_affected_panes() {
+        let project = |lanes: &[&str]| crate::workspace::WorkspaceProject {
+            path: PathBuf::from("/code/api"),
+            workers: lanes.len(),
+            lanes: lanes.iter().map(|lane| lane.to_string()).collect(),
+        };
+        let running = WorkspaceConfig {
+            projects: vec![project(&["core", "auth", "docs"])],
+            ..Default::default()
+        };
+        let mut new = running.clone();
+        new.projects = vec![project(&["core", "auth", "billing"])];
+        new.workers.backend = crate::config::Backend::Codex;
+        new.layout.min_pane_width = 120;
+        new.stale_claims.requeue_after = Some("2h".to_string());
+
+        let workers = vec![
+            ("worker-1".to_string(), "api/core".to_string()),
+            ("worker-2".to_string(), "api/auth".to_string()),
+            ("worker-4".to_string(), "api/docs".to_string()),
+        ];
+        let changes = workspace_changes(&running, &new, Path::new("/ws"), &workers);
+        let summary: Vec<(&str, Vec<&str>)> = changes
+            .iter()
+            .map(|change| {
+                let panes = change.panes.iter().map(String::as_str).collect();
+                (change.change.as_str(), panes)
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("add lane api/billing in /code/api", vec![]),
+                ("remove lane api/docs", vec!["worker-4"]),
+                (
+                    "workers backend claude -> codex",
+                    vec!["worker-1", "worker-2", "worker-4"]
+                ),
+                ("stale_claims", vec![]),
+            ]
+        );
+
+        assert!(workspace_changes(&running, &running, Path::new("/ws"), &workers).is_empty());
+    }
+
+    #[test]
+    fn test_holds_task_counts_in_progress_claims_only() {
+        let tasks: TasksFile = serde_yaml::from_str(
+            r#"
+backend:
+  fixes:
+    backlog:
+      - id: next
+        assignee: worker-2
+    in_progress:
+      - id: waiting
+        claimed_by: worker-1
+        question: Which endpoint?
+"#,
+        )
+        .unwrap();
+        assert!(holds_task(Some(&tasks), "worker-1"));
+        assert!(!holds_task(Some(&tasks), "worker-2"));
+        assert!(!holds_task(None, "worker-1"));
+    }
+}
@@ -0,0 +1,89 @@
+use std::path::Path;
+
+use chrono::DurationRound;
+
+use crate::tasks::{Schedule, TaskTemplate};
+use crate::workspace::{RecurringTask, WorkspaceConfig};
+
+use super::{log_line, ServerState};
+
+/// A `recurring:` entry with its schedule parsed and its template looked up
+pub(super) struct Recurring {
+    entry: RecurringTask,
+    schedule: Schedule,
+    template: TaskTemplate,
+}
+
+/// Parse the workspace's `recurring:` entries, logging and skipping any with
+/// a bad schedule or an unknown template
+pub(super) fn recurring_tasks(config: &WorkspaceConfig, log_path: &Path) -> Vec<Recurring> {
+    let mut recurring = Vec::new();
+    for entry in &config.recurring {
+        let schedule = match Schedule::parse(&entry.schedule) {
+            Ok(schedule) => schedule,
+            Err(e) => {
+                log_line(log_path, &format!("recurring {}: {:#}", entry.name, e));
+                continue;
+            }
+        };
+        let Some(template) = config.task_templates.get(&entry.template) else {
+            log_line(
+                log_path,
+                &format!(
+                    "recurring {}: no task template named '{}'",
+                    entry.name, entry.template
+                ),
+            );
+            continue;
+        };
+        recurring.push(Recurring {
+            entry: entry.clone(),
+            schedule,
+            template: template.clone(),
+        });
+    }
+    recurring
+}
+
+/// Start of the current local minute, which recurring schedules are matched against
+pub(super) fn current_minute() -> chrono::DateTime<chrono::Local> {
+    let now = chrono::Local::now();
+    now.duration_trunc(chrono::Duration::minutes(1))
+        .unwrap_or(now)
+}
+
+/// Add a task for each recurring entry that came due after `after`, up to
+/// `until`, unless the one it added last time is still open. The watcher
+/// picks up the new tasks and nudges their lanes.
+pub(super) fn add_recurring_tasks(
+    state: &ServerState,
+    after: &chrono::DateTime<chrono::Local>,
+    until: &chrono::DateTime<chrono::Local>,
+) {
+    let Some(task_backend) = &state.task_backend else {
+        return;
+    };
+    for recurring in &state.recurring {
+        if !recurring.schedule.due_between(after, until) {
+            continue;
+        }
+        let entry = &recurring.entry;
+        let id = format!("{}-{}", entry.name, until.format("%Y%m%d-%H%M"));
+        let result = recurring
+            .template
+            .expand(&id, &entry.vars)
+            .and_then(|mut task| {
+                task.recurring = Some(entry.name.clone());
+                task_backend.add_unless_open(&entry.lane, task)
+            });
+        let outcome = match result {
+            Ok(true) => format!("added {} to {}", id, entry.lane),
+            Ok(false) => format!("skipped, still open in {}", entry.lane),
+            Err(e) => format!("{:#}", e),
+        };
+        log_line(
+            &state.log_path,
+            &format!("recurring {}: {}", entry.name, outcome),
+        );
+    }
+}
@@ -0,0 +1,135 @@
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::tasks::parse_duration;
+use crate::workspace::{RestartMode, WorkspaceConfig};
+
+use super::{log_line, restart_pane, ClientHandle, ServerState};
+
+/// The workspace's `restart_policy:` with its durations parsed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct RestartPolicy {
+    mode: RestartMode,
+    backoff: Duration,
+    max_backoff: Duration,
+    max_restarts: Option<u32>,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            mode: RestartMode::Never,
+            backoff: Duration::from_secs(30),
+            max_backoff: Duration::from_secs(10 * 60),
+            max_restarts: None,
+        }
+    }
+}
+
+impl RestartPolicy {
+    /// Wait before restarting a pane that has already been restarted
+    /// `restarts` times in a row
+    fn delay(&self, restarts: u32) -> Duration {
+        self.backoff
+            .saturating_mul(2u32.saturating_pow(restarts))
+            .min(self.max_backoff)
+    }
+}
+
+/// Parse the workspace's restart policy, falling back to the default backoff
+pub(super) fn restart_policy(config: &WorkspaceConfig, log_path: &Path) -> RestartPolicy {
+    let parse = |value: &str| {
+        parse_duration(value)
+            .and_then(|age| Ok(age.to_std()?))
+            .map_err(|e| log_line(log_path, &format!("restart_policy: {:#}", e)))
+            .ok()
+    };
+    let defaults = RestartPolicy::default();
+    let policy = &config.restart_policy;
+    RestartPolicy {
+        mode: policy.mode,
+        backoff: parse(&policy.backoff).unwrap_or(defaults.backoff),
+        max_backoff: parse(&policy.max_backoff).unwrap_or(defaults.max_backoff),
+        max_restarts: policy.max_restarts,
+    }
+}
+
+/// Schedule restarts for exited panes the restart policy covers, and restart
+/// those whose backoff has passed
+pub(super) fn auto_restart(state: &mut ServerState, clients: &mut Vec<ClientHandle>) {
+    let policy = state.restart_policy;
+    if policy.mode == RestartMode::Never {
+        return;
+    }
+    let now = Instant::now();
+    let mut due = Vec::new();
+    for pane in &mut state.panes {
+        let Some(exited_at) = pane.exited_at else {
+            continue;
+        };
+        if let Some(restart_at) = pane.restart_at {
+            if restart_at <= now {
+                due.push(pane.id.clone());
+            }
+            continue;
+        }
+        // Not reaped yet; the exit status is needed for on-failure
+        let success = match pane.child.try_wait() {
+            Ok(Some(status)) => status.success(),
+            Ok(None) => continue,
+            Err(_) => false,
+        };
+        if policy.mode == RestartMode::OnFailure && success {
+            continue;
+        }
+        // A pane that ran for a while before exiting starts the backoff over
+        if exited_at.duration_since(pane.started_at) >= policy.max_backoff {
+            pane.restarts = 0;
+        }
+        if policy.max_restarts.is_some_and(|max| pane.restarts >= max) {
+            continue;
+        }
+        let delay = policy.delay(pane.restarts);
+        pane.restart_at = Some(now + delay);
+        log_line(
+            &state.log_path,
+            &format!("restart-scheduled {} in {}s", pane.id, delay.as_secs()),
+        );
+    }
+
+    for pane_id in due {
+        if let Err(err) = restart_pane(state, clients, &pane_id, true) {
+            log_line(
+                &state.log_path,
+                &format!("restart-error {} {:#}", pane_id, err),
+            );
+            if let Some(pane) = state.panes.iter_mut().find(|p| p.id == pane_id) {
+                pane.restarts += 1;
+                pane.restart_at = None;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restart_policy_backs_off_up_to_max() {
+        let config = WorkspaceConfig {
+            restart_policy: serde_yaml::from_str("mode: on-failure\nbackoff: 10s\nmax_backoff: 1m")
+                .unwrap(),
+            ..Default::default()
+        };
+        let policy = restart_policy(&config, Path::new("/dev/null"));
+        assert_eq!(policy.mode, RestartMode::OnFailure);
+        assert_eq!(policy.max_restarts, None);
+        let delays: Vec<u64> = (0..4).map(|n| policy.delay(n).as_secs()).collect();
+        assert_eq!(delays, vec![10, 20, 40, 60]);
+        assert_eq!(policy.delay(u32::MAX), Duration::from_secs(60));
+
+        let default = restart_policy(&WorkspaceConfig::default(), Path::new("/dev/null"));
+        assert_eq!(default, RestartPolicy::default());
+    }
+}
@@ -0,0 +1,134 @@
+use std::path::Path;
+use std::time::Duration;
+
+use crate::app::types::PaneType;
+use crate::tasks::{find_stale_claims, parse_duration, PaneActivity, StaleClaim, StalePolicy};
+use crate::workspace::WorkspaceConfig;
+
+use super::{log_line, ServerState};
+
+/// How often claims are checked for dead or quiet workers
+pub(super) const STALE_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Parse the workspace's stale-claim timeouts, falling back to the defaults
+pub(super) fn stale_policy(config: &WorkspaceConfig, log_path: &Path) -> StalePolicy {
+    let parse = |value: &str| {
+        parse_duration(value)
+            .and_then(|age| Ok(age.to_std()?))
+            .map_err(|e| log_line(log_path, &format!("stale_claims: {:#}", e)))
+            .ok()
+    };
+    let defaults = StalePolicy::default();
+    StalePolicy {
+        after: parse(&config.stale_claims.after).unwrap_or(defaults.after),
+        requeue_after: config.stale_claims.requeue_after.as_deref().and_then(parse),
+    }
+}
+
+/// The claims due for requeueing that weren't already due on the last check.
+/// Each claim is only tried once, so a source that can't requeue isn't
+/// retried forever.
+fn claims_to_requeue<'a>(stale: &'a [StaleClaim], previous: &[StaleClaim]) -> Vec<&'a StaleClaim> {
+    stale
+        .iter()
+        .filter(|claim| {
+            claim.requeue
+                && !previous
+                    .iter()
+                    .any(|earlier| earlier.id == claim.id && earlier.requeue)
+        })
+        .collect()
+}
+
+/// Flag in-progress tasks whose worker pane exited or went quiet, and requeue
+/// those past the policy's timeout. Returns whether the flagged set changed.
+pub(super) fn check_stale_claims(state: &mut ServerState) -> bool {
+    let Some(tasks) = &state.last_tasks else {
+        return false;
+    };
+    let panes = &state.panes;
+    let stale = find_stale_claims(
+        tasks,
+        state.stale_policy,
+        chrono::Utc::now(),
+        |lane, task| {
+            // Claims by someone other than a worker pane (e.g. by hand) are left alone
+            let pane = match task.claimed_by.as_deref() {
+                Some(worker) => panes.iter().find(|pane| pane.id == worker)?,
+                None => panes.iter().find(
+                    |pane| matches!(&pane.pane_type, PaneType::Worker { lane: l } if l == lane),
+                )?,
+            };
+            let activity = PaneActivity {
+                exited_for: pane.exited_at.map(|at| at.elapsed()),
+                quiet_for: pane.last_output.elapsed(),
+            };
+            Some((pane.id.clone(), activity))
+        },
+    );
+
+    if let Some(task_backend) = &state.task_backend {
+        for claim in claims_to_requeue(&stale, &state.stale_claims) {
+            match task_backend.requeue(&claim.id, Some(&claim.lane), &claim.reason) {
+                Ok(_) => log_line(
+                    &state.log_path,
+                    &format!(
+                        "stale: requeued {} ({}) from {}: {}",
+                        claim.id, claim.lane, claim.worker, claim.reason
+                    ),
+                ),
+                Err(e) => log_line(
+                    &state.log_path,
+                    &format!("stale: failed requeueing {}: {:#}", claim.id, e),
+                ),
+            }
+        }
+    }
+
+    let changed = stale != state.stale_claims;
+    if changed {
+        log_line(&state.log_path, &format!("stale: claims={:?}", stale));
+    }
+    state.stale_claims = stale;
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn claim(id: &str, requeue: bool) -> StaleClaim {
+        StaleClaim {
+            lane: "backend".to_string(),
+            id: id.to_string(),
+            worker: "worker-1".to_string(),
+            reason: "pane exited".to_string(),
+            requeue,
+        }
+    }
+
+    #[test]
+    fn test_claims_are_requeued_once() {
+        let stale = vec![
+            claim("new", true),
+            claim("flagged", false),
+            claim("tried", true),
+        ];
+        let previous = vec![claim("tried", true)];
+        let ids: Vec<&str> = claims_to_requeue(&stale, &previous)
+            .iter()
+            .map(|claim| claim.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["new"]);
+    }
+
+    #[test]
+    fn test_flagged_claim_is_requeued_once_due() {
+        let previous = vec![claim("slow", false)];
+        let stale = vec![claim("slow", true)];
+        assert_eq!(claims_to_requeue(&stale, &previous).len(), 1);
+
+        // Still due on the next check, so it isn't tried again
+        assert!(claims_to_requeue(&stale, &stale).is_empty());
+    }
+}
@@ -0,0 +1,111 @@
+use std::sync::mpsc::Sender;
+use std::thread;
+
+use crate::app::build_verify_failed_message;
+use crate::tasks::{run_verification, TaskEvent, TasksFile, Verification};
+
+use super::{log_line, task_pane, type_message, ServerState};
+
+/// Outcome of a task's `verify` commands, sent back from the thread that ran them
+pub(super) struct VerifyResult {
+    lane: String,
+    id: String,
+    verification: Verification,
+}
+
+/// Run the `verify` commands of newly completed tasks on background threads,
+/// in the working directory of the worker that did them
+pub(super) fn start_verifications(
+    state: &mut ServerState,
+    events: &[TaskEvent],
+    tasks: &TasksFile,
+    verify_tx: &Sender<VerifyResult>,
+) {
+    for event in events {
+        let TaskEvent::TaskCompleted { lane, id } = event else {
+            continue;
+        };
+        let Some(task) = tasks
+            .find_task(id, Some(lane))
+            .ok()
+            .and_then(|location| tasks.task(&location))
+        else {
+            continue;
+        };
+        let commands = task.verify.clone().unwrap_or_default();
+        if commands.is_empty() || !state.verifying.insert(id.clone()) {
+            continue;
+        }
+        let cwd = task_pane(&state.panes, lane, task.claimed_by.as_deref())
+            .map(|index| state.panes[index].working_dir.clone())
+            .unwrap_or_else(|| state.project_dir.clone());
+        log_line(
+            &state.log_path,
+            &format!(
+                "verify: running {} command(s) for {} in {}",
+                commands.len(),
+                id,
+                cwd.display()
+            ),
+        );
+
+        let verify_tx = verify_tx.clone();
+        let (lane, id) = (lane.clone(), id.clone());
+        thread::spawn(move || {
+            let verification = run_verification(&commands, &cwd);
+            let _ = verify_tx.send(VerifyResult {
+                lane,
+                id,
+                verification,
+            });
+        });
+    }
+}
+
+/// Store a verification result. On failure the task is back in progress, so
+/// tell its worker, who would otherwise sit idle.
+pub(super) fn finish_verification(state: &mut ServerState, result: VerifyResult) {
+    state.verifying.remove(&result.id);
+    let passed = result.verification.passed;
+    log_line(
+        &state.log_path,
+        &format!(
+            "verify: {} {}",
+            result.id,
+            if passed { "passed" } else { "failed" }
+        ),
+    );
+    let Some(task_backend) = &state.task_backend else {
+        return;
+    };
+    let location =
+        match task_backend.record_verification(&result.id, Some(&result.lane), result.verification)
+        {
+            Ok(location) => location,
+            Err(e) => {
+                log_line(&state.log_path, &format!("verify: {:#}", e));
+                return;
+            }
+        };
+    if passed {
+        return;
+    }
+
+    let Ok(tasks) = task_backend.load() else {
+        return;
+    };
+    let Some(task) = tasks.task(&location) else {
+        return;
+    };
+    let Some(index) = task_pane(&state.panes, &location.lane, task.claimed_by.as_deref()) else {
+        return;
+    };
+    let message = build_verify_failed_message(&state.config, &location.lane, task);
+    let pane = &mut state.panes[index];
+    if let Err(e) = type_message(pane, &message) {
+        log_line(
+            &state.log_path,
+            &format!("verify: failed telling {}: {}", pane.id, e),
+        );
+    }
+}
@@ -0,0 +1,190 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::yaml::{
+    load_tasks, load_tasks_locked, parse_timestamp, save_tasks, update_tasks, Task, TasksFile,
+};
+
+/// Done tasks moved out of the tasks file, summarized so counts and
+/// dependencies still see them
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ArchivedTasks {
+    /// Archived done tasks per lane
+    pub done: HashMap<String, usize>,
+    /// Ids of every archived task
+    pub ids: HashSet<String>,
+}
+
+/// `tasks.archive/` next to the tasks file
+pub fn archive_dir(tasks_path: &Path) -> PathBuf {
+    tasks_path.with_file_name("tasks.archive")
+}
+
+/// Summarize every archive file. Files that don't parse are skipped.
+pub fn load_archive(tasks_path: &Path) -> ArchivedTasks {
+    let mut archived = ArchivedTasks::default();
+    for tasks in load_archive_files(tasks_path) {
+        for (lane, lane_tasks) in tasks.lanes() {
+            *archived.done.entry(lane).or_default() += lane_tasks.done.len();
+            archived
+                .ids
+                .extend(lane_tasks.done.iter().map(|task| task.id.clone()));
+        }
+    }
+    archived
+}
+
+/// Every archive file's tasks, oldest month first. Files that don't parse are skipped.
+pub fn load_archive_files(tasks_path: &Path) -> Vec<TasksFile> {
+    let Ok(entries) = std::fs::read_dir(archive_dir(tasks_path)) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("yaml"))
+        .collect();
+    paths.sort();
+    paths
+        .iter()
+        .filter_map(|path| load_tasks(path).ok())
+        .collect()
+}
+
+/// Move done tasks into `tasks.archive/<yyyy-mm>.yaml`, by the month they were
+/// completed. With `older_than`, only tasks whose `completed_at` is at least
+/// that old are moved. Returns how many tasks were archived.
+pub fn archive_done(
+    tasks_path: &Path,
+    older_than: Option<Duration>,
+    now: DateTime<Utc>,
+) -> Result<usize> {
+    // Check first so a no-op doesn't rewrite the file and wake the watcher
+    let current = load_tasks_locked(tasks_path)?;
+    let any_due = current.lanes().iter().any(|(_, lane_tasks)| {
+        lane_tasks
+            .done
+            .iter()
+            .any(|task| is_due(task, older_than, now))
+    });
+    if !any_due {
+        return Ok(0);
+    }
+
+    let dir = archive_dir(tasks_path);
+    update_tasks(tasks_path, |tasks| {
+        let mut by_month: BTreeMap<String, Vec<(String, Task)>> = BTreeMap::new();
+        let lanes: Vec<String> = tasks.lanes().into_iter().map(|(lane, _)| lane).collect();
+        for lane in lanes {
+            let Some(lane_tasks) = tasks.lane_mut(&lane) else {
+                continue;
+            };
+            let (due, keep): (Vec<Task>, Vec<Task>) = std::mem::take(&mut lane_tasks.done)
+                .into_iter()
+                .partition(|task| is_due(task, older_than, now));
+            lane_tasks.done = keep;
+            for task in due {
+                let completed = task.completed_at.as_deref().and_then(parse_timestamp);
+                let month = completed.unwrap_or(now).format("%Y-%m").to_string();
+                by_month
+                    .entry(month)
+                    .or_default()
+                    .push((lane.clone(), task));
+            }
+        }
+
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed creating {}", dir.display()))?;
+        let mut count = 0;
+        for (month, due) in by_month {
+            let path = dir.join(format!("{}.yaml", month));
+            let mut archive = if path.exists() {
+                load_tasks(&path)?
+            } else {
+                TasksFile::default()
+            };
+            for (lane, task) in due {
+                archive.ensure_lane_mut(&lane)?.done.push(task);
+                count += 1;
+            }
+            // Written before the tasks file, so a failure can duplicate but not lose tasks
+            save_tasks(&path, &archive)?;
+        }
+        Ok(count)
+    })
+}
+
+fn is_due(task: &Task, older_than: Option<Duration>, now: DateTime<Utc>) -> bool {
+    match older_than {
+        None => true,
+        Some(age) => task
+            .completed_at
+            .as_deref()
+            .and_then(parse_timestamp)
+            .is_some_and(|completed| completed <= now - age),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tasks::source::TaskBackend;
+    use crate::tasks::{counts_for_lane, YamlTasks};
+
+    #[test]
+    fn test_archive_moves_old_done_tasks_and_keeps_counts() {
+        let dir = std::env::temp_dir().join(format!("hive-archive-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("tasks.yaml");
+        std::fs::write(
+            &path,
+            r#"
+api:
+  backlog:
+    - id: follow-up
+      depends_on: [old-fix]
+  in_progress: []
+  done:
+    - id: old-fix
+      completed_at: "2024-04-20T10:00:00Z"
+      summary: Fixed it
+      pr_url: https://github.com/acme/app/pull/1
+      files_changed: [src/main.rs]
+    - id: recent-fix
+      completed_at: "2024-05-09T10:00:00Z"
+"#,
+        )
+        .unwrap();
+        let now = parse_timestamp("2024-05-10T00:00:00Z").unwrap();
+
+        let archived = archive_done(&path, Some(Duration::days(7)), now).unwrap();
+        assert_eq!(archived, 1);
+        assert_eq!(
+            archive_done(&path, Some(Duration::days(7)), now).unwrap(),
+            0
+        );
+
+        let month = load_tasks(&archive_dir(&path).join("2024-04.yaml")).unwrap();
+        let task = &month.lane("api").unwrap().done[0];
+        assert_eq!(task.id, "old-fix");
+        assert_eq!(task.summary.as_deref(), Some("Fixed it"));
+        assert_eq!(
+            task.files_changed.as_deref(),
+            Some(&["src/main.rs".to_string()][..])
+        );
+
+        let tasks = YamlTasks::new(path.clone()).load().unwrap();
+        assert_eq!(tasks.lane("api").unwrap().done.len(), 1);
+        let counts = counts_for_lane(&tasks, "api");
+        assert_eq!(counts.done, 2);
+        // Archived tasks still satisfy dependencies
+        assert_eq!(counts.claimable(), 1);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
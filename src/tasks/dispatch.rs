@@ -0,0 +1,220 @@
+use anyhow::Result;
+
+use super::yaml::{add_task, Task, TaskCounts, TasksFile};
+
+/// A `global_backlog` task moved into a lane's backlog, or routed to a lane
+/// it couldn't be added to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dispatched {
+    pub id: String,
+    pub lane: String,
+    /// Why the task stayed in `global_backlog`, e.g. the lane already has a
+    /// task with its id
+    pub held: Option<String>,
+}
+
+/// The project part of a lane name: `backend` for `backend/fixes`
+fn lane_project(lane: &str) -> &str {
+    lane.split('/').next().unwrap_or(lane)
+}
+
+/// Pick the lane for a global task. `lanes` are the configured lanes with
+/// their current counts, in config order.
+///
+/// An explicit `lane:` (or a label naming a lane) wins. Otherwise the task
+/// goes to the lane with the fewest backlog + in-progress tasks among those
+/// matching its `project:` (or labels naming a project), or among all lanes
+/// when it names no project. Ties go to the lane listed first.
+pub fn route_task(task: &Task, lanes: &[(String, TaskCounts)]) -> Option<String> {
+    let is_lane = |name: &str| lanes.iter().any(|(lane, _)| lane == name);
+    let labels = task.labels.as_deref().unwrap_or_default();
+
+    if let Some(lane) = &task.lane {
+        return is_lane(lane).then(|| lane.clone());
+    }
+    if let Some(lane) = labels.iter().find(|label| is_lane(label)) {
+        return Some(lane.clone());
+    }
+
+    let projects: Vec<&str> = task
+        .project
+        .iter()
+        .chain(labels)
+        .map(String::as_str)
+        .filter(|name| lanes.iter().any(|(lane, _)| lane_project(lane) == *name))
+        .collect();
+    if task.project.is_some() && projects.is_empty() {
+        return None;
+    }
+
+    lanes
+        .iter()
+        .enumerate()
+        .filter(|(_, (lane, _))| projects.is_empty() || projects.contains(&lane_project(lane)))
+        .min_by_key(|(index, (_, counts))| (counts.backlog + counts.in_progress, *index))
+        .map(|(_, (lane, _))| lane.clone())
+}
+
+/// Move every routable `global_backlog` task to the end of its lane's backlog.
+/// Tasks with no matching lane, or whose id is already taken in that lane,
+/// stay where they are. `lanes` is updated as tasks are placed so a batch
+/// spreads across equally loaded lanes.
+pub fn dispatch_global_backlog(
+    tasks: &mut TasksFile,
+    lanes: &mut [(String, TaskCounts)],
+) -> Result<Vec<Dispatched>> {
+    let Some(global) = tasks.global_backlog.take() else {
+        return Ok(Vec::new());
+    };
+
+    let mut dispatched = Vec::new();
+    let mut remaining = Vec::new();
+    for mut task in global {
+        let Some(lane) = route_task(&task, lanes) else {
+            remaining.push(task);
+            continue;
+        };
+        let id = task.id.clone();
+        // The lane is where it lives now, so the routing hint is spent,
+        // unless the task can't be added there and stays global
+        let hint = task.lane.take();
+        if let Err(e) = add_task(tasks, &lane, task.clone()) {
+            task.lane = hint;
+            remaining.push(task);
+            dispatched.push(Dispatched {
+                id,
+                lane,
+                held: Some(e.to_string()),
+            });
+            continue;
+        }
+        if let Some((_, counts)) = lanes.iter_mut().find(|(name, _)| *name == lane) {
+            counts.backlog += 1;
+        }
+        dispatched.push(Dispatched {
+            id,
+            lane,
+            held: None,
+        });
+    }
+
+    tasks.global_backlog = Some(remaining);
+    Ok(dispatched)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dispatch_routes_by_lane_label_and_load() {
+        let mut tasks: TasksFile = serde_yaml::from_str(
+            r#"
+global_backlog:
+  - id: pinned
+    lane: web
+  - id: labelled
+    labels: [urgent, backend/features]
+  - id: backend-1
+    project: backend
+  - id: backend-2
+    project: backend
+  - id: anywhere
+  - id: nowhere
+    project: ios
+backend:
+  fixes:
+    backlog:
+      - id: fix-1
+    in_progress:
+      - id: fix-2
+        claimed_by: fixes-worker
+  features:
+    backlog: []
+web:
+  backlog: []
+"#,
+        )
+        .unwrap();
+        let counts = |backlog, in_progress| TaskCounts {
+            backlog,
+            in_progress,
+            ..Default::default()
+        };
+        let mut lanes = vec![
+            ("backend/fixes".to_string(), counts(1, 1)),
+            ("backend/features".to_string(), counts(0, 0)),
+            ("web".to_string(), counts(0, 0)),
+        ];
+
+        let dispatched = dispatch_global_backlog(&mut tasks, &mut lanes).unwrap();
+        assert!(dispatched.iter().all(|d| d.held.is_none()));
+        let placed: Vec<(&str, &str)> = dispatched
+            .iter()
+            .map(|d| (d.id.as_str(), d.lane.as_str()))
+            .collect();
+        assert_eq!(
+            placed,
+            vec![
+                ("pinned", "web"),
+                ("labelled", "backend/features"),
+                ("backend-1", "backend/features"),
+                // features now has 2 queued, fixes has 2 in flight; fixes is listed first
+                ("backend-2", "backend/fixes"),
+                ("anywhere", "web"),
+            ]
+        );
+
+        let remaining: Vec<&str> = tasks
+            .global_backlog
+            .as_deref()
+            .unwrap()
+            .iter()
+            .map(|task| task.id.as_str())
+            .collect();
+        assert_eq!(remaining, vec!["nowhere"]);
+        let web = tasks.lane("web").unwrap();
+        assert_eq!(web.backlog[0].id, "pinned");
+        assert_eq!(web.backlog[0].lane, None);
+    }
+
+    #[test]
+    fn test_dispatch_holds_task_whose_id_is_taken_in_lane() {
+        let mut tasks: TasksFile = serde_yaml::from_str(
+            r#"
+global_backlog:
+  - id: fix-login
+    lane: web
+  - id: add-search
+    lane: web
+web:
+  backlog: []
+  in_progress:
+    - id: fix-login
+      claimed_by: web-worker
+"#,
+        )
+        .unwrap();
+        let mut lanes = vec![("web".to_string(), TaskCounts::default())];
+
+        let dispatched = dispatch_global_backlog(&mut tasks, &mut lanes).unwrap();
+        assert_eq!(dispatched.len(), 2);
+        assert_eq!(dispatched[0].id, "fix-login");
+        assert!(dispatched[0]
+            .held
+            .as_deref()
+            .unwrap()
+            .contains("already exists"));
+        assert_eq!(dispatched[1].id, "add-search");
+        assert_eq!(dispatched[1].held, None);
+
+        let global = tasks.global_backlog.as_deref().unwrap();
+        assert_eq!(global.len(), 1);
+        assert_eq!(global[0].id, "fix-login");
+        assert_eq!(global[0].lane.as_deref(), Some("web"));
+        let web = tasks.lane("web").unwrap();
+        assert_eq!(web.backlog.len(), 1);
+        assert_eq!(web.backlog[0].id, "add-search");
+        assert_eq!(lanes[0].1.backlog, 1);
+    }
+}
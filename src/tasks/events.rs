@@ -0,0 +1,243 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::yaml::{Task, TaskState, TasksFile};
+
+/// A change to a single task, found by diffing two loads of the task source
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TaskEvent {
+    /// A task appeared (in any state)
+    TaskAdded { lane: String, id: String },
+    /// A backlog task moved to in_progress
+    TaskClaimed { lane: String, id: String },
+    /// A task moved to done
+    TaskCompleted { lane: String, id: String },
+    /// A task changed lane, or changed state in a way that isn't a claim or
+    /// completion (e.g. requeued to backlog). `lane` is where it is now.
+    TaskMoved { lane: String, id: String },
+    /// A task's `question` was set or changed
+    QuestionRaised { lane: String, id: String },
+    /// A task's `answer` was set or changed
+    QuestionAnswered { lane: String, id: String },
+}
+
+impl TaskEvent {
+    pub fn lane(&self) -> &str {
+        match self {
+            Self::TaskAdded { lane, .. }
+            | Self::TaskClaimed { lane, .. }
+            | Self::TaskCompleted { lane, .. }
+            | Self::TaskMoved { lane, .. }
+            | Self::QuestionRaised { lane, .. }
+            | Self::QuestionAnswered { lane, .. } => lane,
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        match self {
+            Self::TaskAdded { id, .. }
+            | Self::TaskClaimed { id, .. }
+            | Self::TaskCompleted { id, .. }
+            | Self::TaskMoved { id, .. }
+            | Self::QuestionRaised { id, .. }
+            | Self::QuestionAnswered { id, .. } => id,
+        }
+    }
+
+    /// Whether the lane's worker may have new work because of this event
+    pub fn nudges_lane(&self) -> bool {
+        matches!(
+            self,
+            Self::TaskAdded { .. } | Self::TaskCompleted { .. } | Self::TaskMoved { .. }
+        )
+    }
+}
+
+impl fmt::Display for TaskEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let verb = match self {
+            Self::TaskAdded { .. } => "added",
+            Self::TaskClaimed { .. } => "claimed",
+            Self::TaskCompleted { .. } => "completed",
+            Self::TaskMoved { .. } => "moved",
+            Self::QuestionRaised { .. } => "question on",
+            Self::QuestionAnswered { .. } => "answer on",
+        };
+        write!(f, "{} {} ({})", verb, self.id(), self.lane())
+    }
+}
+
+/// Where a task sits in one load of the tasks
+pub(super) struct Placement<'a> {
+    pub lane: String,
+    pub state: TaskState,
+    pub task: &'a Task,
+}
+
+/// Every task by id, with where it sits
+pub(super) fn placements(tasks: &TasksFile) -> HashMap<&str, Placement<'_>> {
+    let mut placements = HashMap::new();
+    for (lane, lane_tasks) in tasks.lanes() {
+        for state in TaskState::ALL {
+            for task in lane_tasks.tasks(state) {
+                // Ids are unique across lanes in a valid file; keep the first if not
+                placements
+                    .entry(task.id.as_str())
+                    .or_insert_with(|| Placement {
+                        lane: lane.clone(),
+                        state,
+                        task,
+                    });
+            }
+        }
+    }
+    placements
+}
+
+/// The task events that turn `old` into `new`, in `new`'s lane order.
+/// Removed tasks produce no event.
+pub fn diff_tasks(old: &TasksFile, new: &TasksFile) -> Vec<TaskEvent> {
+    let before = placements(old);
+    let mut events = Vec::new();
+
+    for (lane, lane_tasks) in new.lanes() {
+        for state in TaskState::ALL {
+            for task in lane_tasks.tasks(state) {
+                let lane = lane.clone();
+                let id = task.id.clone();
+                let Some(previous) = before.get(task.id.as_str()) else {
+                    events.push(TaskEvent::TaskAdded { lane, id });
+                    continue;
+                };
+
+                let changed = |now: &Option<String>, before: &Option<String>| {
+                    let now = now.as_deref().filter(|text| !text.trim().is_empty());
+                    now.is_some() && now != before.as_deref()
+                };
+                let question_changed = changed(&task.question, &previous.task.question);
+                let answer_changed = changed(&task.answer, &previous.task.answer);
+
+                if previous.lane != lane {
+                    events.push(TaskEvent::TaskMoved {
+                        lane: lane.clone(),
+                        id: id.clone(),
+                    });
+                } else if previous.state != state {
+                    events.push(match (previous.state, state) {
+                        (TaskState::Backlog, TaskState::InProgress) => TaskEvent::TaskClaimed {
+                            lane: lane.clone(),
+                            id: id.clone(),
+                        },
+                        (_, TaskState::Done) => TaskEvent::TaskCompleted {
+                            lane: lane.clone(),
+                            id: id.clone(),
+                        },
+                        _ => TaskEvent::TaskMoved {
+                            lane: lane.clone(),
+                            id: id.clone(),
+                        },
+                    });
+                }
+                if question_changed {
+                    events.push(TaskEvent::QuestionRaised {
+                        lane: lane.clone(),
+                        id: id.clone(),
+                    });
+                }
+                if answer_changed {
+                    events.push(TaskEvent::QuestionAnswered { lane, id });
+                }
+            }
+        }
+    }
+
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(yaml: &str) -> TasksFile {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn test_diff_tasks_emits_typed_events() {
+        let old = parse(
+            r#"
+api:
+  backlog:
+    - id: claim-me
+    - id: move-me
+  in_progress:
+    - id: finish-me
+      claimed_by: worker-1
+    - id: ask-me
+      claimed_by: worker-1
+    - id: answer-me
+      claimed_by: worker-1
+      question: Keep the old API?
+web:
+  backlog: []
+"#,
+        );
+        let new = parse(
+            r#"
+api:
+  backlog:
+    - id: brand-new
+  in_progress:
+    - id: claim-me
+      claimed_by: worker-1
+    - id: ask-me
+      claimed_by: worker-1
+      question: Which endpoint?
+    - id: answer-me
+      claimed_by: worker-1
+      question: Keep the old API?
+      answer: Yes, until v2
+  done:
+    - id: finish-me
+web:
+  backlog:
+    - id: move-me
+"#,
+        );
+
+        let lane = |name: &str| name.to_string();
+        let id = |name: &str| name.to_string();
+        assert_eq!(
+            diff_tasks(&old, &new),
+            vec![
+                TaskEvent::TaskAdded {
+                    lane: lane("api"),
+                    id: id("brand-new")
+                },
+                TaskEvent::TaskClaimed {
+                    lane: lane("api"),
+                    id: id("claim-me")
+                },
+                TaskEvent::QuestionRaised {
+                    lane: lane("api"),
+                    id: id("ask-me")
+                },
+                TaskEvent::QuestionAnswered {
+                    lane: lane("api"),
+                    id: id("answer-me")
+                },
+                TaskEvent::TaskCompleted {
+                    lane: lane("api"),
+                    id: id("finish-me")
+                },
+                TaskEvent::TaskMoved {
+                    lane: lane("web"),
+                    id: id("move-me")
+                },
+            ]
+        );
+        assert!(diff_tasks(&new, &new).is_empty());
+    }
+}
@@ -0,0 +1,466 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use serde_json::Value;
+
+use crate::config::TasksConfig;
+
+use super::events::diff_tasks;
+use super::source::TaskBackend;
+use super::watcher::NudgeRequest;
+use super::yaml::{Completion, Task, TaskLocation, TaskState, TasksFile};
+
+const DEFAULT_POLL_SECS: u64 = 60;
+
+const ITEMS_SELECTION: &str = r#"
+items(first: 100, after: $cursor) {
+  pageInfo { hasNextPage endCursor }
+  nodes {
+    id
+    content {
+      __typename
+      ... on Issue { number title body url assignees(first: 10) { nodes { login } } }
+      ... on PullRequest { number title body url assignees(first: 10) { nodes { login } } }
+      ... on DraftIssue { title body assignees(first: 10) { nodes { login } } }
+    }
+    fieldValues(first: 30) {
+      nodes {
+        ... on ProjectV2ItemFieldSingleSelectValue {
+          name
+          field { ... on ProjectV2SingleSelectField { id name } }
+        }
+      }
+    }
+  }
+}"#;
+
+/// Tasks kept in a GitHub Project (v2), read through `gh api graphql`.
+///
+/// Items are placed in lanes by the lane field and in backlog/in_progress/done
+/// by the status field. Fields are matched by id when configured, otherwise
+/// by the names "Lane" and "Status". A project numbered under `org` may be
+/// owned by an organization or a user account.
+///
+/// Once `watch` is polling the project, `load` only serves the last fetch, so
+/// callers on the server's event loop never wait on the network.
+#[derive(Debug, Clone)]
+pub struct GithubTasks {
+    /// The GitHub CLI to run
+    gh: PathBuf,
+    org: Option<String>,
+    project: Option<u32>,
+    /// `organization` or `user`, once a lookup by number has found the owner
+    owner_kind: Arc<Mutex<Option<&'static str>>>,
+    /// Items the last fetch left out because their lane clashes with another
+    /// item's, e.g. `backend` and `backend/fixes`
+    conflicts: Arc<Mutex<Vec<String>>>,
+    project_id: Option<String>,
+    status_field_id: Option<String>,
+    lane_field_id: Option<String>,
+    poll_interval: Duration,
+    /// Last fetch, reused for `poll_interval` so frequent readers (the TUI)
+    /// don't run `gh` on every call
+    cache: Arc<Mutex<Option<(Instant, TasksFile)>>>,
+    /// Set by `watch`; from then on only its thread fetches
+    polling: Arc<AtomicBool>,
+}
+
+impl GithubTasks {
+    pub fn from_config(config: &TasksConfig) -> Result<Self> {
+        let has_number = config.github_org.is_some() && config.github_project.is_some();
+        if !has_number && config.github_project_id.is_none() {
+            bail!("GitHub task source needs github_org and github_project, or github_project_id");
+        }
+        Ok(Self {
+            gh: PathBuf::from("gh"),
+            org: config.github_org.clone(),
+            project: config.github_project,
+            owner_kind: Arc::new(Mutex::new(None)),
+            conflicts: Arc::new(Mutex::new(Vec::new())),
+            project_id: config.github_project_id.clone(),
+            status_field_id: config.github_status_field_id.clone(),
+            lane_field_id: config.github_lane_field_id.clone(),
+            poll_interval: Duration::from_secs(
+                config.github_poll_secs.unwrap_or(DEFAULT_POLL_SECS).max(1),
+            ),
+            cache: Arc::new(Mutex::new(None)),
+            polling: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    /// Run `gh` from `path` instead of looking it up on `PATH`
+    pub fn with_gh(mut self, path: impl Into<PathBuf>) -> Self {
+        self.gh = path.into();
+        self
+    }
+
+    /// Fetch every project item, following pagination
+    fn fetch_items(&self) -> Result<Vec<Value>> {
+        match (&self.project_id, &self.org, self.project) {
+            (Some(id), _, _) => {
+                let query = format!(
+                    "query=query($id: ID!, $cursor: String) {{ node(id: $id) {{ ... on ProjectV2 {{ {} }} }} }}",
+                    ITEMS_SELECTION
+                );
+                let vars = ["-f".to_string(), format!("id={}", id)];
+                self.fetch_pages(&query, &vars, "/data/node/items")
+            }
+            (None, Some(org), Some(number)) => self.fetch_owner_items(org, number),
+            _ => bail!("GitHub task source is missing its project"),
+        }
+    }
+
+    /// Fetch the items of project `number` of `login`, trying it as an
+    /// organization and then as a user until one works
+    fn fetch_owner_items(&self, login: &str, number: u32) -> Result<Vec<Value>> {
+        let known = self.owner_kind.lock().ok().and_then(|kind| *kind);
+        let kinds = match known {
+            Some(kind) => vec![kind],
+            None => vec!["organization", "user"],
+        };
+
+        let mut errors = Vec::new();
+        for kind in kinds {
+            let query = format!(
+                "query=query($org: String!, $number: Int!, $cursor: String) {{ {}(login: $org) {{ projectV2(number: $number) {{ {} }} }} }}",
+                kind, ITEMS_SELECTION
+            );
+            let vars = [
+                "-f".to_string(),
+                format!("org={}", login),
+                "-F".to_string(),
+                format!("number={}", number),
+            ];
+            let items_path = format!("/data/{}/projectV2/items", kind);
+            match self.fetch_pages(&query, &vars, &items_path) {
+                Ok(items) => {
+                    if let Ok(mut owner_kind) = self.owner_kind.lock() {
+                        *owner_kind = Some(kind);
+                    }
+                    return Ok(items);
+                }
+                Err(e) => errors.push(format!("as {}: {:#}", kind, e)),
+            }
+        }
+        bail!("{}", errors.join("; "))
+    }
+
+    /// Run `query` with `vars` (gh `-f`/`-F` arguments) page by page and
+    /// collect the item nodes found at `items_path`
+    fn fetch_pages(&self, query: &str, vars: &[String], items_path: &str) -> Result<Vec<Value>> {
+        let mut items = Vec::new();
+        let mut cursor: Option<String> = None;
+
+        loop {
+            let mut args = vec![
+                "api".to_string(),
+                "graphql".to_string(),
+                "-f".to_string(),
+                query.to_string(),
+            ];
+            args.extend(vars.iter().cloned());
+            if let Some(cursor) = &cursor {
+                args.push("-f".to_string());
+                args.push(format!("cursor={}", cursor));
+            }
+
+            let output = Command::new(&self.gh)
+                .args(&args)
+                .output()
+                .context("Failed to run gh (is the GitHub CLI installed?)")?;
+            if !output.status.success() {
+                bail!(
+                    "gh api graphql failed: {}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                );
+            }
+
+            let response: Value = serde_json::from_slice(&output.stdout)
+                .context("Failed parsing gh api graphql output")?;
+            if let Some(errors) = response.get("errors").and_then(Value::as_array) {
+                let messages: Vec<&str> = errors
+                    .iter()
+                    .filter_map(|e| e.get("message").and_then(Value::as_str))
+                    .collect();
+                bail!("GitHub API error: {}", messages.join("; "));
+            }
+
+            let page = response
+                .pointer(items_path)
+                .with_context(|| format!("GitHub project not found ({})", self.describe()))?;
+            if let Some(nodes) = page.get("nodes").and_then(Value::as_array) {
+                items.extend(nodes.iter().cloned());
+            }
+
+            let has_next = page
+                .pointer("/pageInfo/hasNextPage")
+                .and_then(Value::as_bool)
+                .unwrap_or(false);
+            cursor = page
+                .pointer("/pageInfo/endCursor")
+                .and_then(Value::as_str)
+                .map(String::from);
+            if !has_next || cursor.is_none() {
+                break;
+            }
+        }
+
+        Ok(items)
+    }
+
+    /// Read the project from GitHub, bypassing and refreshing the cache
+    fn fetch(&self) -> Result<TasksFile> {
+        let (tasks, conflicts) = self.to_tasks_file(&self.fetch_items()?);
+        if let Ok(mut cache) = self.cache.lock() {
+            *cache = Some((Instant::now(), tasks.clone()));
+        }
+        if let Ok(mut last) = self.conflicts.lock() {
+            *last = conflicts;
+        }
+        Ok(tasks)
+    }
+
+    /// Items the last fetch left out because their lane clashes with another
+    /// item's, one message each
+    pub fn lane_conflicts(&self) -> Vec<String> {
+        self.conflicts
+            .lock()
+            .map(|conflicts| conflicts.clone())
+            .unwrap_or_default()
+    }
+
+    /// Value of a single-select field on an item, matched by id or by name
+    fn field_value<'a>(item: &'a Value, field_id: Option<&str>, name: &str) -> Option<&'a str> {
+        item.pointer("/fieldValues/nodes")?
+            .as_array()?
+            .iter()
+            .find(|value| {
+                let field = value.get("field");
+                match field_id {
+                    Some(id) => field.and_then(|f| f.get("id")).and_then(Value::as_str) == Some(id),
+                    None => field
+                        .and_then(|f| f.get("name"))
+                        .and_then(Value::as_str)
+                        .is_some_and(|n| n.eq_ignore_ascii_case(name)),
+                }
+            })?
+            .get("name")?
+            .as_str()
+    }
+
+    /// The items as a tasks file, leaving out those whose lane can't be
+    /// placed, with a message for each
+    fn to_tasks_file(&self, items: &[Value]) -> (TasksFile, Vec<String>) {
+        let mut tasks = TasksFile::default();
+        let mut conflicts = Vec::new();
+
+        for item in items {
+            let Some(lane) = Self::field_value(item, self.lane_field_id.as_deref(), "Lane") else {
+                continue;
+            };
+            let state = status_to_state(Self::field_value(
+                item,
+                self.status_field_id.as_deref(),
+                "Status",
+            ));
+            let task = item_to_task(item, state);
+            match tasks.ensure_lane_mut(lane) {
+                Ok(lane_tasks) => lane_tasks.tasks_mut(state).push(task),
+                Err(e) => conflicts.push(format!(
+                    "item {} skipped, lane '{}': {:#}",
+                    task.id, lane, e
+                )),
+            }
+        }
+
+        (tasks, conflicts)
+    }
+}
+
+impl TaskBackend for GithubTasks {
+    fn describe(&self) -> String {
+        match (&self.project_id, &self.org, self.project) {
+            (Some(id), _, _) => format!("GitHub project {}", id),
+            (None, Some(org), Some(number)) => format!("GitHub project {}/#{}", org, number),
+            _ => "GitHub project".to_string(),
+        }
+    }
+
+    fn load(&self) -> Result<TasksFile> {
+        let polling = self.polling.load(Ordering::Relaxed);
+        if let Ok(cache) = self.cache.lock() {
+            if let Some((fetched_at, tasks)) = cache.as_ref() {
+                if polling || fetched_at.elapsed() < self.poll_interval {
+                    return Ok(tasks.clone());
+                }
+            }
+        }
+        if polling {
+            bail!("{} hasn't been fetched yet", self.describe());
+        }
+        self.fetch()
+    }
+
+    fn claim(&self, id: &str, _lane: Option<&str>, _worker: &str) -> Result<TaskLocation> {
+        bail!(
+            "Claim '{}' by moving it to In Progress on {}",
+            id,
+            self.describe()
+        )
+    }
+
+    fn complete(
+        &self,
+        id: &str,
+        _lane: Option<&str>,
+        _completion: Completion,
+    ) -> Result<TaskLocation> {
+        bail!(
+            "Complete '{}' by moving it to Done on {}",
+            id,
+            self.describe()
+        )
+    }
+
+    fn requeue(&self, id: &str, _lane: Option<&str>, _reason: &str) -> Result<TaskLocation> {
+        bail!(
+            "Requeue '{}' by moving it back to Todo on {}",
+            id,
+            self.describe()
+        )
+    }
+
+    /// Poll the project and send events whenever its items change
+    fn watch(&self, nudge_tx: Sender<NudgeRequest>, log_path: PathBuf) -> Result<()> {
+        self.polling.store(true, Ordering::Relaxed);
+        let source = self.clone();
+        thread::spawn(move || {
+            log_line(
+                &log_path,
+                &format!(
+                    "github-poll: watching {} every {:?}",
+                    source.describe(),
+                    source.poll_interval
+                ),
+            );
+            let mut last: Option<TasksFile> = None;
+            let mut last_conflicts = Vec::new();
+
+            loop {
+                match source.fetch() {
+                    Ok(tasks) => {
+                        let conflicts = source.lane_conflicts();
+                        if conflicts != last_conflicts {
+                            for conflict in &conflicts {
+                                log_line(&log_path, &format!("github-poll: {}", conflict));
+                            }
+                            last_conflicts = conflicts;
+                        }
+                        let first = last.is_none();
+                        let events = match &last {
+                            Some(previous) => diff_tasks(previous, &tasks),
+                            None => Vec::new(),
+                        };
+                        last = Some(tasks);
+                        // `load` had nothing to serve until now
+                        if first && nudge_tx.send(NudgeRequest::Refresh).is_err() {
+                            break;
+                        }
+                        if !events.is_empty() {
+                            log_line(
+                                &log_path,
+                                &format!("github-poll: project changed, events={:?}", events),
+                            );
+                            if nudge_tx.send(NudgeRequest::Events(events)).is_err() {
+                                log_line(&log_path, "github-poll: nudge channel closed");
+                                break;
+                            }
+                        }
+                    }
+                    Err(e) => log_line(&log_path, &format!("github-poll: load failed: {:#}", e)),
+                }
+                thread::sleep(source.poll_interval);
+            }
+        });
+
+        Ok(())
+    }
+}
+
+/// Map a project status option to a task state. Anything unrecognised
+/// (Todo, Backlog, Ready, no status) counts as backlog.
+fn status_to_state(status: Option<&str>) -> TaskState {
+    let normalized: String = status
+        .unwrap_or("")
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .collect::<String>()
+        .to_lowercase();
+    match normalized.as_str() {
+        "inprogress" | "doing" | "inreview" | "review" => TaskState::InProgress,
+        "done" | "complete" | "completed" | "closed" | "merged" => TaskState::Done,
+        _ => TaskState::Backlog,
+    }
+}
+
+fn item_to_task(item: &Value, state: TaskState) -> Task {
+    let content = item.get("content").unwrap_or(&Value::Null);
+    let text = |key: &str| {
+        content
+            .get(key)
+            .and_then(Value::as_str)
+            .filter(|s| !s.trim().is_empty())
+            .map(String::from)
+    };
+
+    // Issues and PRs go by number so workers can `gh issue view <id>`
+    let id = match content.get("number").and_then(Value::as_u64) {
+        Some(number) => number.to_string(),
+        None => item
+            .get("id")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string(),
+    };
+
+    let mut task = Task {
+        id,
+        title: text("title"),
+        description: text("body"),
+        ..Default::default()
+    };
+
+    if state != TaskState::Backlog {
+        task.claimed_by = content
+            .pointer("/assignees/nodes/0/login")
+            .and_then(Value::as_str)
+            .map(String::from);
+    }
+    if let Some(url) = text("url") {
+        if content.get("__typename").and_then(Value::as_str) == Some("PullRequest") {
+            task.pr_url = Some(url);
+        } else {
+            task.extra
+                .insert("url".to_string(), serde_yaml::Value::String(url));
+        }
+    }
+
+    task
+}
+
+fn log_line(path: &Path, line: &str) {
+    if let Ok(mut file) = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+    {
+        let _ = writeln!(file, "{}", line);
+    }
+}
@@ -0,0 +1,223 @@
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::events::placements;
+use super::yaml::{parse_duration, parse_timestamp, TaskState, TasksFile};
+
+/// File the server appends task history to, next to server.log
+pub const HISTORY_FILE: &str = "task-history.jsonl";
+
+/// One state change of one task, as stored in the history log
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// RFC 3339 time the server saw the change
+    pub at: String,
+    /// Lane the task is in after the change (before it, for removals)
+    pub lane: String,
+    pub id: String,
+    /// Previous state; `None` when the task was added
+    pub from: Option<TaskState>,
+    /// New state; `None` when the task was removed
+    pub to: Option<TaskState>,
+    /// Previous lane, when the task moved between lanes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_lane: Option<String>,
+    pub claimed_by: Option<String>,
+}
+
+impl HistoryEntry {
+    pub fn at(&self) -> Option<DateTime<Utc>> {
+        parse_timestamp(&self.at)
+    }
+}
+
+/// Every task whose lane or state differs between `old` and `new`, stamped `at`
+pub fn transitions(old: &TasksFile, new: &TasksFile, at: &str) -> Vec<HistoryEntry> {
+    let before = placements(old);
+    let after = placements(new);
+    let mut entries = Vec::new();
+
+    for (lane, lane_tasks) in new.lanes() {
+        for state in TaskState::ALL {
+            for task in lane_tasks.tasks(state) {
+                let previous = before.get(task.id.as_str());
+                if previous.is_some_and(|p| p.lane == lane && p.state == state) {
+                    continue;
+                }
+                entries.push(HistoryEntry {
+                    at: at.to_string(),
+                    lane: lane.clone(),
+                    id: task.id.clone(),
+                    from: previous.map(|p| p.state),
+                    to: Some(state),
+                    from_lane: previous.filter(|p| p.lane != lane).map(|p| p.lane.clone()),
+                    claimed_by: task.claimed_by.clone(),
+                });
+            }
+        }
+    }
+
+    for (lane, lane_tasks) in old.lanes() {
+        for state in TaskState::ALL {
+            for task in lane_tasks.tasks(state) {
+                if after.contains_key(task.id.as_str()) {
+                    continue;
+                }
+                entries.push(HistoryEntry {
+                    at: at.to_string(),
+                    lane: lane.clone(),
+                    id: task.id.clone(),
+                    from: Some(state),
+                    to: None,
+                    from_lane: None,
+                    claimed_by: task.claimed_by.clone(),
+                });
+            }
+        }
+    }
+
+    entries
+}
+
+/// Append entries to the history log, one JSON object per line
+pub fn append_history(path: &Path, entries: &[HistoryEntry]) -> Result<()> {
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed opening {}", path.display()))?;
+    let mut lines = String::new();
+    for entry in entries {
+        lines.push_str(&serde_json::to_string(entry)?);
+        lines.push('\n');
+    }
+    file.write_all(lines.as_bytes())
+        .with_context(|| format!("Failed writing {}", path.display()))
+}
+
+/// Read the history log, skipping lines that don't parse. A missing log is empty.
+pub fn read_history(path: &Path) -> Result<Vec<HistoryEntry>> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).with_context(|| format!("Failed reading {}", path.display())),
+    };
+    Ok(content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// Parse a `--since` value: a duration ago (`30m`, `12h`, `2d`, `1w`) or a
+/// timestamp (RFC 3339, `2024-05-01T12:00:00` or `2024-05-01`, both UTC)
+pub fn parse_since(value: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    if let Some(at) = parse_timestamp(value) {
+        return Ok(at);
+    }
+    parse_duration(value).map(|ago| now - ago).map_err(|_| {
+        anyhow!(
+            "Can't parse '{}' as a time; use e.g. 12h, 2d, 2024-05-01 or 2024-05-01T12:00:00Z",
+            value.trim()
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transitions_record_state_and_lane_changes() {
+        let old: TasksFile = serde_yaml::from_str(
+            r#"
+api:
+  backlog:
+    - id: claim-me
+    - id: move-me
+  in_progress: []
+  done:
+    - id: archive-me
+web:
+  backlog: []
+"#,
+        )
+        .unwrap();
+        let new: TasksFile = serde_yaml::from_str(
+            r#"
+api:
+  backlog:
+    - id: new-task
+  in_progress:
+    - id: claim-me
+      claimed_by: worker-1
+  done: []
+web:
+  backlog:
+    - id: move-me
+"#,
+        )
+        .unwrap();
+
+        let entries = transitions(&old, &new, "2024-05-01T12:00:00Z");
+        let summary: Vec<(&str, &str, Option<TaskState>, Option<TaskState>)> = entries
+            .iter()
+            .map(|e| (e.lane.as_str(), e.id.as_str(), e.from, e.to))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("api", "new-task", None, Some(TaskState::Backlog)),
+                (
+                    "api",
+                    "claim-me",
+                    Some(TaskState::Backlog),
+                    Some(TaskState::InProgress)
+                ),
+                (
+                    "web",
+                    "move-me",
+                    Some(TaskState::Backlog),
+                    Some(TaskState::Backlog)
+                ),
+                ("api", "archive-me", Some(TaskState::Done), None),
+            ]
+        );
+        assert_eq!(entries[1].claimed_by.as_deref(), Some("worker-1"));
+        assert_eq!(entries[2].from_lane.as_deref(), Some("api"));
+        assert!(transitions(&new, &new, "2024-05-01T12:00:00Z").is_empty());
+    }
+
+    #[test]
+    fn test_history_round_trip_and_since() {
+        let path = std::env::temp_dir().join(format!("hive-history-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let entry = HistoryEntry {
+            at: "2024-05-01T12:00:00Z".to_string(),
+            lane: "api".to_string(),
+            id: "fix-login".to_string(),
+            from: Some(TaskState::Backlog),
+            to: Some(TaskState::InProgress),
+            from_lane: None,
+            claimed_by: Some("worker-1".to_string()),
+        };
+        append_history(&path, std::slice::from_ref(&entry)).unwrap();
+        append_history(&path, std::slice::from_ref(&entry)).unwrap();
+        assert_eq!(read_history(&path).unwrap(), vec![entry.clone(), entry]);
+        let _ = std::fs::remove_file(&path);
+
+        let now = parse_since("2024-05-02T00:00:00Z", Utc::now()).unwrap();
+        assert_eq!(
+            parse_since("12h", now).unwrap(),
+            parse_since("2024-05-01T12:00:00", now).unwrap()
+        );
+        assert_eq!(
+            parse_since("1d", now).unwrap(),
+            parse_since("2024-05-01", now).unwrap()
+        );
+        assert!(parse_since("yesterday", now).is_err());
+    }
+}
@@ -0,0 +1,283 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use anyhow::Result;
+
+use crate::utils::git;
+
+use super::yaml::{add_task, Task, TasksFile};
+
+/// Lines of code kept above and below a marker in the task description
+const SNIPPET_CONTEXT: usize = 2;
+
+/// Longest title taken from a marker's comment text
+const MAX_TITLE_CHARS: usize = 72;
+
+/// A TODO-style marker found in a tracked file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeTodo {
+    /// Path relative to the repo, with `/` separators
+    pub path: String,
+    /// 1-based line number
+    pub line: usize,
+    /// The marker that matched, e.g. `TODO`
+    pub marker: String,
+    /// Comment text after the marker
+    pub text: String,
+    /// The marker's line with a little code around it
+    pub snippet: String,
+    /// How many markers above it in the file are on an identical line
+    pub occurrence: usize,
+}
+
+impl CodeTodo {
+    /// Id that stays the same across runs: the file name plus a hash of the
+    /// path and the marker's line, so it survives lines moving up or down.
+    /// Repeats of an identical line also hash which repeat they are.
+    pub fn task_id(&self) -> String {
+        let stem: String = Path::new(&self.path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_lowercase())
+            .unwrap_or_default()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .collect();
+        let line = self.snippet_line().trim();
+        let mut key = format!("{}\n{}", self.path, line);
+        if self.occurrence > 0 {
+            key.push_str(&format!("\n{}", self.occurrence));
+        }
+        let hash = fnv1a(key.as_bytes());
+        format!(
+            "{}-{}-{:08x}",
+            self.marker.to_lowercase(),
+            stem.trim_matches('-'),
+            hash
+        )
+    }
+
+    pub fn to_task(&self) -> Task {
+        let title = if self.text.is_empty() {
+            format!("{} in {}", self.marker, self.path)
+        } else if self.text.chars().count() > MAX_TITLE_CHARS {
+            let cut: String = self.text.chars().take(MAX_TITLE_CHARS - 3).collect();
+            format!("{}...", cut.trim_end())
+        } else {
+            self.text.clone()
+        };
+        Task {
+            id: self.task_id(),
+            title: Some(title),
+            description: Some(format!(
+                "{} at {}:{}\n\n```\n{}\n```\n",
+                self.marker, self.path, self.line, self.snippet
+            )),
+            labels: Some(vec![self.marker.to_lowercase()]),
+            files_changed: Some(vec![self.path.clone()]),
+            ..Default::default()
+        }
+    }
+
+    /// The marker's own line out of the snippet
+    fn snippet_line(&self) -> &str {
+        let first = self.line.saturating_sub(SNIPPET_CONTEXT).max(1);
+        self.snippet
+            .lines()
+            .nth(self.line - first)
+            .unwrap_or_default()
+    }
+}
+
+/// Find `markers` in every file git tracks in `repo_dir`. Binary and
+/// non-UTF-8 files are skipped.
+pub fn scan_todos(repo_dir: &Path, markers: &[String]) -> Result<Vec<CodeTodo>> {
+    let mut todos = Vec::new();
+    for path in git::tracked_files(repo_dir)? {
+        let Ok(bytes) = std::fs::read(repo_dir.join(&path)) else {
+            continue;
+        };
+        if bytes.iter().take(8000).any(|b| *b == 0) {
+            continue;
+        }
+        let Ok(content) = String::from_utf8(bytes) else {
+            continue;
+        };
+        let path = path.to_string_lossy().replace('\\', "/");
+        todos.extend(find_todos(&path, &content, markers));
+    }
+    Ok(todos)
+}
+
+/// Markers in one file's `content`. A marker only counts as a whole word,
+/// so `TODOS` or `todo_list` don't match `TODO`.
+pub fn find_todos(path: &str, content: &str, markers: &[String]) -> Vec<CodeTodo> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut todos = Vec::new();
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+    for (index, line) in lines.iter().enumerate() {
+        let Some((marker, rest)) = markers.iter().find_map(|m| find_marker(line, m)) else {
+            continue;
+        };
+        let start = index.saturating_sub(SNIPPET_CONTEXT);
+        let end = (index + SNIPPET_CONTEXT + 1).min(lines.len());
+        let repeats = seen.entry(line.trim()).or_default();
+        let occurrence = *repeats;
+        *repeats += 1;
+        todos.push(CodeTodo {
+            path: path.to_string(),
+            line: index + 1,
+            marker: marker.to_string(),
+            text: comment_text(rest),
+            snippet: lines[start..end].join("\n"),
+            occurrence,
+        });
+    }
+    todos
+}
+
+/// The text after `marker` in `line`, if it appears there as a whole word
+fn find_marker<'a>(line: &'a str, marker: &'a str) -> Option<(&'a str, &'a str)> {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    line.match_indices(marker).find_map(|(at, _)| {
+        let before = line[..at].chars().next_back();
+        let rest = &line[at + marker.len()..];
+        let after = rest.chars().next();
+        (!before.is_some_and(is_word) && !after.is_some_and(is_word)).then_some((marker, rest))
+    })
+}
+
+/// Strip `(owner)`, the colon and comment closers from the text after a marker
+fn comment_text(rest: &str) -> String {
+    let mut text = rest.trim_start();
+    if text.starts_with('(') {
+        if let Some(end) = text.find(')') {
+            text = &text[end + 1..];
+        }
+    }
+    let text = text.trim_start_matches([':', '-', ' ', '\t']).trim_end();
+    let text = ["*/", "-->", "#}", "%>"]
+        .iter()
+        .find_map(|closer| text.strip_suffix(closer))
+        .unwrap_or(text);
+    text.trim().to_string()
+}
+
+/// Add a backlog task to `lane` for each marker, skipping ids already in the
+/// tasks file or `archived_ids`. Returns how many were added and skipped.
+pub fn import_todos(
+    tasks: &mut TasksFile,
+    lane: &str,
+    todos: &[CodeTodo],
+    archived_ids: &HashSet<String>,
+) -> Result<(usize, usize)> {
+    let mut existing: HashSet<String> = tasks
+        .lanes()
+        .into_iter()
+        .flat_map(|(_, lane_tasks)| {
+            let all = lane_tasks
+                .backlog
+                .iter()
+                .chain(&lane_tasks.in_progress)
+                .chain(&lane_tasks.done);
+            all.map(|task| task.id.clone()).collect::<Vec<_>>()
+        })
+        .chain(tasks.global_backlog.iter().flatten().map(|t| t.id.clone()))
+        .chain(archived_ids.iter().cloned())
+        .collect();
+
+    let (mut added, mut skipped) = (0, 0);
+    for todo in todos {
+        let task = todo.to_task();
+        if !existing.insert(task.id.clone()) {
+            skipped += 1;
+            continue;
+        }
+        add_task(tasks, lane, task)?;
+        added += 1;
+    }
+    Ok((added, skipped))
+}
+
+/// 32-bit FNV-1a; unlike `DefaultHasher` it gives the same ids on every Rust version
+fn fnv1a(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0x811c9dc5, |hash, byte| {
+        (hash ^ u32::from(*byte)).wrapping_mul(0x01000193)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_todos_and_import_skips_duplicates() {
+        let content = "\
+fn main() {
+    // TODO(sam): retry on timeout */
+    connect();
+    let todo_list = vec![]; // TODOS aren't markers
+    /* FIXME */
+}
+";
+        let markers = vec!["TODO".to_string(), "FIXME".to_string()];
+        let todos = find_todos("src/net/client.rs", content, &markers);
+        let found: Vec<(usize, &str, &str)> = todos
+            .iter()
+            .map(|t| (t.line, t.marker.as_str(), t.text.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![(2, "TODO", "retry on timeout"), (5, "FIXME", "")]
+        );
+        assert_eq!(todos[0].snippet.lines().count(), 4);
+
+        let task = todos[0].to_task();
+        assert!(task.id.starts_with("todo-client-"));
+        assert_eq!(task.title.as_deref(), Some("retry on timeout"));
+        assert!(task
+            .description
+            .as_deref()
+            .is_some_and(|d| d.starts_with("TODO at src/net/client.rs:2\n")));
+        assert_eq!(
+            task.files_changed,
+            Some(vec!["src/net/client.rs".to_string()])
+        );
+        assert_eq!(
+            todos[1].to_task().title.as_deref(),
+            Some("FIXME in src/net/client.rs")
+        );
+
+        // Moving the line keeps the id
+        let moved = find_todos("src/net/client.rs", &format!("\n\n{}", content), &markers);
+        assert_eq!(moved[0].task_id(), task.id);
+
+        let mut tasks = TasksFile::default();
+        let first = import_todos(&mut tasks, "api", &todos, &HashSet::new()).unwrap();
+        assert_eq!(first, (2, 0));
+        let again = import_todos(&mut tasks, "api", &moved, &HashSet::new()).unwrap();
+        assert_eq!(again, (0, 2));
+    }
+
+    #[test]
+    fn test_identical_marker_lines_get_their_own_ids() {
+        let content = "\
+fn parse() {
+    // TODO
+}
+
+fn render() {
+    // TODO
+}
+";
+        let markers = vec!["TODO".to_string()];
+        let todos = find_todos("src/view.rs", content, &markers);
+        assert_eq!(todos.len(), 2);
+        assert_ne!(todos[0].task_id(), todos[1].task_id());
+
+        let mut tasks = TasksFile::default();
+        let first = import_todos(&mut tasks, "web", &todos, &HashSet::new()).unwrap();
+        assert_eq!(first, (2, 0));
+        let again = import_todos(&mut tasks, "web", &todos, &HashSet::new()).unwrap();
+        assert_eq!(again, (0, 2));
+    }
+}
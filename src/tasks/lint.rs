@@ -0,0 +1,525 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use serde_yaml::{Mapping, Value};
+
+use super::yaml::{parse_timestamp, TaskState, TasksFile};
+
+/// Top-level keys that aren't projects
+const RESERVED_KEYS: &[&str] = &["worker_protocol", "rules", "global_backlog"];
+
+/// Task fields that must hold a timestamp
+const TIMESTAMP_FIELDS: &[&str] = &["claimed_at", "completed_at"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A problem in a tasks file. Line and column are 1-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub line: usize,
+    pub column: usize,
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        write!(
+            f,
+            "{}:{}: {}: {}",
+            self.line, self.column, severity, self.message
+        )
+    }
+}
+
+/// Check a tasks.yaml for mistakes serde either rejects with an opaque message
+/// or silently accepts. `known_lanes` is the lanes from the workspace config;
+/// pass an empty slice to skip the unknown-lane check. `archived_ids` are the
+/// tasks in the archive, which still satisfy `depends_on`.
+pub fn lint_tasks(
+    content: &str,
+    known_lanes: &[String],
+    archived_ids: &HashSet<String>,
+) -> Vec<Diagnostic> {
+    let mut linter = Linter {
+        lines: content.lines().collect(),
+        known_lanes,
+        seen_ids: HashMap::new(),
+        dependencies: Vec::new(),
+        diagnostics: Vec::new(),
+    };
+
+    let root: Value = match serde_yaml::from_str(content) {
+        Ok(value) => value,
+        Err(e) => {
+            linter.push_serde_error(&e);
+            return linter.diagnostics;
+        }
+    };
+
+    match &root {
+        Value::Null => {}
+        Value::Mapping(mapping) => linter.lint_root(mapping),
+        _ => linter.error(
+            (1, 1),
+            "tasks file must be a mapping of projects".to_string(),
+        ),
+    }
+    linter.lint_dependencies(archived_ids);
+
+    // Anything serde still rejects that the checks above didn't explain
+    if !linter.has_errors() {
+        if let Err(e) = serde_yaml::from_str::<TasksFile>(content) {
+            linter.push_serde_error(&e);
+        }
+    }
+
+    linter
+        .diagnostics
+        .sort_by_key(|d| (d.line, d.column, d.severity == Severity::Warning));
+    linter.diagnostics
+}
+
+struct Linter<'a> {
+    lines: Vec<&'a str>,
+    known_lanes: &'a [String],
+    /// Task id -> lane it was first seen in
+    seen_ids: HashMap<String, String>,
+    /// (position of `depends_on`, task id, dependency id), checked once every
+    /// id has been seen
+    dependencies: Vec<(Position, String, String)>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+type Position = (usize, usize);
+
+impl Linter<'_> {
+    fn lint_root(&mut self, root: &Mapping) {
+        for (key, value) in root {
+            let Some(key) = key.as_str() else {
+                continue;
+            };
+            let position = self.find_top_key(key).unwrap_or((1, 1));
+
+            if key == "global_backlog" {
+                self.lint_tasks_list("global_backlog", TaskState::Backlog, value, position);
+                continue;
+            }
+            if RESERVED_KEYS.contains(&key) {
+                continue;
+            }
+
+            let Value::Mapping(entry) = value else {
+                self.error(
+                    position,
+                    format!(
+                        "'{}' must be a lane (backlog/in_progress/done) or a mapping of lanes",
+                        key
+                    ),
+                );
+                continue;
+            };
+
+            // Like `ProjectEntry`, an entry whose keys are all states is a direct
+            // lane and anything else maps lane names to lanes. Lists only hold
+            // tasks, so an entry with one is a lane with a misspelled state.
+            let is_direct = entry
+                .iter()
+                .all(|(k, _)| k.as_str().and_then(TaskState::parse).is_some())
+                || entry.values().any(|v| matches!(v, Value::Sequence(_)));
+            if is_direct {
+                self.lint_lane(key, entry, position);
+                continue;
+            }
+
+            for (lane, lane_value) in entry {
+                let Some(lane) = lane.as_str() else {
+                    continue;
+                };
+                let lane_position = self.find_key(position.0, lane).unwrap_or(position);
+                match lane_value {
+                    Value::Mapping(lane_entry) => {
+                        self.lint_lane(&format!("{}/{}", key, lane), lane_entry, lane_position)
+                    }
+                    Value::Null => self.error(
+                        lane_position,
+                        format!("empty lane '{}/{}', write '{}: {{}}'", key, lane, lane),
+                    ),
+                    _ => self.error(
+                        lane_position,
+                        format!(
+                            "lane '{}/{}' must be a mapping of backlog/in_progress/done",
+                            key, lane
+                        ),
+                    ),
+                }
+            }
+        }
+    }
+
+    fn lint_lane(&mut self, lane: &str, entry: &Mapping, position: Position) {
+        if !self.known_lanes.is_empty() && !self.known_lanes.iter().any(|l| l == lane) {
+            self.warning(
+                position,
+                format!(
+                    "lane '{}' is not assigned to any worker (known lanes: {})",
+                    lane,
+                    self.known_lanes.join(", ")
+                ),
+            );
+        }
+
+        for (key, value) in entry {
+            let Some(key) = key.as_str() else {
+                continue;
+            };
+            let key_position = self.find_key(position.0, key).unwrap_or(position);
+
+            let Some(state) = TaskState::parse(key).filter(|s| s.as_str() == key) else {
+                let hint = closest_state(key)
+                    .map(|state| format!(", did you mean '{}'?", state))
+                    .unwrap_or_default();
+                self.error(
+                    key_position,
+                    format!(
+                        "unknown key '{}' in lane '{}' (expected backlog, in_progress or done){}",
+                        key, lane, hint
+                    ),
+                );
+                continue;
+            };
+
+            self.lint_tasks_list(lane, state, value, key_position);
+        }
+    }
+
+    fn lint_tasks_list(&mut self, lane: &str, state: TaskState, value: &Value, position: Position) {
+        let key = state.as_str();
+        let tasks = match value {
+            Value::Sequence(tasks) => tasks,
+            Value::Null => {
+                self.error(
+                    position,
+                    format!("empty '{}:' in lane '{}', write '{}: []'", key, lane, key),
+                );
+                return;
+            }
+            _ => {
+                self.error(
+                    position,
+                    format!("'{}' in lane '{}' must be a list of tasks", key, lane),
+                );
+                return;
+            }
+        };
+
+        let mut search_from = position.0;
+        for task in tasks {
+            let Some(id) = task.get("id").and_then(value_as_string) else {
+                self.error(
+                    position,
+                    format!("task in '{}' of lane '{}' has no id", key, lane),
+                );
+                continue;
+            };
+            let task_position = self.find_value(search_from, "id", &id).unwrap_or(position);
+            search_from = task_position.0;
+
+            if let Some(first_lane) = self.seen_ids.get(&id) {
+                let message = format!("duplicate task id '{}' (also in {})", id, first_lane);
+                self.error(task_position, message);
+            } else {
+                self.seen_ids.insert(id.clone(), lane.to_string());
+            }
+
+            if state == TaskState::InProgress
+                && task.get("claimed_by").and_then(value_as_string).is_none()
+            {
+                self.warning(
+                    task_position,
+                    format!("task '{}' is in_progress but has no claimed_by", id),
+                );
+            }
+
+            if lane == "global_backlog" && !self.known_lanes.is_empty() {
+                if let Some(target) = task.get("lane").and_then(value_as_string) {
+                    if !self.known_lanes.contains(&target) {
+                        let field_position = self
+                            .find_key(task_position.0.saturating_sub(1), "lane")
+                            .unwrap_or(task_position);
+                        self.warning(
+                            field_position,
+                            format!(
+                                "task '{}' names lane '{}', which no worker has, so it won't be dispatched",
+                                id, target
+                            ),
+                        );
+                    }
+                }
+            }
+
+            if let Some(Value::Sequence(depends_on)) = task.get("depends_on") {
+                let field_position = self
+                    .find_key(task_position.0.saturating_sub(1), "depends_on")
+                    .unwrap_or(task_position);
+                for dependency in depends_on.iter().filter_map(value_as_string) {
+                    self.dependencies
+                        .push((field_position, id.clone(), dependency));
+                }
+            }
+
+            for field in TIMESTAMP_FIELDS {
+                let Some(value) = task.get(*field) else {
+                    continue;
+                };
+                let valid = value_as_string(value).is_some_and(|v| parse_timestamp(&v).is_some());
+                if !valid {
+                    let field_position = self
+                        .find_key(task_position.0.saturating_sub(1), field)
+                        .unwrap_or(task_position);
+                    self.error(
+                        field_position,
+                        format!(
+                            "task '{}' has a malformed {} (expected RFC 3339, e.g. 2024-05-01T12:00:00Z)",
+                            id, field
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    /// A dependency that's in no lane and not archived can never be done, so
+    /// its task would wait forever
+    fn lint_dependencies(&mut self, archived_ids: &HashSet<String>) {
+        for (position, id, dependency) in std::mem::take(&mut self.dependencies) {
+            if !self.seen_ids.contains_key(&dependency) && !archived_ids.contains(&dependency) {
+                self.warning(
+                    position,
+                    format!(
+                        "task '{}' depends on unknown task '{}', so it can never be claimed",
+                        id, dependency
+                    ),
+                );
+            }
+        }
+    }
+
+    /// Line of an unindented `key:`
+    fn find_top_key(&self, key: &str) -> Option<Position> {
+        self.lines.iter().enumerate().find_map(|(index, line)| {
+            let (column, found, _) = split_key(line)?;
+            (column == 1 && found == key).then_some((index + 1, column))
+        })
+    }
+
+    /// First line after `after_line` (1-based) whose key is `key`
+    fn find_key(&self, after_line: usize, key: &str) -> Option<Position> {
+        self.lines
+            .iter()
+            .enumerate()
+            .skip(after_line)
+            .find_map(|(index, line)| {
+                let (column, found, _) = split_key(line)?;
+                (found == key).then_some((index + 1, column))
+            })
+    }
+
+    /// First line after `after_line` holding `key: value`
+    fn find_value(&self, after_line: usize, key: &str, value: &str) -> Option<Position> {
+        self.lines
+            .iter()
+            .enumerate()
+            .skip(after_line)
+            .find_map(|(index, line)| {
+                let (column, found, rest) = split_key(line)?;
+                let rest = rest.trim().trim_matches(|c| c == '"' || c == '\'');
+                (found == key && rest == value).then_some((index + 1, column))
+            })
+    }
+
+    fn push_serde_error(&mut self, error: &serde_yaml::Error) {
+        let position = error
+            .location()
+            .map(|l| (l.line(), l.column()))
+            .unwrap_or((1, 1));
+        self.error(position, error.to_string());
+    }
+
+    fn has_errors(&self) -> bool {
+        self.diagnostics
+            .iter()
+            .any(|d| d.severity == Severity::Error)
+    }
+
+    fn error(&mut self, position: Position, message: String) {
+        self.push(position, Severity::Error, message);
+    }
+
+    fn warning(&mut self, position: Position, message: String) {
+        self.push(position, Severity::Warning, message);
+    }
+
+    fn push(&mut self, (line, column): Position, severity: Severity, message: String) {
+        self.diagnostics.push(Diagnostic {
+            line,
+            column,
+            severity,
+            message,
+        });
+    }
+}
+
+/// Split `  - key: rest` into (1-based column of key, key, rest)
+fn split_key(line: &str) -> Option<(usize, &str, &str)> {
+    let content = line.trim_start();
+    if content.starts_with('#') {
+        return None;
+    }
+    let mut column = line.len() - content.len() + 1;
+    let content = match content.strip_prefix("- ") {
+        Some(rest) => {
+            let trimmed = rest.trim_start();
+            column += 2 + rest.len() - trimmed.len();
+            trimmed
+        }
+        None => content,
+    };
+    let (key, rest) = content.split_once(':')?;
+    if !(rest.is_empty() || rest.starts_with(' ')) {
+        return None;
+    }
+    Some((
+        column,
+        key.trim().trim_matches(|c| c == '"' || c == '\''),
+        rest,
+    ))
+}
+
+fn value_as_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Nearest state key to a misspelling, if it's close enough to be a typo
+fn closest_state(key: &str) -> Option<&'static str> {
+    TaskState::ALL
+        .iter()
+        .map(|state| (state.as_str(), edit_distance(key, state.as_str())))
+        .filter(|(_, distance)| *distance <= 3)
+        .min_by_key(|(_, distance)| *distance)
+        .map(|(state, _)| state)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BROKEN_YAML: &str = "backend:
+  fixes:
+    backlog:
+      - id: fix-login
+    in_progres: []
+  features:
+    backlog:
+    in_progress:
+      - id: fix-login
+        claimed_at: yesterday
+android-sdk:
+  backlog: []
+  in_progress: []
+  done: []
+";
+
+    #[test]
+    fn test_lint_reports_positions() {
+        let known = vec!["backend/fixes".to_string(), "backend/features".to_string()];
+        let diagnostics: Vec<String> = lint_tasks(BROKEN_YAML, &known, &HashSet::new())
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        assert_eq!(
+            diagnostics,
+            [
+                "5:5: error: unknown key 'in_progres' in lane 'backend/fixes' (expected backlog, in_progress or done), did you mean 'in_progress'?",
+                "7:5: error: empty 'backlog:' in lane 'backend/features', write 'backlog: []'",
+                "9:9: error: duplicate task id 'fix-login' (also in backend/fixes)",
+                "9:9: warning: task 'fix-login' is in_progress but has no claimed_by",
+                "10:9: error: task 'fix-login' has a malformed claimed_at (expected RFC 3339, e.g. 2024-05-01T12:00:00Z)",
+                "11:1: warning: lane 'android-sdk' is not assigned to any worker (known lanes: backend/fixes, backend/features)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lint_reports_yaml_syntax_errors() {
+        let diagnostics = lint_tasks("api:\n  backlog: [\n", &[], &HashSet::new());
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert!(diagnostics[0].line >= 2);
+    }
+
+    #[test]
+    fn test_lint_reports_unknown_dependencies() {
+        let content = "api:
+  backlog:
+    - id: deploy
+      depends_on: [migrate, release-notes, typo]
+    - id: migrate
+  done: []
+";
+        let archived = HashSet::from(["release-notes".to_string()]);
+        let diagnostics: Vec<String> = lint_tasks(content, &[], &archived)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            diagnostics,
+            ["4:7: warning: task 'deploy' depends on unknown task 'typo', so it can never be claimed"]
+        );
+    }
+
+    #[test]
+    fn test_lint_treats_project_with_null_lane_as_nested() {
+        let content = "docs:
+  api:
+  guides:
+    backlog: []
+";
+        let known = vec!["docs/api".to_string(), "docs/guides".to_string()];
+        let diagnostics: Vec<String> = lint_tasks(content, &known, &HashSet::new())
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            diagnostics,
+            ["2:3: error: empty lane 'docs/api', write 'api: {}'"]
+        );
+    }
+}
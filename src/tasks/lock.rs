@@ -0,0 +1,107 @@
+use std::fs::{File, OpenOptions};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use nix::errno::Errno;
+use nix::fcntl::{Flock, FlockArg};
+
+/// How long to wait for another process to release the tasks file lock
+pub const LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The lock was still held by someone else when the wait ran out
+#[derive(Debug)]
+pub struct LockBusy(PathBuf);
+
+impl std::fmt::Display for LockBusy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Timed out waiting for lock on {} (another hive task command may be stuck)",
+            self.0.display()
+        )
+    }
+}
+
+impl std::error::Error for LockBusy {}
+
+/// Advisory lock on a tasks file, released when dropped.
+///
+/// The lock is taken on a sidecar `.<name>.lock` file rather than the tasks
+/// file itself, because writers replace the tasks file with a rename.
+pub struct TasksLock {
+    _lock: Flock<File>,
+}
+
+pub fn lock_path(tasks_path: &Path) -> PathBuf {
+    let file_name = tasks_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("tasks.yaml");
+    tasks_path.with_file_name(format!(".{}.lock", file_name))
+}
+
+/// Lock for writing: excludes all other readers and writers
+pub fn lock_exclusive(tasks_path: &Path) -> Result<TasksLock> {
+    lock_exclusive_within(tasks_path, LOCK_TIMEOUT)
+}
+
+/// Lock for writing, failing with `LockBusy` after `wait`
+pub fn lock_exclusive_within(tasks_path: &Path, wait: Duration) -> Result<TasksLock> {
+    acquire(tasks_path, FlockArg::LockExclusiveNonblock, wait)
+}
+
+/// Lock for reading: excludes writers but not other readers
+pub fn lock_shared(tasks_path: &Path) -> Result<TasksLock> {
+    acquire(tasks_path, FlockArg::LockSharedNonblock, LOCK_TIMEOUT)
+}
+
+/// Lock for reading, or `None` if the lock file can't be created (e.g. in a
+/// read-only directory, where nothing can rewrite the tasks file either).
+/// Timeouts and other lock failures are errors.
+pub fn lock_shared_if_writable(tasks_path: &Path) -> Result<Option<TasksLock>> {
+    lock_shared_if_writable_within(tasks_path, LOCK_TIMEOUT)
+}
+
+/// `lock_shared_if_writable`, failing with `LockBusy` after `wait`
+pub fn lock_shared_if_writable_within(
+    tasks_path: &Path,
+    wait: Duration,
+) -> Result<Option<TasksLock>> {
+    match acquire(tasks_path, FlockArg::LockSharedNonblock, wait) {
+        Ok(lock) => Ok(Some(lock)),
+        Err(e) => match e.downcast_ref::<std::io::Error>().map(|e| e.kind()) {
+            Some(ErrorKind::PermissionDenied | ErrorKind::ReadOnlyFilesystem) => Ok(None),
+            _ => Err(e),
+        },
+    }
+}
+
+fn acquire(tasks_path: &Path, arg: FlockArg, wait: Duration) -> Result<TasksLock> {
+    let path = lock_path(tasks_path);
+    let mut file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&path)
+        .with_context(|| format!("Failed opening lock file {}", path.display()))?;
+
+    let start = Instant::now();
+    loop {
+        match Flock::lock(file, arg) {
+            Ok(lock) => return Ok(TasksLock { _lock: lock }),
+            Err((returned, Errno::EWOULDBLOCK)) if start.elapsed() < wait => {
+                file = returned;
+                thread::sleep(Duration::from_millis(25));
+            }
+            Err((_, Errno::EWOULDBLOCK)) => {
+                return Err(LockBusy(tasks_path.to_path_buf()).into());
+            }
+            Err((_, errno)) => {
+                return Err(errno).with_context(|| format!("Failed locking {}", path.display()));
+            }
+        }
+    }
+}
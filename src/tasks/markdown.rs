@@ -0,0 +1,564 @@
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Sender;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+
+use super::lint::{Diagnostic, Severity};
+use super::lock;
+use super::source::TaskBackend;
+use super::watcher::{spawn_tasks_watcher, NudgeRequest};
+use super::yaml::{
+    add_task, claim_task, complete_task, requeue_task, write_atomic, Completion, Task,
+    TaskLocation, TaskState, TasksFile,
+};
+
+/// Tasks kept as a Markdown checklist, one `## lane` heading per lane:
+///
+/// ```markdown
+/// ## backend/fixes
+/// - [ ] `fix-login` Fix the login redirect
+/// - [ ] Add rate limiting (@worker-3)
+/// - [x] Bump dependencies
+///   - summary: Bumped serde and tokio
+///   - pr: https://github.com/acme/api/pull/12
+/// ```
+///
+/// Unchecked items are backlog, unchecked items annotated `(@worker)` are in
+/// progress, and checked items are done. An item's id is a leading `code span`
+/// if it has one, otherwise its text in kebab-case. Completing an item writes
+/// its `summary`, `pr`, `branch` and `files` in a sub-list under it, and
+/// requeueing one writes who held it and why as `requeued`. Items under a
+/// heading that clashes with another lane (`## docs` beside `## docs/api`) are
+/// left out, and `lint` points at them.
+pub struct MarkdownTasks {
+    path: PathBuf,
+}
+
+/// A checklist item and the line it came from
+struct Item {
+    line: usize,
+    lane: String,
+    state: TaskState,
+    task: Task,
+}
+
+impl MarkdownTasks {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// Apply a change made on the parsed tasks back to the item's line.
+    /// `rewrite` gets the line and the changed task.
+    fn update_line(
+        &self,
+        id: &str,
+        change: impl FnOnce(&mut TasksFile) -> Result<TaskLocation>,
+        rewrite: impl FnOnce(&str, &Task) -> String,
+    ) -> Result<TaskLocation> {
+        let _lock = lock::lock_exclusive(&self.path)?;
+        let content = read(&self.path)?;
+        let items = parse_items(&content);
+
+        let (mut tasks, _) = to_tasks_file(&items);
+        let location = change(&mut tasks)?;
+        let task = &tasks
+            .lane(&location.lane)
+            .context("Changed task's lane is missing")?
+            .tasks(location.state)[location.index];
+        let item = items
+            .iter()
+            .find(|item| item.lane == location.lane && item.task.id == id)
+            .with_context(|| format!("Task '{}' not found in {}", id, self.path.display()))?;
+
+        // The rewritten line may hold several lines, joined below
+        let mut lines: Vec<String> = content.lines().map(String::from).collect();
+        lines[item.line] = rewrite(&lines[item.line], task);
+        let mut updated = lines.join("\n");
+        if content.ends_with('\n') {
+            updated.push('\n');
+        }
+        write_atomic(&self.path, &updated)?;
+        Ok(location)
+    }
+}
+
+impl TaskBackend for MarkdownTasks {
+    fn describe(&self) -> String {
+        self.path.display().to_string()
+    }
+
+    fn load(&self) -> Result<TasksFile> {
+        load_markdown_locked(&self.path)
+    }
+
+    fn claim(&self, id: &str, lane: Option<&str>, worker: &str) -> Result<TaskLocation> {
+        self.update_line(
+            id,
+            |tasks| claim_task(tasks, id, lane, worker),
+            |line, _| format!("{} (@{})", line.trim_end(), worker),
+        )
+    }
+
+    fn complete(
+        &self,
+        id: &str,
+        lane: Option<&str>,
+        completion: Completion,
+    ) -> Result<TaskLocation> {
+        let details = completion_details(&completion);
+        self.update_line(
+            id,
+            |tasks| complete_task(tasks, id, lane, completion),
+            |line, _| format!("{}{}", line.replacen("[ ]", "[x]", 1), details),
+        )
+    }
+
+    fn requeue(&self, id: &str, lane: Option<&str>, reason: &str) -> Result<TaskLocation> {
+        self.update_line(
+            id,
+            |tasks| requeue_task(tasks, id, lane, reason),
+            |line, task| {
+                let line = match split_worker(line.trim_end()) {
+                    (text, Some(_)) => text,
+                    (_, None) => line,
+                };
+                match &task.requeued_from {
+                    Some(requeued_from) => format!("{}\n  - requeued: {}", line, requeued_from),
+                    None => line.to_string(),
+                }
+            },
+        )
+    }
+
+    fn add(&self, lane: &str, task: Task) -> Result<()> {
+        let _lock = lock::lock_exclusive(&self.path)?;
+        let content = read(&self.path)?;
+        let item = match &task.title {
+            Some(title) => format!("- [ ] `{}` {}", task.id, title),
+            None => format!("- [ ] `{}`", task.id),
+        };
+        // Same checks as the other sources: lane name, duplicate ids
+        add_task(&mut to_tasks_file(&parse_items(&content)).0, lane, task)?;
+
+        let mut lines: Vec<String> = content.lines().map(String::from).collect();
+        let heading = lines
+            .iter()
+            .position(|line| line.strip_prefix("## ").is_some_and(|h| h.trim() == lane));
+        match heading {
+            Some(start) => {
+                // After the last non-blank line of the lane's section
+                let end = lines[start + 1..]
+                    .iter()
+                    .position(|line| line.starts_with("## "))
+                    .map_or(lines.len(), |offset| start + 1 + offset);
+                let at = (start + 1..end)
+                    .rev()
+                    .find(|&i| !lines[i].trim().is_empty())
+                    .map_or(start + 1, |i| i + 1);
+                lines.insert(at, item);
+            }
+            None => {
+                if lines.last().is_some_and(|line| !line.trim().is_empty()) {
+                    lines.push(String::new());
+                }
+                lines.push(format!("## {}", lane));
+                lines.push(item);
+            }
+        }
+        let mut updated = lines.join("\n");
+        updated.push('\n');
+        write_atomic(&self.path, &updated)
+    }
+
+    fn lint(&self, _known_lanes: &[String]) -> Vec<Diagnostic> {
+        match std::fs::read_to_string(&self.path) {
+            Ok(content) => to_tasks_file(&parse_items(&content)).1,
+            Err(_) => Vec::new(),
+        }
+    }
+
+    fn watch(&self, nudge_tx: Sender<NudgeRequest>, log_path: PathBuf) -> Result<()> {
+        spawn_tasks_watcher(
+            self.path.clone(),
+            load_markdown_locked,
+            nudge_tx,
+            Duration::from_secs(10),
+            Duration::from_secs(5),
+            log_path,
+        )
+    }
+}
+
+fn read(path: &Path) -> Result<String> {
+    std::fs::read_to_string(path)
+        .with_context(|| format!("Failed reading tasks file at {}", path.display()))
+}
+
+fn load_markdown_locked(path: &Path) -> Result<TasksFile> {
+    let _lock = lock::lock_shared_if_writable(path)?;
+    parse_markdown(&read(path)?)
+}
+
+pub fn parse_markdown(content: &str) -> Result<TasksFile> {
+    Ok(to_tasks_file(&parse_items(content)).0)
+}
+
+/// The items as a tasks file, leaving out those whose lane clashes with an
+/// earlier heading's, with an error for each
+fn to_tasks_file(items: &[Item]) -> (TasksFile, Vec<Diagnostic>) {
+    let mut tasks = TasksFile::default();
+    let mut conflicts = Vec::new();
+    for item in items {
+        match tasks.ensure_lane_mut(&item.lane) {
+            Ok(lane_tasks) => lane_tasks.tasks_mut(item.state).push(item.task.clone()),
+            Err(e) => conflicts.push(Diagnostic {
+                line: item.line + 1,
+                column: 1,
+                severity: Severity::Error,
+                message: format!(
+                    "item '{}' skipped, lane '{}': {:#}",
+                    item.task.id, item.lane, e
+                ),
+            }),
+        }
+    }
+    (tasks, conflicts)
+}
+
+fn parse_items(content: &str) -> Vec<Item> {
+    let mut items: Vec<Item> = Vec::new();
+    let mut lane: Option<String> = None;
+    // Whether the lines so far since the last item are its details
+    let mut under_item = false;
+
+    for (line_no, line) in content.lines().enumerate() {
+        if under_item {
+            if let Some(item) = items.last_mut() {
+                if parse_detail(line, &mut item.task) {
+                    continue;
+                }
+            }
+        }
+        under_item = false;
+
+        if let Some(heading) = line.strip_prefix("## ") {
+            let heading = heading.trim();
+            lane = (!heading.is_empty()).then(|| heading.to_string());
+            continue;
+        }
+        let Some(lane) = &lane else {
+            continue;
+        };
+        let Some((checked, text)) = parse_checkbox(line) else {
+            continue;
+        };
+
+        let (text, worker) = split_worker(text);
+        let (id, title) = split_id(text);
+        let mut id = id.unwrap_or_else(|| slugify(&title));
+        if id.is_empty() {
+            continue;
+        }
+        // Identical items in one lane still need distinct ids
+        let base = id.clone();
+        let mut n = 2;
+        while items.iter().any(|i| &i.lane == lane && i.task.id == id) {
+            id = format!("{}-{}", base, n);
+            n += 1;
+        }
+
+        let state = match (checked, &worker) {
+            (true, _) => TaskState::Done,
+            (false, Some(_)) => TaskState::InProgress,
+            (false, None) => TaskState::Backlog,
+        };
+        items.push(Item {
+            line: line_no,
+            lane: lane.clone(),
+            state,
+            task: Task {
+                id,
+                title: (!title.is_empty()).then_some(title),
+                claimed_by: worker,
+                ..Default::default()
+            },
+        });
+        under_item = true;
+    }
+
+    items
+}
+
+/// `- [ ] text` / `* [x] text` at the start of a line
+fn parse_checkbox(line: &str) -> Option<(bool, &str)> {
+    let rest = line
+        .strip_prefix("- [")
+        .or_else(|| line.strip_prefix("* ["))?;
+    let mut chars = rest.chars();
+    let mark = chars.next()?;
+    let rest = chars.as_str().strip_prefix(']')?;
+    let checked = match mark {
+        ' ' => false,
+        'x' | 'X' => true,
+        _ => return None,
+    };
+    Some((checked, rest.trim()))
+}
+
+/// The sub-list `complete` writes under an item, each line starting with a
+/// newline
+fn completion_details(completion: &Completion) -> String {
+    let mut details = Vec::new();
+    if let Some(summary) = &completion.summary {
+        let summary: Vec<&str> = summary.split_whitespace().collect();
+        details.push(("summary", summary.join(" ")));
+    }
+    if let Some(pr_url) = &completion.pr_url {
+        details.push(("pr", pr_url.clone()));
+    }
+    if let Some(branch) = &completion.branch {
+        details.push(("branch", branch.clone()));
+    }
+    if !completion.files_changed.is_empty() {
+        details.push(("files", completion.files_changed.join(", ")));
+    }
+    details
+        .iter()
+        .map(|(key, value)| format!("\n  - {}: {}", key, value))
+        .collect()
+}
+
+/// Read an indented `- key: value` line from under an item into its task.
+/// Returns whether the line was one.
+fn parse_detail(line: &str, task: &mut Task) -> bool {
+    if !line.starts_with([' ', '\t']) {
+        return false;
+    }
+    let Some((key, value)) = line
+        .trim_start()
+        .strip_prefix("- ")
+        .and_then(|rest| rest.split_once(':'))
+    else {
+        return false;
+    };
+    let value = value.trim().to_string();
+    match key {
+        "summary" => task.summary = Some(value),
+        "pr" => task.pr_url = Some(value),
+        "branch" => task.branch = Some(value),
+        "files" => task.files_changed = Some(value.split(", ").map(String::from).collect()),
+        "requeued" => task.requeued_from = Some(value),
+        _ => return false,
+    }
+    true
+}
+
+/// Split a trailing `(@worker)` annotation off an item
+fn split_worker(text: &str) -> (&str, Option<String>) {
+    if let Some(without_paren) = text.strip_suffix(')') {
+        if let Some(start) = without_paren.rfind("(@") {
+            let worker = without_paren[start + 2..].trim();
+            if !worker.is_empty() && !worker.contains(char::is_whitespace) {
+                return (text[..start].trim_end(), Some(worker.to_string()));
+            }
+        }
+    }
+    (text, None)
+}
+
+/// Split a leading `` `id` `` code span off an item
+fn split_id(text: &str) -> (Option<String>, String) {
+    if let Some(rest) = text.strip_prefix('`') {
+        if let Some((id, title)) = rest.split_once('`') {
+            let title = title.trim_start_matches([':', ' ', '-']).trim();
+            return (Some(id.trim().to_string()), title.to_string());
+        }
+    }
+    (None, text.to_string())
+}
+
+fn slugify(text: &str) -> String {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TODO_MD: &str = "# TODO
+
+Notes before any lane are ignored.
+- [ ] not a task
+
+## backend/fixes
+- [ ] `fix-login` Fix the login redirect
+- [ ] Add rate limiting (@worker-3)
+- [x] Bump dependencies
+  - [ ] nested items are ignored
+
+## docs
+* [ ] Write the README
+";
+
+    #[test]
+    fn test_parse_markdown_lanes_and_states() {
+        let tasks = parse_markdown(TODO_MD).unwrap();
+
+        let fixes = tasks.lane("backend/fixes").unwrap();
+        assert_eq!(fixes.backlog[0].id, "fix-login");
+        assert_eq!(
+            fixes.backlog[0].title.as_deref(),
+            Some("Fix the login redirect")
+        );
+        assert_eq!(fixes.in_progress[0].id, "add-rate-limiting");
+        assert_eq!(fixes.in_progress[0].claimed_by.as_deref(), Some("worker-3"));
+        assert_eq!(fixes.done[0].id, "bump-dependencies");
+        assert_eq!(
+            tasks.lane("docs").unwrap().backlog[0].id,
+            "write-the-readme"
+        );
+    }
+
+    #[test]
+    fn test_claim_and_complete_rewrite_lines() {
+        let dir = std::env::temp_dir().join(format!("hive-markdown-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("TODO.md");
+        std::fs::write(&path, TODO_MD).unwrap();
+        let source = MarkdownTasks::new(path.clone());
+
+        source.claim("fix-login", None, "worker-1").unwrap();
+        assert!(source.claim("fix-login", None, "worker-2").is_err());
+        let completion = Completion {
+            summary: Some("Limit logins\nper IP".to_string()),
+            pr_url: Some("https://github.com/acme/api/pull/7".to_string()),
+            branch: None,
+            files_changed: vec!["src/auth.rs".to_string(), "src/ip.rs".to_string()],
+        };
+        source
+            .complete("add-rate-limiting", None, completion)
+            .unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("- [ ] `fix-login` Fix the login redirect (@worker-1)\n"));
+        assert!(content.contains(
+            "- [x] Add rate limiting (@worker-3)
+  - summary: Limit logins per IP
+  - pr: https://github.com/acme/api/pull/7
+  - files: src/auth.rs, src/ip.rs
+- [x] Bump dependencies
+"
+        ));
+        let tasks = source.load().unwrap();
+        let done = &tasks.lane("backend/fixes").unwrap().done;
+        assert_eq!(done[0].summary.as_deref(), Some("Limit logins per IP"));
+        assert_eq!(
+            done[0].pr_url.as_deref(),
+            Some("https://github.com/acme/api/pull/7")
+        );
+        assert_eq!(
+            done[0].files_changed,
+            Some(vec!["src/auth.rs".to_string(), "src/ip.rs".to_string()])
+        );
+        assert_eq!(done[1].summary, None);
+        assert!(content.starts_with("# TODO\n\nNotes before any lane are ignored.\n"));
+
+        let counts = source.counts("backend/fixes").unwrap();
+        assert_eq!((counts.backlog, counts.in_progress, counts.done), (0, 1, 2));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_add_appends_to_lane_section() {
+        let dir = std::env::temp_dir().join(format!("hive-markdown-add-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("TODO.md");
+        std::fs::write(&path, TODO_MD).unwrap();
+        let source = MarkdownTasks::new(path.clone());
+
+        let task = |id: &str, title: Option<&str>| Task {
+            id: id.to_string(),
+            title: title.map(String::from),
+            ..Default::default()
+        };
+        source
+            .add("backend/fixes", task("add-cache", Some("Cache sessions")))
+            .unwrap();
+        source.add("infra", task("pin-ci", None)).unwrap();
+        assert!(source.add("docs", task("write-the-readme", None)).is_err());
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains(
+            "  - [ ] nested items are ignored
+- [ ] `add-cache` Cache sessions
+
+## docs
+"
+        ));
+        assert!(content.ends_with("* [ ] Write the README\n\n## infra\n- [ ] `pin-ci`\n"));
+        let tasks = source.load().unwrap();
+        assert_eq!(
+            tasks.lane("backend/fixes").unwrap().backlog[1].id,
+            "add-cache"
+        );
+        assert_eq!(tasks.lane("infra").unwrap().backlog[0].id, "pin-ci");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_requeue_writes_reason_under_item() {
+        let dir =
+            std::env::temp_dir().join(format!("hive-markdown-requeue-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("TODO.md");
+        std::fs::write(&path, TODO_MD).unwrap();
+        let source = MarkdownTasks::new(path.clone());
+
+        source
+            .requeue("add-rate-limiting", None, "pane exited")
+            .unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("- [ ] Add rate limiting\n  - requeued: worker-3 (pane exited, "));
+        let tasks = source.load().unwrap();
+        let requeued = &tasks.lane("backend/fixes").unwrap().backlog[1];
+        assert_eq!(requeued.id, "add-rate-limiting");
+        assert!(requeued
+            .requeued_from
+            .as_deref()
+            .is_some_and(|from| from.starts_with("worker-3 (pane exited, ")));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_clashing_headings_skip_items_and_lint() {
+        let dir = std::env::temp_dir().join(format!("hive-markdown-clash-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("TODO.md");
+        std::fs::write(
+            &path,
+            "## docs\n- [ ] Write the README\n\n## docs/api\n- [ ] Document the endpoints\n",
+        )
+        .unwrap();
+        let source = MarkdownTasks::new(path.clone());
+
+        let tasks = source.load().unwrap();
+        assert_eq!(tasks.lane("docs").unwrap().backlog.len(), 1);
+        let diagnostics: Vec<String> = source.lint(&[]).iter().map(ToString::to_string).collect();
+        assert_eq!(
+            diagnostics,
+            ["5:1: error: item 'document-the-endpoints' skipped, lane 'docs/api': 'docs' is a single-lane project, use lane 'docs'"]
+        );
+        source.claim("write-the-readme", None, "worker-1").unwrap();
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,5 +1,40 @@
+pub mod archive;
+pub mod dispatch;
+pub mod events;
+pub mod github;
+pub mod history;
+pub mod import;
+pub mod lint;
+pub mod lock;
+pub mod markdown;
+pub mod report;
+pub mod schedule;
+pub mod source;
+pub mod stale;
+pub mod template;
+pub mod verify;
 pub mod watcher;
 pub mod yaml;
 
+pub use archive::{archive_dir, load_archive_files, ArchivedTasks};
+pub use dispatch::{dispatch_global_backlog, route_task, Dispatched};
+pub use events::{diff_tasks, TaskEvent};
+pub use github::GithubTasks;
+pub use history::{append_history, read_history, transitions, HistoryEntry, HISTORY_FILE};
+pub use import::{import_todos, scan_todos, CodeTodo};
+pub use lint::{lint_tasks, Diagnostic, Severity};
+pub use markdown::MarkdownTasks;
+pub use report::{build_report, Report};
+pub use schedule::Schedule;
+pub use source::{open_backend, open_workspace_backend, TaskBackend, YamlTasks};
+pub use stale::{find_stale_claims, PaneActivity, StaleClaim, StalePolicy};
+pub use template::{parse_vars, TaskTemplate};
+pub use verify::{run_verification, Verification};
 pub use watcher::{spawn_yaml_watcher, NudgeRequest};
-pub use yaml::{counts_for_lane, load_tasks, LaneTasks, ProjectEntry, Task, TaskCounts, TasksFile};
+pub use yaml::{
+    add_task, add_task_unless_open, answer_question, ask_question, check_lane_known, claim_task,
+    complete_task, counts_for_lane, delete_task, load_tasks, load_tasks_locked, move_task,
+    parse_duration, parse_timestamp, record_verification, reprioritize_task, requeue_task,
+    save_tasks, timestamp_now, update_tasks, ClaimError, Completion, LaneTasks, Priority,
+    ProjectEntry, Task, TaskCounts, TaskLocation, TaskState, TasksFile,
+};
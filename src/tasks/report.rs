@@ -0,0 +1,379 @@
+use std::collections::HashSet;
+use std::fmt::Write as _;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use super::stale::format_age;
+use super::yaml::{counts_for_lane, parse_timestamp, Task, TasksFile};
+
+/// Per-lane standup summary built by `hive report`
+#[derive(Debug, Clone, Serialize)]
+pub struct Report {
+    pub generated_at: String,
+    /// Only tasks completed after this are listed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since: Option<String>,
+    pub lanes: Vec<LaneReport>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LaneReport {
+    pub lane: String,
+    /// Repo the lane's worker runs in
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    pub backlog: usize,
+    pub blocked: usize,
+    pub completed: Vec<CompletedTask>,
+    pub in_progress: Vec<InProgressTask>,
+    pub questions: Vec<OpenQuestion>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CompletedTask {
+    pub id: String,
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completed_by: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pr_url: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub files_changed: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InProgressTask {
+    pub id: String,
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub claimed_by: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub claimed_at: Option<String>,
+    /// How long ago it was claimed, e.g. `3h`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub claim_age: Option<String>,
+}
+
+/// A worker's question the architect hasn't answered
+#[derive(Debug, Clone, Serialize)]
+pub struct OpenQuestion {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub asked_by: Option<String>,
+    pub question: String,
+}
+
+/// Summarize `tasks` and the archived done tasks per lane. `lanes` are the
+/// configured lanes in order, each with the project it belongs to; other
+/// lanes in the file are listed after them. With `since`, only tasks
+/// completed at or after it are listed.
+pub fn build_report(
+    tasks: &TasksFile,
+    archived: &[TasksFile],
+    lanes: &[(String, Option<String>)],
+    since: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> Report {
+    let mut order: Vec<(String, Option<String>)> = lanes.to_vec();
+    let mut seen: HashSet<String> = lanes.iter().map(|(lane, _)| lane.clone()).collect();
+    for (lane, _) in tasks.lanes() {
+        if seen.insert(lane.clone()) {
+            order.push((lane, None));
+        }
+    }
+
+    let completed_since = |task: &&Task| {
+        since.is_none_or(|since| {
+            task.completed_at
+                .as_deref()
+                .and_then(parse_timestamp)
+                .is_some_and(|at| at >= since)
+        })
+    };
+
+    let lanes = order
+        .into_iter()
+        .map(|(lane, project)| {
+            let counts = counts_for_lane(tasks, &lane);
+            let open = tasks.lane(&lane);
+            let done = archived
+                .iter()
+                .chain(std::iter::once(tasks))
+                .filter_map(|file| file.lane(&lane))
+                .flat_map(|lane_tasks| &lane_tasks.done);
+            let in_progress = open.map(|l| l.in_progress.as_slice()).unwrap_or_default();
+            LaneReport {
+                project,
+                backlog: counts.backlog,
+                blocked: counts.blocked,
+                completed: done.filter(completed_since).map(completed).collect(),
+                in_progress: in_progress.iter().map(|task| claimed(task, now)).collect(),
+                questions: in_progress
+                    .iter()
+                    .filter(|task| task.awaiting_answer())
+                    .map(|task| OpenQuestion {
+                        id: task.id.clone(),
+                        asked_by: task.claimed_by.clone(),
+                        question: task.question.clone().unwrap_or_default(),
+                    })
+                    .collect(),
+                lane,
+            }
+        })
+        .collect();
+
+    Report {
+        generated_at: now.to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        since: since.map(|at| at.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)),
+        lanes,
+    }
+}
+
+fn title(task: &Task) -> String {
+    task.title.clone().unwrap_or_else(|| task.id.clone())
+}
+
+fn completed(task: &Task) -> CompletedTask {
+    CompletedTask {
+        id: task.id.clone(),
+        title: title(task),
+        completed_by: task.claimed_by.clone(),
+        completed_at: task.completed_at.clone(),
+        summary: task.summary.clone(),
+        pr_url: task.pr_url.clone(),
+        files_changed: task.files_changed.clone().unwrap_or_default(),
+    }
+}
+
+fn claimed(task: &Task, now: DateTime<Utc>) -> InProgressTask {
+    InProgressTask {
+        id: task.id.clone(),
+        title: title(task),
+        claimed_by: task.claimed_by.clone(),
+        claimed_at: task.claimed_at.clone(),
+        claim_age: task
+            .claimed_at
+            .as_deref()
+            .and_then(parse_timestamp)
+            .and_then(|at| (now - at).to_std().ok())
+            .map(format_age),
+    }
+}
+
+impl Report {
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        match &self.since {
+            Some(since) => writeln!(out, "# Hive report since {}", since),
+            None => writeln!(out, "# Hive report"),
+        }
+        .ok();
+        for lane in &self.lanes {
+            let project = lane
+                .project
+                .as_deref()
+                .map(|p| format!(" ({})", p))
+                .unwrap_or_default();
+            writeln!(out, "\n## {}{}\n", lane.lane, project).ok();
+            writeln!(out, "Backlog: {} ({} blocked)", lane.backlog, lane.blocked).ok();
+
+            if !lane.completed.is_empty() {
+                out.push_str("\n### Completed\n\n");
+                for task in &lane.completed {
+                    write!(out, "- **{}** {}", task.id, task.title).ok();
+                    if let Some(summary) = &task.summary {
+                        write!(out, ": {}", summary).ok();
+                    }
+                    if let Some(pr_url) = &task.pr_url {
+                        write!(out, " ([PR]({}))", pr_url).ok();
+                    }
+                    out.push('\n');
+                    if !task.files_changed.is_empty() {
+                        writeln!(out, "  - Files: {}", task.files_changed.join(", ")).ok();
+                    }
+                }
+            }
+
+            if !lane.in_progress.is_empty() {
+                out.push_str("\n### In progress\n\n");
+                for task in &lane.in_progress {
+                    write!(out, "- **{}** {}", task.id, task.title).ok();
+                    if let Some(by) = &task.claimed_by {
+                        write!(out, " ({}", by).ok();
+                        if let Some(age) = &task.claim_age {
+                            write!(out, ", claimed {} ago", age).ok();
+                        }
+                        out.push(')');
+                    }
+                    out.push('\n');
+                }
+            }
+
+            if !lane.questions.is_empty() {
+                out.push_str("\n### Open questions\n\n");
+                for question in &lane.questions {
+                    let by = question
+                        .asked_by
+                        .as_deref()
+                        .map(|by| format!(" ({})", by))
+                        .unwrap_or_default();
+                    writeln!(out, "- **{}**{}: {}", question.id, by, question.question).ok();
+                }
+            }
+        }
+        out
+    }
+
+    /// One row per completed task, in-progress task, open question and lane backlog
+    pub fn to_csv(&self) -> String {
+        let mut out =
+            String::from("project,lane,kind,id,title,worker,time,detail,pr_url,files_changed\n");
+        for lane in &self.lanes {
+            let project = lane.project.as_deref().unwrap_or("");
+            let mut row = |fields: [&str; 8]| {
+                let cells: Vec<String> = [project, lane.lane.as_str()]
+                    .iter()
+                    .chain(fields.iter())
+                    .map(|cell| csv_cell(cell))
+                    .collect();
+                out.push_str(&cells.join(","));
+                out.push('\n');
+            };
+            let backlog = format!("{} ({} blocked)", lane.backlog, lane.blocked);
+            row(["backlog", "", "", "", "", &backlog, "", ""]);
+            for task in &lane.completed {
+                row([
+                    "completed",
+                    &task.id,
+                    &task.title,
+                    task.completed_by.as_deref().unwrap_or(""),
+                    task.completed_at.as_deref().unwrap_or(""),
+                    task.summary.as_deref().unwrap_or(""),
+                    task.pr_url.as_deref().unwrap_or(""),
+                    &task.files_changed.join(";"),
+                ]);
+            }
+            for task in &lane.in_progress {
+                row([
+                    "in_progress",
+                    &task.id,
+                    &task.title,
+                    task.claimed_by.as_deref().unwrap_or(""),
+                    task.claimed_at.as_deref().unwrap_or(""),
+                    task.claim_age.as_deref().unwrap_or(""),
+                    "",
+                    "",
+                ]);
+            }
+            for question in &lane.questions {
+                row([
+                    "question",
+                    &question.id,
+                    "",
+                    question.asked_by.as_deref().unwrap_or(""),
+                    "",
+                    &question.question,
+                    "",
+                    "",
+                ]);
+            }
+        }
+        out
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+}
+
+/// Quote a CSV cell when it holds a comma, quote or line break
+fn csv_cell(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_report_summarizes_lanes_with_archive() {
+        let tasks: TasksFile = serde_yaml::from_str(
+            r#"
+backend:
+  api:
+    backlog:
+      - id: add-cache
+        depends_on: [add-metrics]
+      - id: add-metrics
+    in_progress:
+      - id: fix-login
+        title: Fix login
+        claimed_by: worker-2
+        claimed_at: "2024-05-06T09:00:00Z"
+        question: "Keep the old endpoint?"
+    done:
+      - id: old-task
+        completed_at: "2024-05-01T09:00:00Z"
+      - id: add-logging
+        title: Add logging
+        claimed_by: worker-2
+        completed_at: "2024-05-06T08:00:00Z"
+        summary: "Logs requests, with ids"
+        pr_url: https://example.com/pr/1
+        files_changed: [src/log.rs, src/main.rs]
+"#,
+        )
+        .unwrap();
+        let archived: TasksFile = serde_yaml::from_str(
+            r#"
+backend:
+  api:
+    done:
+      - id: archived-task
+        completed_at: "2024-05-06T07:00:00Z"
+"#,
+        )
+        .unwrap();
+        let now = parse_timestamp("2024-05-06T12:00:00Z").unwrap();
+        let since = parse_timestamp("2024-05-05T12:00:00Z");
+        let lanes = [
+            ("backend/api".to_string(), Some("backend".to_string())),
+            ("frontend".to_string(), Some("web".to_string())),
+        ];
+
+        let report = build_report(&tasks, &[archived], &lanes, since, now);
+        let names: Vec<&str> = report.lanes.iter().map(|l| l.lane.as_str()).collect();
+        assert_eq!(names, vec!["backend/api", "frontend"]);
+
+        let api = &report.lanes[0];
+        assert_eq!((api.backlog, api.blocked), (2, 1));
+        let completed: Vec<&str> = api.completed.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(completed, vec!["archived-task", "add-logging"]);
+        assert_eq!(api.in_progress[0].claim_age.as_deref(), Some("3h"));
+        assert_eq!(api.questions[0].question, "Keep the old endpoint?");
+
+        let markdown = report.to_markdown();
+        assert!(markdown.contains("## backend/api (backend)"));
+        assert!(markdown.contains(
+            "- **add-logging** Add logging: Logs requests, with ids ([PR](https://example.com/pr/1))"
+        ));
+        assert!(markdown.contains("(worker-2, claimed 3h ago)"));
+
+        let csv = report.to_csv();
+        assert!(csv.contains(
+            "backend,backend/api,completed,add-logging,Add logging,worker-2,\
+             2024-05-06T08:00:00Z,\"Logs requests, with ids\",https://example.com/pr/1,\
+             src/log.rs;src/main.rs\n"
+        ));
+        assert!(csv.contains("web,frontend,backlog,,,,,0 (0 blocked),,\n"));
+    }
+}
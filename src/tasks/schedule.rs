@@ -0,0 +1,156 @@
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Datelike, Duration, TimeZone, Timelike};
+
+/// A five-field cron schedule: minute, hour, day of month, month, day of week.
+/// Fields take `*`, numbers, ranges (`1-5`), steps (`*/15`, `0-30/10`) and
+/// comma lists. Day of week runs 0-7 with both 0 and 7 meaning Sunday.
+/// `@hourly`, `@daily`, `@weekly` and `@monthly` are accepted too.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schedule {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    /// Whether day of month / day of week were `*`; when both are
+    /// restricted, cron matches a day that satisfies either
+    any_day: bool,
+    any_weekday: bool,
+}
+
+/// Longest gap `due_between` looks through, so a long sleep costs a bounded scan
+const MAX_SCAN_MINUTES: i64 = 7 * 24 * 60;
+
+impl Schedule {
+    pub fn parse(expr: &str) -> Result<Self> {
+        let expr = expr.trim();
+        let expanded = match expr {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            other => other,
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            bail!(
+                "Schedule '{}' needs 5 fields (minute hour day month weekday)",
+                expr
+            );
+        };
+        let parse = || -> Result<Self> {
+            let weekdays = parse_field(weekday, 0, 7).context("day of week")?;
+            Ok(Self {
+                minutes: parse_field(minute, 0, 59).context("minute")?,
+                hours: parse_field(hour, 0, 23).context("hour")?,
+                days: parse_field(day, 1, 31).context("day of month")?,
+                months: parse_field(month, 1, 12).context("month")?,
+                // Fold 7 onto 0 so both mean Sunday
+                weekdays: (weekdays | (weekdays >> 7)) & 0x7f,
+                any_day: day == "*",
+                any_weekday: weekday == "*",
+            })
+        };
+        parse().with_context(|| format!("Invalid schedule '{}'", expr))
+    }
+
+    /// Whether the schedule fires in the minute containing `at`
+    pub fn matches<Tz: TimeZone>(&self, at: &DateTime<Tz>) -> bool {
+        let bit = |mask: u64, value: u32| mask & (1 << value) != 0;
+        let day = bit(self.days, at.day());
+        let weekday = bit(self.weekdays, at.weekday().num_days_from_sunday());
+        let day_matches = match (self.any_day, self.any_weekday) {
+            (false, false) => day || weekday,
+            _ => day && weekday,
+        };
+        bit(self.minutes, at.minute())
+            && bit(self.hours, at.hour())
+            && bit(self.months, at.month())
+            && day_matches
+    }
+
+    /// Whether the schedule fired in any minute after `after`, up to and
+    /// including the minute containing `until`
+    pub fn due_between<Tz: TimeZone>(&self, after: &DateTime<Tz>, until: &DateTime<Tz>) -> bool {
+        let minutes = (until.clone() - after.clone())
+            .num_minutes()
+            .min(MAX_SCAN_MINUTES);
+        (0..minutes).any(|back| self.matches(&(until.clone() - Duration::minutes(back))))
+    }
+}
+
+/// A bitmask of the values in one cron field
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64> {
+    let mut mask = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().ok().filter(|s| *s > 0)),
+            None => (part, Some(1)),
+        };
+        let Some(step) = step else {
+            bail!("bad step in '{}'", part);
+        };
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (parse_value(start, min, max)?, parse_value(end, min, max)?)
+        } else {
+            let value = parse_value(range, min, max)?;
+            // `5/15` means every 15 from 5
+            (value, if part.contains('/') { max } else { value })
+        };
+        if start > end {
+            bail!("range '{}' runs backwards", range);
+        }
+        for value in (start..=end).step_by(step as usize) {
+            mask |= 1 << value;
+        }
+    }
+    Ok(mask)
+}
+
+fn parse_value(value: &str, min: u32, max: u32) -> Result<u32> {
+    match value.parse::<u32>() {
+        Ok(n) if (min..=max).contains(&n) => Ok(n),
+        _ => bail!("'{}' is not a number from {} to {}", value, min, max),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn at(text: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(text)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_schedule_matches_cron_fields() {
+        // 2024-05-06 is a Monday
+        let weekdays = Schedule::parse("30 9 * * 1-5").unwrap();
+        assert!(weekdays.matches(&at("2024-05-06T09:30:59Z")));
+        assert!(!weekdays.matches(&at("2024-05-05T09:30:00Z")));
+        assert!(!weekdays.matches(&at("2024-05-06T09:31:00Z")));
+
+        let quarter_hours = Schedule::parse("*/15 * * * *").unwrap();
+        assert!(quarter_hours.matches(&at("2024-05-06T13:45:00Z")));
+        assert!(!quarter_hours.matches(&at("2024-05-06T13:50:00Z")));
+
+        // Day of month or day of week when both are restricted; 7 is Sunday
+        let first_or_sunday = Schedule::parse("0 0 1 * 7").unwrap();
+        assert!(first_or_sunday.matches(&at("2024-05-01T00:00:00Z")));
+        assert!(first_or_sunday.matches(&at("2024-05-05T00:00:00Z")));
+        assert!(!first_or_sunday.matches(&at("2024-05-06T00:00:00Z")));
+
+        let daily = Schedule::parse("@daily").unwrap();
+        assert!(daily.due_between(&at("2024-05-05T23:59:00Z"), &at("2024-05-06T00:00:00Z")));
+        assert!(!daily.due_between(&at("2024-05-06T00:00:00Z"), &at("2024-05-06T00:05:00Z")));
+
+        assert!(Schedule::parse("0 9 * *").is_err());
+        assert!(Schedule::parse("61 * * * *").is_err());
+        assert!(Schedule::parse("*/0 * * * *").is_err());
+    }
+}
@@ -0,0 +1,392 @@
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Sender;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+
+use crate::config::{self, HiveConfig, TaskSource};
+use crate::workspace::config::WorkspaceConfig;
+
+use super::archive::{archive_done, load_archive};
+use super::dispatch::{dispatch_global_backlog, Dispatched};
+use super::github::GithubTasks;
+use super::import::{import_todos, CodeTodo};
+use super::lint::{lint_tasks, Diagnostic};
+use super::lock::{LockBusy, LOCK_TIMEOUT};
+use super::markdown::MarkdownTasks;
+use super::verify::Verification;
+use super::watcher::{spawn_yaml_watcher, NudgeRequest};
+use super::yaml::{
+    add_task, add_task_unless_open, answer_question, ask_question, claim_task, complete_task,
+    counts_for_lane, delete_task, load_tasks_locked_within, move_task, record_verification,
+    reprioritize_task, requeue_task, update_tasks, update_tasks_within, Completion, Task,
+    TaskCounts, TaskLocation, TaskState, TasksFile,
+};
+
+/// Where a session's tasks come from. Every source is read into the
+/// `TasksFile` shape so counts, nudges and the UI don't care which one is used.
+pub trait TaskBackend: Send {
+    /// Short description for logs, e.g. the file path or project number
+    fn describe(&self) -> String;
+
+    /// Read every lane's tasks
+    fn load(&self) -> Result<TasksFile>;
+
+    fn counts(&self, lane: &str) -> Result<TaskCounts> {
+        Ok(counts_for_lane(&self.load()?, lane))
+    }
+
+    /// Move a backlog task to in_progress for `worker`
+    fn claim(&self, id: &str, lane: Option<&str>, worker: &str) -> Result<TaskLocation>;
+
+    /// Move an in-progress task to done
+    fn complete(
+        &self,
+        id: &str,
+        lane: Option<&str>,
+        completion: Completion,
+    ) -> Result<TaskLocation>;
+
+    /// Move an in-progress task back to backlog, noting `reason`
+    fn requeue(&self, id: &str, lane: Option<&str>, reason: &str) -> Result<TaskLocation>;
+
+    /// Send `NudgeRequest::Events` on `nudge_tx` whenever the tasks change
+    fn watch(&self, nudge_tx: Sender<NudgeRequest>, log_path: PathBuf) -> Result<()>;
+
+    /// Store the result of a task's `verify` commands, moving it back to
+    /// in_progress when they failed
+    fn record_verification(
+        &self,
+        id: &str,
+        _lane: Option<&str>,
+        _verification: Verification,
+    ) -> Result<TaskLocation> {
+        bail!("{} can't record verification of '{}'", self.describe(), id)
+    }
+
+    /// Add `task` to a lane's backlog unless a task from the same `recurring`
+    /// entry is still open there, returning whether it was added
+    fn add_unless_open(&self, lane: &str, task: Task) -> Result<bool> {
+        bail!("{} can't add '{}' to {}", self.describe(), task.id, lane)
+    }
+
+    /// Add `task` to the end of a lane's backlog
+    fn add(&self, lane: &str, task: Task) -> Result<()> {
+        bail!("{} can't add '{}' to {}", self.describe(), task.id, lane)
+    }
+
+    /// Move a task to another state and/or lane without the claim/complete checks
+    fn move_to(
+        &self,
+        id: &str,
+        _lane: Option<&str>,
+        _state: TaskState,
+        _to_lane: Option<&str>,
+    ) -> Result<TaskLocation> {
+        bail!("{} can't move '{}'", self.describe(), id)
+    }
+
+    /// Record a worker's question on an in-progress task for the architect
+    fn ask(&self, id: &str, _lane: Option<&str>, _question: &str) -> Result<TaskLocation> {
+        bail!("{} can't hold questions on '{}'", self.describe(), id)
+    }
+
+    /// Record the architect's answer to the question on a task
+    fn answer(&self, id: &str, _lane: Option<&str>, _answer: &str) -> Result<TaskLocation> {
+        bail!("{} can't hold answers on '{}'", self.describe(), id)
+    }
+
+    /// Add backlog tasks for code TODOs not already in the source or its
+    /// archive, returning how many were added and skipped
+    fn import_todos(&self, lane: &str, _todos: &[CodeTodo]) -> Result<(usize, usize)> {
+        bail!("{} can't import into {}", self.describe(), lane)
+    }
+
+    /// Move a backlog task one place up or down in its lane's priority order
+    fn reprioritize(&self, id: &str, _lane: Option<&str>, _up: bool) -> Result<TaskLocation> {
+        bail!("{} can't reprioritize '{}'", self.describe(), id)
+    }
+
+    /// Remove a task from the source
+    fn delete(&self, id: &str, _lane: Option<&str>) -> Result<()> {
+        bail!("{} can't delete '{}'", self.describe(), id)
+    }
+
+    /// Move done tasks (completed at least `older_than` ago, or all of them)
+    /// out of the source, returning how many were moved
+    fn archive(&self, _older_than: Option<chrono::Duration>) -> Result<usize> {
+        bail!("{} doesn't support archiving", self.describe())
+    }
+
+    /// Move `global_backlog` tasks into lanes. `lanes` are the configured
+    /// lanes with their current counts, in config order. Sources without a
+    /// global backlog have nothing to route.
+    fn dispatch(&self, _lanes: &mut [(String, TaskCounts)]) -> Result<Vec<Dispatched>> {
+        Ok(Vec::new())
+    }
+
+    /// Problems in the source that `load` can't explain well. `known_lanes`
+    /// are the configured lanes (empty to skip that check).
+    fn lint(&self, _known_lanes: &[String]) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+
+    /// Give up on a lock held by another process after `wait` when loading
+    /// or dispatching, and load the last snapshot instead. The server sets
+    /// this so its event loop never waits out the full lock timeout.
+    fn set_lock_wait(&mut self, _wait: Duration) {}
+}
+
+/// Tasks kept in a tasks.yaml file
+pub struct YamlTasks {
+    path: PathBuf,
+    debounce: Duration,
+    settle: Duration,
+    lock_wait: Duration,
+    /// Last successful load, served while the lock is busy
+    last: Mutex<Option<TasksFile>>,
+}
+
+impl YamlTasks {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            debounce: Duration::from_secs(10),
+            settle: Duration::from_secs(5),
+            lock_wait: LOCK_TIMEOUT,
+            last: Mutex::new(None),
+        }
+    }
+
+    /// Override the watcher's debounce and settle delays
+    pub fn with_watch_timing(mut self, debounce: Duration, settle: Duration) -> Self {
+        self.debounce = debounce;
+        self.settle = settle;
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl TaskBackend for YamlTasks {
+    fn describe(&self) -> String {
+        self.path.display().to_string()
+    }
+
+    fn load(&self) -> Result<TasksFile> {
+        let mut tasks = match load_tasks_locked_within(&self.path, self.lock_wait) {
+            Ok(tasks) => tasks,
+            Err(e) if e.is::<LockBusy>() => {
+                let last = self.last.lock().ok().and_then(|last| last.clone());
+                return last.ok_or(e);
+            }
+            Err(e) => return Err(e),
+        };
+        tasks.archived = load_archive(&self.path);
+        if let Ok(mut last) = self.last.lock() {
+            *last = Some(tasks.clone());
+        }
+        Ok(tasks)
+    }
+
+    fn claim(&self, id: &str, lane: Option<&str>, worker: &str) -> Result<TaskLocation> {
+        // Archived dependencies count as done
+        let archived = load_archive(&self.path);
+        update_tasks(&self.path, |tasks| {
+            tasks.archived = archived;
+            claim_task(tasks, id, lane, worker)
+        })
+    }
+
+    fn complete(
+        &self,
+        id: &str,
+        lane: Option<&str>,
+        completion: Completion,
+    ) -> Result<TaskLocation> {
+        update_tasks(&self.path, |tasks| {
+            complete_task(tasks, id, lane, completion)
+        })
+    }
+
+    fn requeue(&self, id: &str, lane: Option<&str>, reason: &str) -> Result<TaskLocation> {
+        update_tasks(&self.path, |tasks| requeue_task(tasks, id, lane, reason))
+    }
+
+    fn record_verification(
+        &self,
+        id: &str,
+        lane: Option<&str>,
+        verification: Verification,
+    ) -> Result<TaskLocation> {
+        update_tasks(&self.path, |tasks| {
+            record_verification(tasks, id, lane, verification)
+        })
+    }
+
+    fn add_unless_open(&self, lane: &str, task: Task) -> Result<bool> {
+        update_tasks(&self.path, |tasks| add_task_unless_open(tasks, lane, task))
+    }
+
+    fn add(&self, lane: &str, task: Task) -> Result<()> {
+        update_tasks(&self.path, |tasks| add_task(tasks, lane, task))
+    }
+
+    fn move_to(
+        &self,
+        id: &str,
+        lane: Option<&str>,
+        state: TaskState,
+        to_lane: Option<&str>,
+    ) -> Result<TaskLocation> {
+        update_tasks(&self.path, |tasks| {
+            move_task(tasks, id, lane, state, to_lane)
+        })
+    }
+
+    fn ask(&self, id: &str, lane: Option<&str>, question: &str) -> Result<TaskLocation> {
+        update_tasks(&self.path, |tasks| ask_question(tasks, id, lane, question))
+    }
+
+    fn answer(&self, id: &str, lane: Option<&str>, answer: &str) -> Result<TaskLocation> {
+        update_tasks(&self.path, |tasks| answer_question(tasks, id, lane, answer))
+    }
+
+    fn import_todos(&self, lane: &str, todos: &[CodeTodo]) -> Result<(usize, usize)> {
+        let archived = load_archive(&self.path).ids;
+        update_tasks(&self.path, |tasks| {
+            import_todos(tasks, lane, todos, &archived)
+        })
+    }
+
+    fn reprioritize(&self, id: &str, lane: Option<&str>, up: bool) -> Result<TaskLocation> {
+        update_tasks(&self.path, |tasks| reprioritize_task(tasks, id, lane, up))
+    }
+
+    fn delete(&self, id: &str, lane: Option<&str>) -> Result<()> {
+        update_tasks(&self.path, |tasks| delete_task(tasks, id, lane)).map(|_| ())
+    }
+
+    fn archive(&self, older_than: Option<chrono::Duration>) -> Result<usize> {
+        archive_done(&self.path, older_than, chrono::Utc::now())
+    }
+
+    fn dispatch(&self, lanes: &mut [(String, TaskCounts)]) -> Result<Vec<Dispatched>> {
+        // Check first so an empty global backlog doesn't rewrite the file
+        let current = load_tasks_locked_within(&self.path, self.lock_wait)?;
+        if current.global_backlog.as_ref().is_none_or(Vec::is_empty) {
+            return Ok(Vec::new());
+        }
+        update_tasks_within(&self.path, self.lock_wait, |tasks| {
+            dispatch_global_backlog(tasks, lanes)
+        })
+    }
+
+    fn lint(&self, known_lanes: &[String]) -> Vec<Diagnostic> {
+        match std::fs::read_to_string(&self.path) {
+            Ok(content) => lint_tasks(&content, known_lanes, &load_archive(&self.path).ids),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    fn set_lock_wait(&mut self, wait: Duration) {
+        self.lock_wait = wait;
+    }
+
+    fn watch(&self, nudge_tx: Sender<NudgeRequest>, log_path: PathBuf) -> Result<()> {
+        spawn_yaml_watcher(
+            self.path.clone(),
+            nudge_tx,
+            self.debounce,
+            self.settle,
+            log_path,
+        )
+    }
+}
+
+/// Open the task source configured in a legacy .hive.yaml
+pub fn open_backend(config_path: &Path, config: &HiveConfig) -> Result<Box<dyn TaskBackend>> {
+    match config.tasks.source {
+        TaskSource::Yaml => Ok(Box::new(YamlTasks::new(config::tasks_file_path(
+            config_path,
+            config,
+        )))),
+        TaskSource::Github => Ok(Box::new(GithubTasks::from_config(&config.tasks)?)),
+        TaskSource::Markdown => Ok(Box::new(MarkdownTasks::new(config::tasks_file_path(
+            config_path,
+            config,
+        )))),
+    }
+}
+
+/// Open the task source configured in a workspace's workspace.yaml
+pub fn open_workspace_backend(
+    workspace_dir: &Path,
+    config: &WorkspaceConfig,
+) -> Result<Box<dyn TaskBackend>> {
+    let file = config.tasks_file(workspace_dir);
+    let yaml = |file| {
+        YamlTasks::new(file).with_watch_timing(
+            Duration::from_secs(config.watch.debounce_secs),
+            Duration::from_secs(config.watch.settle_secs),
+        )
+    };
+    let Some(tasks) = &config.tasks else {
+        return Ok(Box::new(yaml(file)));
+    };
+    match tasks.source {
+        TaskSource::Yaml => Ok(Box::new(yaml(file))),
+        TaskSource::Github => Ok(Box::new(GithubTasks::from_config(tasks)?)),
+        TaskSource::Markdown => Ok(Box::new(MarkdownTasks::new(file))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tasks::lock;
+
+    #[test]
+    fn test_load_serves_last_snapshot_while_locked() {
+        let dir = std::env::temp_dir().join(format!("hive-source-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("tasks.yaml");
+        std::fs::write(&path, "api:\n  backlog:\n    - id: first\n").unwrap();
+
+        let mut source = YamlTasks::new(path.clone());
+        source.set_lock_wait(Duration::from_millis(50));
+        assert_eq!(source.counts("api").unwrap().backlog, 1);
+
+        let _held = lock::lock_exclusive(&path).unwrap();
+        std::fs::write(&path, "api:\n  backlog: []\n").unwrap();
+        assert_eq!(source.counts("api").unwrap().backlog, 1);
+
+        let mut fresh = YamlTasks::new(path.clone());
+        fresh.set_lock_wait(Duration::from_millis(50));
+        assert!(fresh.load().unwrap_err().is::<LockBusy>());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_claim_counts_archived_dependency_as_done() {
+        let dir = std::env::temp_dir().join(format!("hive-source-deps-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("tasks.yaml");
+        std::fs::write(
+            &path,
+            "api:\n  backlog:\n    - id: deploy\n      depends_on: [migrate]\n  in_progress: []\n  done:\n    - id: migrate\n      completed_at: 2024-05-01T12:00:00Z\n",
+        )
+        .unwrap();
+
+        let source = YamlTasks::new(path.clone());
+        assert_eq!(source.archive(None).unwrap(), 1);
+        let location = source.claim("deploy", None, "worker-1").unwrap();
+        assert_eq!(location.state, TaskState::InProgress);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
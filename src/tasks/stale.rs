@@ -0,0 +1,174 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::yaml::{parse_timestamp, Task, TasksFile};
+
+/// When claims count as stale and when they're requeued
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StalePolicy {
+    /// Flag a claim once its worker has been quiet this long
+    pub after: Duration,
+    /// Requeue a stale claim once its worker has been inactive this long
+    pub requeue_after: Option<Duration>,
+}
+
+impl Default for StalePolicy {
+    fn default() -> Self {
+        Self {
+            after: Duration::from_secs(30 * 60),
+            requeue_after: None,
+        }
+    }
+}
+
+/// What the server knows about the pane holding a claim
+#[derive(Debug, Clone, Copy)]
+pub struct PaneActivity {
+    /// How long ago the pane's process exited, if it has
+    pub exited_for: Option<Duration>,
+    /// How long ago the pane last printed anything
+    pub quiet_for: Duration,
+}
+
+/// An in-progress task whose worker looks gone or hung
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StaleClaim {
+    pub lane: String,
+    pub id: String,
+    /// Pane id of the worker holding the claim
+    pub worker: String,
+    /// Why the claim looks abandoned, e.g. "pane exited" or "no output for 45m"
+    pub reason: String,
+    /// Whether the requeue timeout has passed
+    pub requeue: bool,
+}
+
+/// In-progress tasks whose worker pane has exited or gone quiet. `pane_for`
+/// finds the pane holding a task, given its lane and `claimed_by`; tasks with
+/// no pane (e.g. claimed by hand) are never stale.
+pub fn find_stale_claims(
+    tasks: &TasksFile,
+    policy: StalePolicy,
+    now: DateTime<Utc>,
+    pane_for: impl Fn(&str, &Task) -> Option<(String, PaneActivity)>,
+) -> Vec<StaleClaim> {
+    let mut stale = Vec::new();
+    for (lane, lane_tasks) in tasks.lanes() {
+        for task in &lane_tasks.in_progress {
+            let Some((worker, activity)) = pane_for(&lane, task) else {
+                continue;
+            };
+            // A fresh claim isn't stale just because the pane was quiet before it
+            let claimed_for = task
+                .claimed_at
+                .as_deref()
+                .and_then(parse_timestamp)
+                .and_then(|at| (now - at).to_std().ok())
+                .unwrap_or(Duration::MAX);
+
+            let (inactive_for, reason) = match activity.exited_for {
+                Some(exited_for) => (exited_for.min(claimed_for), "pane exited".to_string()),
+                None => {
+                    let quiet_for = activity.quiet_for.min(claimed_for);
+                    if quiet_for < policy.after {
+                        continue;
+                    }
+                    (
+                        quiet_for,
+                        format!("no output for {}", format_age(quiet_for)),
+                    )
+                }
+            };
+            stale.push(StaleClaim {
+                lane: lane.clone(),
+                id: task.id.clone(),
+                worker,
+                reason,
+                requeue: policy
+                    .requeue_after
+                    .is_some_and(|after| inactive_for >= after),
+            });
+        }
+    }
+    stale
+}
+
+/// Whole minutes, hours or days, e.g. `45m`, `3h`, `2d`
+pub fn format_age(age: Duration) -> String {
+    let minutes = age.as_secs() / 60;
+    match minutes {
+        0..=119 => format!("{}m", minutes),
+        120..=2879 => format!("{}h", minutes / 60),
+        _ => format!("{}d", minutes / (60 * 24)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_stale_claims_by_exit_and_quiet_time() {
+        let tasks: TasksFile = serde_yaml::from_str(
+            r#"
+api:
+  in_progress:
+    - id: exited
+      claimed_by: api-1
+      claimed_at: "2024-05-01T10:00:00Z"
+    - id: quiet
+      claimed_by: api-2
+      claimed_at: "2024-05-01T10:00:00Z"
+    - id: just-claimed
+      claimed_by: api-2
+      claimed_at: "2024-05-01T11:55:00Z"
+    - id: busy
+      claimed_by: api-3
+      claimed_at: "2024-05-01T10:00:00Z"
+    - id: by-hand
+      claimed_by: someone
+"#,
+        )
+        .unwrap();
+        let now = parse_timestamp("2024-05-01T12:00:00Z").unwrap();
+        let minutes = |m: u64| Duration::from_secs(m * 60);
+        let policy = StalePolicy {
+            after: minutes(30),
+            requeue_after: Some(minutes(60)),
+        };
+
+        let stale = find_stale_claims(&tasks, policy, now, |_, task| {
+            let activity = match task.claimed_by.as_deref()? {
+                "api-1" => PaneActivity {
+                    exited_for: Some(minutes(5)),
+                    quiet_for: minutes(5),
+                },
+                "api-2" => PaneActivity {
+                    exited_for: None,
+                    quiet_for: minutes(90),
+                },
+                "api-3" => PaneActivity {
+                    exited_for: None,
+                    quiet_for: minutes(1),
+                },
+                _ => return None,
+            };
+            Some((task.claimed_by.clone()?, activity))
+        });
+
+        let summary: Vec<(&str, &str, bool)> = stale
+            .iter()
+            .map(|claim| (claim.id.as_str(), claim.reason.as_str(), claim.requeue))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("exited", "pane exited", false),
+                ("quiet", "no output for 90m", true),
+            ]
+        );
+        assert_eq!(format_age(Duration::from_secs(3 * 24 * 3600)), "3d");
+    }
+}
@@ -0,0 +1,158 @@
+use anyhow::{bail, Context, Result};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use super::yaml::{Priority, Task};
+
+/// A reusable task shape from the workspace's `task_templates:`. Any field
+/// can use `{name}` placeholders, filled from `--var name=value`, a recurring
+/// task's `vars`, or the built-in `{id}` and `{date}` (today, local time).
+/// `${name}` is left alone so shell variables in `verify` commands survive.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TaskTemplate {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub acceptance: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub verify: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<String>,
+}
+
+impl TaskTemplate {
+    /// Build a backlog task with id `id`, filling placeholders from `vars`
+    pub fn expand(&self, id: &str, vars: &IndexMap<String, String>) -> Result<Task> {
+        let mut vars = vars.clone();
+        vars.entry("id".to_string())
+            .or_insert_with(|| id.to_string());
+        vars.entry("date".to_string())
+            .or_insert_with(|| chrono::Local::now().format("%Y-%m-%d").to_string());
+
+        let one = |value: &Option<String>| value.as_deref().map(|v| fill(v, &vars)).transpose();
+        let list = |values: &[String]| -> Result<Option<Vec<String>>> {
+            let filled = values
+                .iter()
+                .map(|v| fill(v, &vars))
+                .collect::<Result<Vec<_>>>()?;
+            Ok((!filled.is_empty()).then_some(filled))
+        };
+        let priority = one(&self.priority)?
+            .map(|value| {
+                Priority::parse(&value)
+                    .with_context(|| format!("Template priority '{}' is not a priority", value))
+            })
+            .transpose()?;
+
+        Ok(Task {
+            id: id.to_string(),
+            title: one(&self.title)?,
+            description: one(&self.description)?,
+            priority,
+            acceptance: list(&self.acceptance)?,
+            verify: list(&self.verify)?,
+            depends_on: list(&self.depends_on)?,
+            labels: list(&self.labels)?,
+            ..Default::default()
+        })
+    }
+}
+
+/// Parse `--var` arguments of the form `name=value`
+pub fn parse_vars(args: &[String]) -> Result<IndexMap<String, String>> {
+    args.iter()
+        .map(|arg| match arg.split_once('=') {
+            Some((name, value)) if !name.trim().is_empty() => {
+                Ok((name.trim().to_string(), value.to_string()))
+            }
+            _ => bail!("--var '{}' should look like name=value", arg),
+        })
+        .collect()
+}
+
+/// Replace `{name}` placeholders in `text`, failing on any without a value
+fn fill(text: &str, vars: &IndexMap<String, String>) -> Result<String> {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('{') {
+        let (before, from_brace) = rest.split_at(start);
+        out.push_str(before);
+        let name = from_brace[1..]
+            .find('}')
+            .map(|end| &from_brace[1..1 + end])
+            .filter(|name| is_placeholder(name) && !before.ends_with('$'));
+        match name {
+            Some(name) => {
+                let Some(value) = vars.get(name) else {
+                    bail!(
+                        "Template needs a value for {{{}}}; pass --var {}=...",
+                        name,
+                        name
+                    );
+                };
+                out.push_str(value);
+                rest = &from_brace[name.len() + 2..];
+            }
+            None => {
+                out.push('{');
+                rest = &from_brace[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn is_placeholder(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_template_fills_vars() {
+        let template: TaskTemplate = serde_yaml::from_str(
+            r#"
+title: "Bump {crate} to {version}"
+description: "Tracked in {id}; leave {braces alone} and ${HOME}"
+priority: "{priority}"
+verify:
+  - "cargo update -p {crate}"
+labels: [deps]
+"#,
+        )
+        .unwrap();
+        let vars = parse_vars(&[
+            "crate=serde".to_string(),
+            "version=1.0.200".to_string(),
+            "priority=high".to_string(),
+        ])
+        .unwrap();
+
+        let task = template.expand("bump-serde", &vars).unwrap();
+        assert_eq!(task.title.as_deref(), Some("Bump serde to 1.0.200"));
+        assert_eq!(
+            task.description.as_deref(),
+            Some("Tracked in bump-serde; leave {braces alone} and ${HOME}")
+        );
+        assert_eq!(task.priority, Some(Priority::High));
+        assert_eq!(task.verify, Some(vec!["cargo update -p serde".to_string()]));
+        assert_eq!(task.labels, Some(vec!["deps".to_string()]));
+        assert_eq!(task.acceptance, None);
+
+        let missing = template.expand("bump", &IndexMap::new()).unwrap_err();
+        assert!(missing.to_string().contains("needs a value"));
+        assert!(parse_vars(&["novalue".to_string()]).is_err());
+    }
+}
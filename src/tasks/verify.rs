@@ -0,0 +1,87 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::utils::shell::run_shell_command_output;
+
+use super::yaml::timestamp_now;
+
+/// Lines of a failing command's output kept in the task
+const OUTPUT_LINES: usize = 40;
+
+/// Result of running a task's `verify` commands, stored in its `verification` field
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Verification {
+    pub passed: bool,
+    /// When the commands finished
+    pub at: String,
+    /// The command that failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    /// The last lines of the failing command's stdout and stderr
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+}
+
+/// Run `commands` in order in `cwd`, stopping at the first one that fails
+pub fn run_verification(commands: &[String], cwd: &Path) -> Verification {
+    for command in commands {
+        let failure = match run_shell_command_output(command, cwd) {
+            Ok(output) if output.status.success() => continue,
+            Ok(output) => {
+                let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+                text.push_str(&String::from_utf8_lossy(&output.stderr));
+                if text.trim().is_empty() {
+                    text = format!("exited with {}", output.status);
+                }
+                text
+            }
+            Err(e) => format!("{:#}", e),
+        };
+        return Verification {
+            passed: false,
+            at: timestamp_now(),
+            command: Some(command.clone()),
+            output: Some(tail(&failure, OUTPUT_LINES)),
+        };
+    }
+    Verification {
+        passed: true,
+        at: timestamp_now(),
+        command: None,
+        output: None,
+    }
+}
+
+fn tail(text: &str, lines: usize) -> String {
+    let all: Vec<&str> = text.trim_end().lines().collect();
+    all[all.len().saturating_sub(lines)..].join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_verification_stops_at_first_failure() {
+        let dir = std::env::temp_dir();
+        let commands = |list: &[&str]| list.iter().map(|c| c.to_string()).collect::<Vec<_>>();
+
+        let passed = run_verification(&commands(&["true", "test -d ."]), &dir);
+        assert!(passed.passed);
+        assert_eq!(passed.command, None);
+
+        let failed = run_verification(
+            &commands(&["true", "seq 1 100; echo broken >&2; exit 3", "true"]),
+            &dir,
+        );
+        assert!(!failed.passed);
+        assert_eq!(
+            failed.command.as_deref(),
+            Some("seq 1 100; echo broken >&2; exit 3")
+        );
+        let output = failed.output.unwrap();
+        assert_eq!(output.lines().count(), OUTPUT_LINES);
+        assert!(output.ends_with("100\nbroken"));
+    }
+}
@@ -7,11 +7,15 @@ use std::time::{Duration, Instant};
 use anyhow::Result;
 use notify::{RecursiveMode, Watcher};
 
-use super::yaml::load_tasks;
+use super::events::{diff_tasks, TaskEvent};
+use super::yaml::{load_tasks_locked, TasksFile};
 
 #[derive(Debug, Clone)]
 pub enum NudgeRequest {
-    All,
+    /// Tasks changed; nudge the lanes these events affect
+    Events(Vec<TaskEvent>),
+    /// Reload tasks and problems without nudging (e.g. the file became invalid)
+    Refresh,
 }
 
 fn log_line(path: &Path, line: &str) {
@@ -30,6 +34,26 @@ pub fn spawn_yaml_watcher(
     debounce: Duration,
     settle: Duration,
     log_path: PathBuf,
+) -> Result<()> {
+    spawn_tasks_watcher(
+        tasks_file,
+        load_tasks_locked,
+        nudge_tx,
+        debounce,
+        settle,
+        log_path,
+    )
+}
+
+/// Watch a tasks file of any format. Once `load` accepts the new contents they
+/// are diffed against the last good load and the changes sent as events.
+pub fn spawn_tasks_watcher(
+    tasks_file: PathBuf,
+    load: fn(&Path) -> Result<TasksFile>,
+    nudge_tx: Sender<NudgeRequest>,
+    debounce: Duration,
+    settle: Duration,
+    log_path: PathBuf,
 ) -> Result<()> {
     thread::spawn(move || {
         let (tx, rx) = mpsc::channel();
@@ -41,10 +65,18 @@ pub fn spawn_yaml_watcher(
             }
         };
 
-        if let Err(e) = watcher.watch(&tasks_file, RecursiveMode::NonRecursive) {
+        // Writers replace the file with a rename, which would orphan a watch on
+        // the file itself, so watch the directory and filter by name instead.
+        let watch_dir = match tasks_file.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        let file_name = tasks_file.file_name().map(|n| n.to_os_string());
+
+        if let Err(e) = watcher.watch(&watch_dir, RecursiveMode::NonRecursive) {
             log_line(
                 &log_path,
-                &format!("watcher: failed to watch {}: {}", tasks_file.display(), e),
+                &format!("watcher: failed to watch {}: {}", watch_dir.display(), e),
             );
             return;
         }
@@ -54,41 +86,73 @@ pub fn spawn_yaml_watcher(
             &format!("watcher: watching {}", tasks_file.display()),
         );
 
+        let mut last = load(&tasks_file).ok();
         let mut last_nudge = Instant::now()
             .checked_sub(debounce * 2)
             .unwrap_or_else(Instant::now);
+        // A change arrived during the debounce window and still needs handling
+        let mut pending = false;
 
         loop {
             match rx.recv_timeout(Duration::from_secs(1)) {
                 Ok(event) => {
+                    let touches_tasks_file = match &event {
+                        Ok(event) => event
+                            .paths
+                            .iter()
+                            .any(|p| p.file_name() == file_name.as_deref()),
+                        Err(_) => true,
+                    };
+                    if !touches_tasks_file {
+                        continue;
+                    }
+
                     log_line(&log_path, &format!("watcher: file event {:?}", event));
+                    pending = true;
+                }
+                Err(mpsc::RecvTimeoutError::Timeout) => {}
+                Err(mpsc::RecvTimeoutError::Disconnected) => {
+                    log_line(&log_path, "watcher: channel disconnected");
+                    break;
+                }
+            }
 
-                    if last_nudge.elapsed() >= debounce {
-                        thread::sleep(settle);
+            if !pending {
+                continue;
+            }
+            if last_nudge.elapsed() < debounce {
+                continue;
+            }
+            thread::sleep(settle);
+            pending = false;
 
-                        // Validate YAML before triggering nudge
-                        match load_tasks(&tasks_file) {
-                            Ok(_) => {
-                                log_line(&log_path, "watcher: yaml valid, sending nudge");
-                                last_nudge = Instant::now();
-                                if nudge_tx.send(NudgeRequest::All).is_err() {
-                                    log_line(&log_path, "watcher: nudge channel closed");
-                                    break;
-                                }
-                            }
-                            Err(e) => {
-                                log_line(&log_path, &format!("watcher: yaml invalid: {}", e));
-                            }
-                        }
+            // Validate the file before triggering nudge
+            let request = match load(&tasks_file) {
+                Ok(tasks) => {
+                    let events = match &last {
+                        Some(previous) => diff_tasks(previous, &tasks),
+                        None => Vec::new(),
+                    };
+                    last = Some(tasks);
+                    log_line(
+                        &log_path,
+                        &format!("watcher: tasks valid, events={:?}", events),
+                    );
+                    last_nudge = Instant::now();
+                    if events.is_empty() {
+                        NudgeRequest::Refresh
                     } else {
-                        log_line(&log_path, "watcher: debounce, skipping");
+                        NudgeRequest::Events(events)
                     }
                 }
-                Err(mpsc::RecvTimeoutError::Timeout) => continue,
-                Err(mpsc::RecvTimeoutError::Disconnected) => {
-                    log_line(&log_path, "watcher: channel disconnected");
-                    break;
+                Err(e) => {
+                    log_line(&log_path, &format!("watcher: tasks invalid: {}", e));
+                    NudgeRequest::Refresh
                 }
+            };
+            if nudge_tx.send(request).is_err() {
+                log_line(&log_path, "watcher: nudge channel closed");
+                break;
             }
         }
     });
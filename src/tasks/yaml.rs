@@ -1,22 +1,33 @@
-use std::collections::HashMap;
+use std::collections::HashSet;
 use std::path::Path;
+use std::time::Duration;
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize, Serialize, Default)]
+use super::archive::ArchivedTasks;
+use super::verify::Verification;
+
+#[derive(Debug, Deserialize, Serialize, Default, Clone)]
 pub struct TasksFile {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub worker_protocol: Option<WorkerProtocol>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub rules: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub global_backlog: Option<Vec<Task>>,
     #[serde(flatten)]
-    pub projects: HashMap<String, ProjectEntry>,
+    pub projects: IndexMap<String, ProjectEntry>,
+    /// Done tasks moved to the archive, filled in by the task source
+    #[serde(skip)]
+    pub archived: ArchivedTasks,
 }
 
 /// A project entry can be either:
 /// - Direct lane tasks (for single-lane projects like android-sdk)
 /// - Nested lanes (for multi-lane projects like backend with fixes/features/misc)
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(untagged)]
 pub enum ProjectEntry {
     /// Direct lane tasks: android-sdk: { backlog: [], in_progress: [] }
@@ -24,16 +35,16 @@ pub enum ProjectEntry {
     Direct(LaneTasks),
     /// Nested lanes: backend: { fixes: { backlog: [] }, features: { backlog: [] } }
     /// Keys are lane names like "fixes", "features", etc.
-    Nested(HashMap<String, LaneTasks>),
+    Nested(IndexMap<String, LaneTasks>),
 }
 
-#[derive(Debug, Deserialize, Serialize, Default)]
+#[derive(Debug, Deserialize, Serialize, Default, Clone)]
 pub struct WorkerProtocol {
     pub claim: Option<String>,
     pub complete: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Default)]
+#[derive(Debug, Deserialize, Serialize, Default, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct LaneTasks {
     #[serde(default)]
@@ -44,25 +55,73 @@ pub struct LaneTasks {
     pub done: Vec<Task>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
 pub struct Task {
     pub id: String,
     /// Task title (short summary)
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub title: Option<String>,
     /// Task description (detailed explanation)
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
-    pub priority: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<Priority>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub acceptance: Option<Vec<String>>,
+    /// Shell commands hive runs in the worker's directory when the task is done
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verify: Option<Vec<String>>,
+    /// Ids of tasks (in any lane) that must be done before this one can be claimed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub depends_on: Option<Vec<String>>,
+    /// Lane a `global_backlog` task should be dispatched to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lane: Option<String>,
+    /// Project a `global_backlog` task belongs to; it goes to that project's
+    /// least-loaded lane
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    /// Free-form labels, filterable in the task queue. On a `global_backlog`
+    /// task, a label naming a lane or project routes it like `lane`/`project`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub labels: Option<Vec<String>>,
+    /// Worker id the task is pinned to; other workers on the lane can't claim it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assignee: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub claimed_by: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub claimed_at: Option<String>,
+    /// Who held the task before it was requeued, why, and when
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub requeued_from: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub completed_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub summary: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub files_changed: Option<Vec<String>>,
+    /// Set by a worker that needs the architect's input; relayed to the architect pane
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub question: Option<String>,
+    /// The architect's reply to `question`; relayed back to the worker's pane
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub answer: Option<String>,
+    /// Result of the last `verify` run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verification: Option<Verification>,
     /// PR URL for completed tasks
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub pr_url: Option<String>,
     /// Branch name for the task
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub branch: Option<String>,
+    /// Name of the workspace's `recurring:` entry that added the task
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recurring: Option<String>,
+    /// Fields hive doesn't know about, kept so rewrites don't drop them
+    #[serde(flatten)]
+    pub extra: IndexMap<String, serde_yaml::Value>,
 }
 
 #[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
@@ -70,6 +129,415 @@ pub struct TaskCounts {
     pub backlog: usize,
     pub in_progress: usize,
     pub done: usize,
+    /// Backlog tasks still waiting on unfinished dependencies
+    #[serde(default)]
+    pub blocked: usize,
+    /// In-progress tasks with a question the architect hasn't answered
+    #[serde(default)]
+    pub awaiting_answer: usize,
+}
+
+impl TaskCounts {
+    /// Backlog tasks whose dependencies are all done
+    pub fn claimable(&self) -> usize {
+        self.backlog.saturating_sub(self.blocked)
+    }
+}
+
+/// Task priority: a named level or a number where lower is more urgent.
+/// Named levels line up with numbers: critical = 0, high = 1, medium = 2, low = 3.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Priority {
+    Critical,
+    High,
+    Medium,
+    Low,
+    Numeric(u32),
+    /// Text kept as written in the tasks file: a known priority spelled
+    /// another way (`P1`, `High`), which ranks as what it spells, or an
+    /// unknown one (`urgent`), which ranks as medium
+    Other(String),
+}
+
+impl Priority {
+    /// A priority from the tasks file, keeping its spelling unless it's
+    /// already the canonical one
+    pub fn from_text(text: &str) -> Self {
+        match Self::parse(text) {
+            Some(priority) if priority.to_string() == text => priority,
+            _ => Self::Other(text.to_string()),
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        match value.to_ascii_lowercase().as_str() {
+            "critical" => Some(Self::Critical),
+            "high" => Some(Self::High),
+            "medium" => Some(Self::Medium),
+            "low" => Some(Self::Low),
+            other => other
+                .strip_prefix('p')
+                .unwrap_or(other)
+                .parse()
+                .ok()
+                .map(Self::Numeric),
+        }
+    }
+
+    /// Sort rank, lower is more urgent
+    pub fn rank(&self) -> u32 {
+        match self {
+            Self::Critical => 0,
+            Self::High => 1,
+            Self::Medium => 2,
+            Self::Low => 3,
+            Self::Numeric(n) => *n,
+            Self::Other(text) => Self::parse(text).map_or(2, |priority| priority.rank()),
+        }
+    }
+}
+
+impl Ord for Priority {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        // Named levels sort ahead of numbers with the same rank, and both
+        // ahead of other spellings
+        let kind = |p: &Self| match p {
+            Self::Numeric(_) => 1,
+            Self::Other(_) => 2,
+            _ => 0,
+        };
+        self.rank()
+            .cmp(&other.rank())
+            .then_with(|| kind(self).cmp(&kind(other)))
+            .then_with(|| self.to_string().cmp(&other.to_string()))
+    }
+}
+
+impl PartialOrd for Priority {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl std::fmt::Display for Priority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Critical => f.write_str("critical"),
+            Self::High => f.write_str("high"),
+            Self::Medium => f.write_str("medium"),
+            Self::Low => f.write_str("low"),
+            Self::Numeric(n) => write!(f, "{}", n),
+            Self::Other(text) => f.write_str(text),
+        }
+    }
+}
+
+impl Serialize for Priority {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Numeric(n) => serializer.serialize_u32(*n),
+            named => serializer.serialize_str(&named.to_string()),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Priority {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Number(u32),
+            Text(String),
+        }
+
+        Ok(match Raw::deserialize(deserializer)? {
+            Raw::Number(n) => Self::Numeric(n),
+            Raw::Text(text) => Self::from_text(&text),
+        })
+    }
+}
+
+/// The three states a task moves through within a lane
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskState {
+    Backlog,
+    InProgress,
+    Done,
+}
+
+impl TaskState {
+    pub const ALL: [TaskState; 3] = [TaskState::Backlog, TaskState::InProgress, TaskState::Done];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TaskState::Backlog => "backlog",
+            TaskState::InProgress => "in_progress",
+            TaskState::Done => "done",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "backlog" => Some(TaskState::Backlog),
+            "in_progress" | "in-progress" => Some(TaskState::InProgress),
+            "done" => Some(TaskState::Done),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for TaskState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl LaneTasks {
+    pub fn tasks(&self, state: TaskState) -> &Vec<Task> {
+        match state {
+            TaskState::Backlog => &self.backlog,
+            TaskState::InProgress => &self.in_progress,
+            TaskState::Done => &self.done,
+        }
+    }
+
+    pub fn tasks_mut(&mut self, state: TaskState) -> &mut Vec<Task> {
+        match state {
+            TaskState::Backlog => &mut self.backlog,
+            TaskState::InProgress => &mut self.in_progress,
+            TaskState::Done => &mut self.done,
+        }
+    }
+
+    /// Backlog ordered by priority, keeping file order within a priority
+    pub fn backlog_by_priority(&self) -> Vec<&Task> {
+        let mut backlog: Vec<&Task> = self.backlog.iter().collect();
+        backlog.sort_by_key(|task| task.priority_rank());
+        backlog
+    }
+
+    pub fn counts(&self) -> TaskCounts {
+        TaskCounts {
+            backlog: self.backlog.len(),
+            in_progress: self.in_progress.len(),
+            done: self.done.len(),
+            blocked: 0,
+            awaiting_answer: self
+                .in_progress
+                .iter()
+                .filter(|task| task.awaiting_answer())
+                .count(),
+        }
+    }
+}
+
+impl Task {
+    /// Sort rank for the backlog; tasks without a priority rank as medium
+    pub fn priority_rank(&self) -> u32 {
+        self.priority
+            .as_ref()
+            .map_or(Priority::Medium.rank(), Priority::rank)
+    }
+
+    /// The worker asked a question and the architect hasn't answered it yet
+    pub fn awaiting_answer(&self) -> bool {
+        let asked = self
+            .question
+            .as_deref()
+            .is_some_and(|q| !q.trim().is_empty());
+        let answered = self.answer.as_deref().is_some_and(|a| !a.trim().is_empty());
+        asked && !answered
+    }
+
+    /// Dependencies that aren't in `done_ids` yet
+    pub fn blockers<'a>(&'a self, done_ids: &HashSet<&str>) -> Vec<&'a str> {
+        self.depends_on
+            .iter()
+            .flatten()
+            .map(String::as_str)
+            .filter(|dep| !done_ids.contains(dep))
+            .collect()
+    }
+}
+
+/// Where a task lives in the tasks file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskLocation {
+    pub lane: String,
+    pub state: TaskState,
+    pub index: usize,
+}
+
+impl TasksFile {
+    /// All lanes in file order, using the same naming as `counts_for_lane`
+    pub fn lanes(&self) -> Vec<(String, &LaneTasks)> {
+        let mut lanes = Vec::new();
+        for (project, entry) in &self.projects {
+            match entry {
+                ProjectEntry::Direct(lane_tasks) => lanes.push((project.clone(), lane_tasks)),
+                ProjectEntry::Nested(nested) => {
+                    for (lane, lane_tasks) in nested {
+                        lanes.push((format!("{}/{}", project, lane), lane_tasks));
+                    }
+                }
+            }
+        }
+        lanes
+    }
+
+    pub fn lane(&self, lane: &str) -> Option<&LaneTasks> {
+        match lane.split_once('/') {
+            Some((project, sublane)) => match self.projects.get(project)? {
+                ProjectEntry::Nested(lanes) => lanes.get(sublane),
+                ProjectEntry::Direct(_) => None,
+            },
+            None => match self.projects.get(lane)? {
+                ProjectEntry::Direct(lane_tasks) => Some(lane_tasks),
+                ProjectEntry::Nested(_) => None,
+            },
+        }
+    }
+
+    pub fn lane_mut(&mut self, lane: &str) -> Option<&mut LaneTasks> {
+        match lane.split_once('/') {
+            Some((project, sublane)) => match self.projects.get_mut(project)? {
+                ProjectEntry::Nested(lanes) => lanes.get_mut(sublane),
+                ProjectEntry::Direct(_) => None,
+            },
+            None => match self.projects.get_mut(lane)? {
+                ProjectEntry::Direct(lane_tasks) => Some(lane_tasks),
+                ProjectEntry::Nested(_) => None,
+            },
+        }
+    }
+
+    /// Get a lane for writing, creating it (and its project) if missing
+    pub fn ensure_lane_mut(&mut self, lane: &str) -> Result<&mut LaneTasks> {
+        match lane.split_once('/') {
+            Some((project, sublane)) => {
+                let entry = self
+                    .projects
+                    .entry(project.to_string())
+                    .or_insert_with(|| ProjectEntry::Nested(IndexMap::new()));
+                match entry {
+                    ProjectEntry::Nested(lanes) => {
+                        Ok(lanes.entry(sublane.to_string()).or_default())
+                    }
+                    ProjectEntry::Direct(_) => {
+                        bail!(
+                            "'{}' is a single-lane project, use lane '{}'",
+                            project,
+                            project
+                        )
+                    }
+                }
+            }
+            None => {
+                let entry = self
+                    .projects
+                    .entry(lane.to_string())
+                    .or_insert_with(|| ProjectEntry::Direct(LaneTasks::default()));
+                match entry {
+                    ProjectEntry::Direct(lane_tasks) => Ok(lane_tasks),
+                    ProjectEntry::Nested(_) => {
+                        bail!("'{}' has nested lanes, use '{}/<lane>'", lane, lane)
+                    }
+                }
+            }
+        }
+    }
+
+    /// Find a task by id, optionally restricted to one lane
+    pub fn find_task(&self, id: &str, lane: Option<&str>) -> Result<TaskLocation> {
+        let mut found = Vec::new();
+        for (lane_name, lane_tasks) in self.lanes() {
+            if lane.is_some_and(|l| l != lane_name) {
+                continue;
+            }
+            for state in TaskState::ALL {
+                if let Some(index) = lane_tasks.tasks(state).iter().position(|t| t.id == id) {
+                    found.push(TaskLocation {
+                        lane: lane_name.clone(),
+                        state,
+                        index,
+                    });
+                }
+            }
+        }
+
+        match found.len() {
+            0 => match lane {
+                Some(lane) => bail!("Task '{}' not found in lane '{}'", id, lane),
+                None => bail!("Task '{}' not found", id),
+            },
+            1 => Ok(found.remove(0)),
+            _ => {
+                let lanes: Vec<String> = found.iter().map(|loc| loc.lane.clone()).collect();
+                bail!(
+                    "Task id '{}' is ambiguous (found in {}), pass --lane",
+                    id,
+                    lanes.join(", ")
+                )
+            }
+        }
+    }
+
+    /// Ids of every task sitting in some lane's `done`
+    pub fn done_ids(&self) -> HashSet<&str> {
+        self.lanes()
+            .into_iter()
+            .flat_map(|(_, lane_tasks)| lane_tasks.done.iter().map(|t| t.id.as_str()))
+            .chain(self.archived.ids.iter().map(String::as_str))
+            .collect()
+    }
+
+    /// Highest-priority backlog task in `lane` whose dependencies are done
+    pub fn next_task(&self, lane: &str) -> Option<&Task> {
+        let done_ids = self.done_ids();
+        self.lane(lane)?
+            .backlog_by_priority()
+            .into_iter()
+            .find(|task| task.blockers(&done_ids).is_empty())
+    }
+
+    /// Like `next_task`, skipping tasks assigned to a worker other than `worker`
+    pub fn next_task_for(&self, lane: &str, worker: &str) -> Option<&Task> {
+        let done_ids = self.done_ids();
+        self.lane(lane)?
+            .backlog_by_priority()
+            .into_iter()
+            .find(|task| {
+                task.blockers(&done_ids).is_empty()
+                    && task.assignee.as_deref().is_none_or(|a| a == worker)
+            })
+    }
+
+    pub fn task(&self, location: &TaskLocation) -> Option<&Task> {
+        self.lane(&location.lane)?
+            .tasks(location.state)
+            .get(location.index)
+    }
+
+    fn task_mut(&mut self, location: &TaskLocation) -> Result<&mut Task> {
+        self.lane_mut(&location.lane)
+            .with_context(|| format!("Lane '{}' not found", location.lane))?
+            .tasks_mut(location.state)
+            .get_mut(location.index)
+            .with_context(|| format!("Task index out of range in lane '{}'", location.lane))
+    }
+
+    fn take_task(&mut self, location: &TaskLocation) -> Result<Task> {
+        let lane_tasks = self
+            .lane_mut(&location.lane)
+            .with_context(|| format!("Lane '{}' not found", location.lane))?;
+        let tasks = lane_tasks.tasks_mut(location.state);
+        if location.index >= tasks.len() {
+            bail!("Task index out of range in lane '{}'", location.lane);
+        }
+        Ok(tasks.remove(location.index))
+    }
 }
 
 pub fn load_tasks(path: &Path) -> Result<TasksFile> {
@@ -80,39 +548,501 @@ pub fn load_tasks(path: &Path) -> Result<TasksFile> {
     Ok(tasks)
 }
 
+/// Write the tasks file atomically: serialize to a temp file next to it, then rename.
+/// Watchers and other readers only ever see the old or the new file, never a partial one.
+pub fn save_tasks(path: &Path, tasks: &TasksFile) -> Result<()> {
+    let content = serde_yaml::to_string(tasks).context("Failed serializing tasks file")?;
+    write_atomic(path, &content)
+}
+
+/// Replace `path` with `content` via a temp file and rename
+pub(crate) fn write_atomic(path: &Path, content: &str) -> Result<()> {
+    let file_name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("tasks.yaml");
+    let tmp_path = path.with_file_name(format!(".{}.{}.tmp", file_name, std::process::id()));
+    std::fs::write(&tmp_path, content)
+        .with_context(|| format!("Failed writing {}", tmp_path.display()))?;
+    if let Err(err) = std::fs::rename(&tmp_path, path) {
+        let _ = std::fs::remove_file(&tmp_path);
+        return Err(err).with_context(|| format!("Failed replacing {}", path.display()));
+    }
+    Ok(())
+}
+
+/// Load the tasks file under a shared lock so a concurrent `update_tasks` can't
+/// change it between the read and the parse. Falls back to an unlocked read if
+/// the lock file can't be created (e.g. a read-only directory); a lock that
+/// times out is an error rather than a torn read.
+pub fn load_tasks_locked(path: &Path) -> Result<TasksFile> {
+    load_tasks_locked_within(path, super::lock::LOCK_TIMEOUT)
+}
+
+/// `load_tasks_locked`, failing with `LockBusy` if a writer holds the lock
+/// for longer than `wait`
+pub fn load_tasks_locked_within(path: &Path, wait: Duration) -> Result<TasksFile> {
+    let _lock = super::lock::lock_shared_if_writable_within(path, wait)?;
+    load_tasks(path)
+}
+
+/// Load the tasks file, apply `f`, and write it back atomically if `f` succeeds.
+/// Holds the exclusive tasks lock for the whole read-modify-write.
+pub fn update_tasks<T>(path: &Path, f: impl FnOnce(&mut TasksFile) -> Result<T>) -> Result<T> {
+    update_tasks_within(path, super::lock::LOCK_TIMEOUT, f)
+}
+
+/// `update_tasks`, failing with `LockBusy` if the lock stays held for
+/// longer than `wait`
+pub fn update_tasks_within<T>(
+    path: &Path,
+    wait: Duration,
+    f: impl FnOnce(&mut TasksFile) -> Result<T>,
+) -> Result<T> {
+    let _lock = super::lock::lock_exclusive_within(path, wait)?;
+    let mut tasks = load_tasks(path)?;
+    let result = f(&mut tasks)?;
+    save_tasks(path, &tasks)?;
+    Ok(result)
+}
+
+/// Current time in the format hive writes to `claimed_at`/`completed_at`
+pub fn timestamp_now() -> String {
+    chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+}
+
+/// Parse a task timestamp: RFC 3339, or `2024-05-01T12:00:00` / `2024-05-01` as UTC
+pub fn parse_timestamp(value: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    let value = value.trim();
+    if let Ok(at) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Some(at.with_timezone(&chrono::Utc));
+    }
+    if let Ok(at) = chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S") {
+        return Some(at.and_utc());
+    }
+    chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|at| at.and_utc())
+}
+
+/// Parse a duration like `30s`, `30m`, `12h`, `7d` or `2w`
+pub fn parse_duration(value: &str) -> Result<chrono::Duration> {
+    let value = value.trim();
+    let unit_start = value.char_indices().last().map_or(0, |(i, _)| i);
+    let (amount, unit) = value.split_at(unit_start);
+    let Ok(amount) = amount.parse::<i64>() else {
+        bail!(
+            "Can't parse '{}' as a duration; use e.g. 30m, 12h, 7d or 2w",
+            value
+        );
+    };
+    Ok(match unit {
+        "s" => chrono::Duration::seconds(amount),
+        "m" => chrono::Duration::minutes(amount),
+        "h" => chrono::Duration::hours(amount),
+        "d" => chrono::Duration::days(amount),
+        "w" => chrono::Duration::weeks(amount),
+        _ => bail!(
+            "Unknown unit '{}' in '{}' (use s, m, h, d or w)",
+            unit,
+            value
+        ),
+    })
+}
+
+/// Refuse a lane that's neither configured nor already in the file. Adding
+/// to one would start a lane no worker reads, most often from a typo.
+pub fn check_lane_known(tasks: &TasksFile, lane: &str, configured: &[String]) -> Result<()> {
+    if tasks.lane(lane).is_some() || configured.iter().any(|known| known == lane) {
+        return Ok(());
+    }
+    bail!(
+        "Unknown lane '{}' (configured: {})",
+        lane,
+        if configured.is_empty() {
+            "none".to_string()
+        } else {
+            configured.join(", ")
+        }
+    )
+}
+
+/// Add a task to a lane's backlog, creating the lane if needed
+pub fn add_task(tasks: &mut TasksFile, lane: &str, task: Task) -> Result<()> {
+    if task.id.trim().is_empty() {
+        bail!("Task id must not be empty");
+    }
+    if let Ok(existing) = tasks.find_task(&task.id, Some(lane)) {
+        bail!(
+            "Task '{}' already exists in lane '{}' ({})",
+            task.id,
+            existing.lane,
+            existing.state
+        );
+    }
+    tasks.ensure_lane_mut(lane)?.backlog.push(task);
+    Ok(())
+}
+
+/// Add a task to a lane's backlog unless a task from the same `recurring`
+/// entry is still in that lane's backlog or in_progress. Tasks from no entry
+/// are always added. Returns whether it was added.
+pub fn add_task_unless_open(tasks: &mut TasksFile, lane: &str, task: Task) -> Result<bool> {
+    let still_open = task.recurring.is_some()
+        && tasks.lane(lane).is_some_and(|open| {
+            open.backlog
+                .iter()
+                .chain(&open.in_progress)
+                .any(|t| t.recurring == task.recurring)
+        });
+    if still_open {
+        return Ok(false);
+    }
+    add_task(tasks, lane, task)?;
+    Ok(true)
+}
+
+/// Why a claim was refused. Returned inside the `anyhow::Error` from
+/// `claim_task` so callers can `downcast_ref` when they need to react to it.
+#[derive(Debug, thiserror::Error)]
+pub enum ClaimError {
+    #[error("Task '{id}' is already claimed by {claimed_by}")]
+    AlreadyClaimed { id: String, claimed_by: String },
+    #[error("Task '{id}' is in {state}, only backlog tasks can be claimed")]
+    NotInBacklog { id: String, state: TaskState },
+    #[error("Task '{id}' is blocked until {} are done", waiting_on.join(", "))]
+    Blocked { id: String, waiting_on: Vec<String> },
+    #[error("Task '{id}' is assigned to {assignee}")]
+    AssignedToOther { id: String, assignee: String },
+}
+
+/// Move a backlog task to in_progress and record who claimed it.
+/// Run it inside `update_tasks` so the check and the claim happen under one lock.
+pub fn claim_task(
+    tasks: &mut TasksFile,
+    id: &str,
+    lane: Option<&str>,
+    worker: &str,
+) -> Result<TaskLocation> {
+    let location = tasks.find_task(id, lane)?;
+    if location.state != TaskState::Backlog {
+        let claimed_by = tasks.task(&location).and_then(|t| t.claimed_by.clone());
+        return Err(match (location.state, claimed_by) {
+            (TaskState::InProgress, Some(claimed_by)) => ClaimError::AlreadyClaimed {
+                id: id.to_string(),
+                claimed_by,
+            },
+            (state, _) => ClaimError::NotInBacklog {
+                id: id.to_string(),
+                state,
+            },
+        }
+        .into());
+    }
+    let waiting_on: Vec<String> = match tasks.task(&location) {
+        Some(task) => task
+            .blockers(&tasks.done_ids())
+            .into_iter()
+            .map(String::from)
+            .collect(),
+        None => Vec::new(),
+    };
+    if !waiting_on.is_empty() {
+        return Err(ClaimError::Blocked {
+            id: id.to_string(),
+            waiting_on,
+        }
+        .into());
+    }
+    if let Some(assignee) = tasks
+        .task(&location)
+        .and_then(|task| task.assignee.as_deref())
+        .filter(|assignee| *assignee != worker)
+    {
+        return Err(ClaimError::AssignedToOther {
+            id: id.to_string(),
+            assignee: assignee.to_string(),
+        }
+        .into());
+    }
+    let mut task = tasks.take_task(&location)?;
+    task.claimed_by = Some(worker.to_string());
+    task.claimed_at = Some(timestamp_now());
+    let lane_tasks = tasks.ensure_lane_mut(&location.lane)?;
+    lane_tasks.in_progress.push(task);
+    Ok(TaskLocation {
+        lane: location.lane,
+        state: TaskState::InProgress,
+        index: lane_tasks.in_progress.len() - 1,
+    })
+}
+
+/// Details recorded when a task is completed
+#[derive(Debug, Default, Clone)]
+pub struct Completion {
+    pub summary: Option<String>,
+    pub pr_url: Option<String>,
+    pub branch: Option<String>,
+    pub files_changed: Vec<String>,
+}
+
+/// Move an in-progress task to done and record the completion details
+pub fn complete_task(
+    tasks: &mut TasksFile,
+    id: &str,
+    lane: Option<&str>,
+    completion: Completion,
+) -> Result<TaskLocation> {
+    let location = tasks.find_task(id, lane)?;
+    if location.state != TaskState::InProgress {
+        bail!(
+            "Task '{}' is in {}, only in_progress tasks can be completed",
+            id,
+            location.state
+        );
+    }
+    let mut task = tasks.take_task(&location)?;
+    task.completed_at = Some(timestamp_now());
+    // Any earlier result is out of date; hive verifies the task again
+    task.verification = None;
+    if completion.summary.is_some() {
+        task.summary = completion.summary;
+    }
+    if completion.pr_url.is_some() {
+        task.pr_url = completion.pr_url;
+    }
+    if completion.branch.is_some() {
+        task.branch = completion.branch;
+    }
+    if !completion.files_changed.is_empty() {
+        task.files_changed = Some(completion.files_changed);
+    }
+    let lane_tasks = tasks.ensure_lane_mut(&location.lane)?;
+    lane_tasks.done.push(task);
+    Ok(TaskLocation {
+        lane: location.lane,
+        state: TaskState::Done,
+        index: lane_tasks.done.len() - 1,
+    })
+}
+
+/// Store the result of a task's `verify` commands. A failure sends a done
+/// task back to in_progress so its worker picks it up again.
+pub fn record_verification(
+    tasks: &mut TasksFile,
+    id: &str,
+    lane: Option<&str>,
+    verification: Verification,
+) -> Result<TaskLocation> {
+    let location = tasks.find_task(id, lane)?;
+    if verification.passed || location.state != TaskState::Done {
+        tasks.task_mut(&location)?.verification = Some(verification);
+        return Ok(location);
+    }
+    let mut task = tasks.take_task(&location)?;
+    task.completed_at = None;
+    task.verification = Some(verification);
+    let lane_tasks = tasks.ensure_lane_mut(&location.lane)?;
+    lane_tasks.in_progress.push(task);
+    Ok(TaskLocation {
+        lane: location.lane,
+        state: TaskState::InProgress,
+        index: lane_tasks.in_progress.len() - 1,
+    })
+}
+
+/// Record a worker's question on an in-progress task. A new question clears
+/// the previous answer so the task shows as awaiting one again.
+pub fn ask_question(
+    tasks: &mut TasksFile,
+    id: &str,
+    lane: Option<&str>,
+    question: &str,
+) -> Result<TaskLocation> {
+    let location = tasks.find_task(id, lane)?;
+    if location.state != TaskState::InProgress {
+        bail!(
+            "Task '{}' is in {}, questions can only be asked on in_progress tasks",
+            id,
+            location.state
+        );
+    }
+    let task = tasks.task_mut(&location)?;
+    task.question = Some(question.to_string());
+    task.answer = None;
+    Ok(location)
+}
+
+/// Record the architect's answer to a task's question
+pub fn answer_question(
+    tasks: &mut TasksFile,
+    id: &str,
+    lane: Option<&str>,
+    answer: &str,
+) -> Result<TaskLocation> {
+    let location = tasks.find_task(id, lane)?;
+    let task = tasks.task_mut(&location)?;
+    if task.question.is_none() {
+        bail!("Task '{}' has no question to answer", id);
+    }
+    task.answer = Some(answer.to_string());
+    Ok(location)
+}
+
+/// Move a task to another state and/or lane without the claim/complete checks.
+/// Moving back to backlog clears the claim so the task can be picked up again.
+pub fn move_task(
+    tasks: &mut TasksFile,
+    id: &str,
+    lane: Option<&str>,
+    to_state: TaskState,
+    to_lane: Option<&str>,
+) -> Result<TaskLocation> {
+    let location = tasks.find_task(id, lane)?;
+    let target_lane = to_lane.unwrap_or(&location.lane).to_string();
+    if target_lane != location.lane {
+        if let Ok(existing) = tasks.find_task(id, Some(&target_lane)) {
+            bail!(
+                "Task '{}' already exists in lane '{}' ({})",
+                id,
+                existing.lane,
+                existing.state
+            );
+        }
+        // Validate the target before removing anything
+        tasks.ensure_lane_mut(&target_lane)?;
+    }
+
+    let mut task = tasks.take_task(&location)?;
+    if to_state == TaskState::Backlog {
+        task.claimed_by = None;
+        task.claimed_at = None;
+    }
+    if to_state != TaskState::Done {
+        task.completed_at = None;
+    }
+    let list = tasks.ensure_lane_mut(&target_lane)?.tasks_mut(to_state);
+    list.push(task);
+    Ok(TaskLocation {
+        lane: target_lane,
+        state: to_state,
+        index: list.len() - 1,
+    })
+}
+
+/// Move a backlog task one place up or down in its lane's priority order.
+/// Passing a task with another priority takes that priority, so the task
+/// keeps its new place when the backlog is sorted again.
+pub fn reprioritize_task(
+    tasks: &mut TasksFile,
+    id: &str,
+    lane: Option<&str>,
+    up: bool,
+) -> Result<TaskLocation> {
+    let location = tasks.find_task(id, lane)?;
+    if location.state != TaskState::Backlog {
+        bail!(
+            "Task '{}' is in {}, only backlog tasks can be reprioritized",
+            id,
+            location.state
+        );
+    }
+    let backlog = tasks
+        .ensure_lane_mut(&location.lane)?
+        .tasks_mut(TaskState::Backlog);
+    let mut order: Vec<usize> = (0..backlog.len()).collect();
+    order.sort_by_key(|&index| backlog[index].priority_rank());
+    let position = order
+        .iter()
+        .position(|&index| index == location.index)
+        .context("Task missing from its backlog")?;
+    let neighbour = if up {
+        position.checked_sub(1)
+    } else {
+        Some(position + 1).filter(|&next| next < order.len())
+    };
+    // Already first or last
+    let Some(neighbour) = neighbour.map(|next| order[next]) else {
+        return Ok(location);
+    };
+
+    let mut task = backlog.remove(location.index);
+    let neighbour = if neighbour > location.index {
+        neighbour - 1
+    } else {
+        neighbour
+    };
+    if task.priority_rank() != backlog[neighbour].priority_rank() {
+        task.priority = backlog[neighbour].priority.clone();
+    }
+    let index = if up { neighbour } else { neighbour + 1 };
+    backlog.insert(index, task);
+    Ok(TaskLocation { index, ..location })
+}
+
+/// Remove a task from the tasks file, returning it
+pub fn delete_task(tasks: &mut TasksFile, id: &str, lane: Option<&str>) -> Result<Task> {
+    let location = tasks.find_task(id, lane)?;
+    tasks.take_task(&location)
+}
+
+/// Move an in-progress task back to the front of its lane's backlog,
+/// recording who held it and why in `requeued_from`
+pub fn requeue_task(
+    tasks: &mut TasksFile,
+    id: &str,
+    lane: Option<&str>,
+    reason: &str,
+) -> Result<TaskLocation> {
+    let location = tasks.find_task(id, lane)?;
+    if location.state != TaskState::InProgress {
+        bail!(
+            "Task '{}' is in {}, only in_progress tasks can be requeued",
+            id,
+            location.state
+        );
+    }
+    let mut task = tasks.take_task(&location)?;
+    let holder = task
+        .claimed_by
+        .take()
+        .unwrap_or_else(|| "nobody".to_string());
+    task.claimed_at = None;
+    task.requeued_from = Some(format!("{} ({}, {})", holder, reason, timestamp_now()));
+    tasks
+        .ensure_lane_mut(&location.lane)?
+        .backlog
+        .insert(0, task);
+    Ok(TaskLocation {
+        lane: location.lane,
+        state: TaskState::Backlog,
+        index: 0,
+    })
+}
+
 /// Get task counts for a lane. Lane format:
 /// - "project/lane" for nested (e.g., "backend/fixes")
 /// - "project" for direct (e.g., "android-sdk")
 pub fn counts_for_lane(tasks: &TasksFile, lane: &str) -> TaskCounts {
-    // Check if lane has a slash (nested format: project/lane)
-    if let Some((project, sublane)) = lane.split_once('/') {
-        if let Some(ProjectEntry::Nested(lanes)) = tasks.projects.get(project) {
-            if let Some(lane_tasks) = lanes.get(sublane) {
-                return TaskCounts {
-                    backlog: lane_tasks.backlog.len(),
-                    in_progress: lane_tasks.in_progress.len(),
-                    done: lane_tasks.done.len(),
-                };
-            }
-        }
-    } else {
-        // Direct format: project name is the lane
-        match tasks.projects.get(lane) {
-            Some(ProjectEntry::Direct(lane_tasks)) => {
-                return TaskCounts {
-                    backlog: lane_tasks.backlog.len(),
-                    in_progress: lane_tasks.in_progress.len(),
-                    done: lane_tasks.done.len(),
-                };
-            }
-            Some(ProjectEntry::Nested(_)) => {
-                // Project has nested lanes but was queried without sublane
-                // This shouldn't happen with proper config
-            }
-            None => {}
-        }
+    let archived = tasks.archived.done.get(lane).copied().unwrap_or(0);
+    let Some(lane_tasks) = tasks.lane(lane) else {
+        return TaskCounts {
+            done: archived,
+            ..Default::default()
+        };
+    };
+    let done_ids = tasks.done_ids();
+    let counts = lane_tasks.counts();
+    TaskCounts {
+        blocked: lane_tasks
+            .backlog
+            .iter()
+            .filter(|task| !task.blockers(&done_ids).is_empty())
+            .count(),
+        done: counts.done + archived,
+        ..counts
     }
-    TaskCounts::default()
 }
 
 #[cfg(test)]
@@ -232,4 +1162,453 @@ android-sdk:
         assert_eq!(android_counts.backlog, 1);
         assert_eq!(android_counts.done, 2);
     }
+
+    const LIFECYCLE_YAML: &str = r#"
+backend:
+  fixes:
+    backlog:
+      - id: fix-login
+        title: Fix login
+        notes: keep me
+    in_progress: []
+    done: []
+android-sdk:
+  backlog:
+    - id: bump-sdk
+  in_progress: []
+  done: []
+"#;
+
+    #[test]
+    fn test_claim_and_complete_nested_lane() {
+        let mut tasks: TasksFile = serde_yaml::from_str(LIFECYCLE_YAML).unwrap();
+
+        let claimed = claim_task(&mut tasks, "fix-login", None, "worker-2").unwrap();
+        assert_eq!(claimed.lane, "backend/fixes");
+        assert_eq!(claimed.state, TaskState::InProgress);
+        let task = tasks.task(&claimed).unwrap();
+        assert_eq!(task.claimed_by.as_deref(), Some("worker-2"));
+        assert!(task.claimed_at.is_some());
+
+        // Claiming again fails because it is no longer in backlog
+        assert!(claim_task(&mut tasks, "fix-login", None, "worker-3").is_err());
+
+        let done = complete_task(
+            &mut tasks,
+            "fix-login",
+            Some("backend/fixes"),
+            Completion {
+                summary: Some("Fixed".to_string()),
+                files_changed: vec!["src/login.rs".to_string()],
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let task = tasks.task(&done).unwrap();
+        assert_eq!(task.summary.as_deref(), Some("Fixed"));
+        assert!(task.completed_at.is_some());
+        assert_eq!(counts_for_lane(&tasks, "backend/fixes").done, 1);
+        assert_eq!(counts_for_lane(&tasks, "backend/fixes").backlog, 0);
+    }
+
+    #[test]
+    fn test_add_and_move_between_lanes() {
+        let mut tasks: TasksFile = serde_yaml::from_str(LIFECYCLE_YAML).unwrap();
+
+        add_task(
+            &mut tasks,
+            "android-sdk",
+            Task {
+                id: "new-task".to_string(),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert!(add_task(
+            &mut tasks,
+            "android-sdk",
+            Task {
+                id: "new-task".to_string(),
+                ..Default::default()
+            }
+        )
+        .is_err());
+
+        claim_task(&mut tasks, "bump-sdk", None, "worker-1").unwrap();
+        let moved = move_task(
+            &mut tasks,
+            "bump-sdk",
+            None,
+            TaskState::Backlog,
+            Some("backend/features"),
+        )
+        .unwrap();
+        assert_eq!(moved.lane, "backend/features");
+        let task = tasks.task(&moved).unwrap();
+        assert!(task.claimed_by.is_none());
+
+        // A direct project can't grow nested lanes
+        assert!(add_task(
+            &mut tasks,
+            "android-sdk/extra",
+            Task {
+                id: "x".to_string(),
+                ..Default::default()
+            }
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_depends_on_blocks_until_done_in_other_lane() {
+        let mut tasks: TasksFile = serde_yaml::from_str(LIFECYCLE_YAML).unwrap();
+        add_task(
+            &mut tasks,
+            "android-sdk",
+            Task {
+                id: "use-login".to_string(),
+                depends_on: Some(vec!["fix-login".to_string()]),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let counts = counts_for_lane(&tasks, "android-sdk");
+        assert_eq!(counts.backlog, 2);
+        assert_eq!(counts.blocked, 1);
+        assert_eq!(counts.claimable(), 1);
+
+        let err = claim_task(&mut tasks, "use-login", None, "worker-1").unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ClaimError>(),
+            Some(ClaimError::Blocked { waiting_on, .. }) if waiting_on == &["fix-login"]
+        ));
+
+        claim_task(&mut tasks, "fix-login", None, "worker-2").unwrap();
+        complete_task(&mut tasks, "fix-login", None, Completion::default()).unwrap();
+
+        assert_eq!(counts_for_lane(&tasks, "android-sdk").blocked, 0);
+        claim_task(&mut tasks, "use-login", None, "worker-1").unwrap();
+    }
+
+    #[test]
+    fn test_question_awaits_answer_until_answered() {
+        let mut tasks: TasksFile = serde_yaml::from_str(LIFECYCLE_YAML).unwrap();
+        assert!(ask_question(&mut tasks, "fix-login", None, "Which page?").is_err());
+        assert!(answer_question(&mut tasks, "fix-login", None, "Home").is_err());
+
+        claim_task(&mut tasks, "fix-login", None, "worker-1").unwrap();
+        ask_question(&mut tasks, "fix-login", None, "Which page?").unwrap();
+        assert_eq!(counts_for_lane(&tasks, "backend/fixes").awaiting_answer, 1);
+
+        answer_question(&mut tasks, "fix-login", None, "The home page").unwrap();
+        assert_eq!(counts_for_lane(&tasks, "backend/fixes").awaiting_answer, 0);
+
+        // A follow-up question clears the old answer
+        let location = ask_question(&mut tasks, "fix-login", None, "And mobile?").unwrap();
+        let task = tasks.task(&location).unwrap();
+        assert!(task.awaiting_answer());
+        assert_eq!(task.answer, None);
+    }
+
+    #[test]
+    fn test_requeue_returns_claim_to_backlog() {
+        let mut tasks: TasksFile = serde_yaml::from_str(LIFECYCLE_YAML).unwrap();
+        assert!(requeue_task(&mut tasks, "fix-login", None, "pane exited").is_err());
+
+        claim_task(&mut tasks, "fix-login", None, "worker-1").unwrap();
+        let location = requeue_task(&mut tasks, "fix-login", None, "pane exited").unwrap();
+        assert_eq!(location.state, TaskState::Backlog);
+        assert_eq!(location.index, 0);
+        let task = tasks.task(&location).unwrap();
+        assert_eq!(task.claimed_by, None);
+        assert_eq!(task.claimed_at, None);
+        assert!(task
+            .requeued_from
+            .as_deref()
+            .is_some_and(|note| note.starts_with("worker-1 (pane exited, ")));
+    }
+
+    #[test]
+    fn test_failed_verification_reopens_done_task() {
+        let mut tasks: TasksFile = serde_yaml::from_str(LIFECYCLE_YAML).unwrap();
+        claim_task(&mut tasks, "fix-login", None, "worker-1").unwrap();
+        complete_task(&mut tasks, "fix-login", None, Completion::default()).unwrap();
+        let verification = |passed: bool| Verification {
+            passed,
+            at: timestamp_now(),
+            command: (!passed).then(|| "cargo test".to_string()),
+            output: (!passed).then(|| "1 failed".to_string()),
+        };
+
+        let location =
+            record_verification(&mut tasks, "fix-login", None, verification(false)).unwrap();
+        assert_eq!(location.state, TaskState::InProgress);
+        let task = tasks.task(&location).unwrap();
+        assert_eq!(task.claimed_by.as_deref(), Some("worker-1"));
+        assert_eq!(task.completed_at, None);
+
+        complete_task(&mut tasks, "fix-login", None, Completion::default()).unwrap();
+        let location =
+            record_verification(&mut tasks, "fix-login", None, verification(true)).unwrap();
+        assert_eq!(location.state, TaskState::Done);
+        let task = tasks.task(&location).unwrap();
+        assert!(task.verification.as_ref().is_some_and(|v| v.passed));
+    }
+
+    #[test]
+    fn test_add_task_unless_open_skips_open_task_from_same_entry() {
+        let mut tasks: TasksFile = serde_yaml::from_str(LIFECYCLE_YAML).unwrap();
+        // Titles vary between runs when the template fills in dates and such
+        let recurring = |id: &str, entry: &str| Task {
+            id: id.to_string(),
+            title: Some(format!("Triage for {}", id)),
+            recurring: Some(entry.to_string()),
+            ..Default::default()
+        };
+
+        assert!(
+            add_task_unless_open(&mut tasks, "backend/fixes", recurring("a", "triage")).unwrap()
+        );
+        assert!(
+            !add_task_unless_open(&mut tasks, "backend/fixes", recurring("b", "triage")).unwrap()
+        );
+        // Another entry, or a task from none, isn't held back
+        assert!(add_task_unless_open(&mut tasks, "backend/fixes", recurring("c", "deps")).unwrap());
+        let plain = |id: &str| Task {
+            id: id.to_string(),
+            ..Default::default()
+        };
+        assert!(add_task_unless_open(&mut tasks, "backend/fixes", plain("d")).unwrap());
+        assert!(add_task_unless_open(&mut tasks, "backend/fixes", plain("e")).unwrap());
+
+        claim_task(&mut tasks, "a", None, "worker-1").unwrap();
+        assert!(
+            !add_task_unless_open(&mut tasks, "backend/fixes", recurring("f", "triage")).unwrap()
+        );
+        complete_task(&mut tasks, "a", None, Completion::default()).unwrap();
+        assert!(
+            add_task_unless_open(&mut tasks, "backend/fixes", recurring("g", "triage")).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_check_lane_known() {
+        let tasks: TasksFile = serde_yaml::from_str(LIFECYCLE_YAML).unwrap();
+        let configured = vec!["backend/fixes".to_string(), "backend/api".to_string()];
+
+        // In the file, configured but not yet in the file, or neither
+        assert!(check_lane_known(&tasks, "android-sdk", &configured).is_ok());
+        assert!(check_lane_known(&tasks, "backend/api", &configured).is_ok());
+        let err = check_lane_known(&tasks, "backend/fixs", &configured).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unknown lane 'backend/fixs' (configured: backend/fixes, backend/api)"
+        );
+    }
+
+    #[test]
+    fn test_assignee_pins_task_to_worker() {
+        let mut tasks: TasksFile = serde_yaml::from_str(
+            r#"
+api:
+  backlog:
+    - id: pinned
+      priority: high
+      assignee: worker-2
+    - id: open
+"#,
+        )
+        .unwrap();
+        assert_eq!(tasks.next_task_for("api", "worker-1").unwrap().id, "open");
+        assert_eq!(tasks.next_task_for("api", "worker-2").unwrap().id, "pinned");
+
+        let err = claim_task(&mut tasks, "pinned", None, "worker-1").unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ClaimError>(),
+            Some(ClaimError::AssignedToOther { assignee, .. }) if assignee == "worker-2"
+        ));
+        claim_task(&mut tasks, "pinned", None, "worker-2").unwrap();
+    }
+
+    #[test]
+    fn test_reprioritize_moves_task_past_neighbour() {
+        let mut tasks: TasksFile = serde_yaml::from_str(
+            r#"
+api:
+  backlog:
+    - id: first
+      priority: high
+    - id: second
+    - id: third
+    - id: last
+      priority: low
+"#,
+        )
+        .unwrap();
+        let order = |tasks: &TasksFile| -> Vec<String> {
+            let lane = tasks.lane("api").unwrap();
+            lane.backlog_by_priority()
+                .iter()
+                .map(|t| t.id.clone())
+                .collect()
+        };
+
+        reprioritize_task(&mut tasks, "third", None, true).unwrap();
+        assert_eq!(order(&tasks), ["first", "third", "second", "last"]);
+
+        let location = reprioritize_task(&mut tasks, "third", None, true).unwrap();
+        assert_eq!(order(&tasks), ["third", "first", "second", "last"]);
+        assert_eq!(
+            tasks.task(&location).unwrap().priority,
+            Some(Priority::High)
+        );
+
+        // Already at the top
+        reprioritize_task(&mut tasks, "third", None, true).unwrap();
+        assert_eq!(order(&tasks), ["third", "first", "second", "last"]);
+
+        reprioritize_task(&mut tasks, "second", None, false).unwrap();
+        assert_eq!(order(&tasks), ["third", "first", "last", "second"]);
+
+        claim_task(&mut tasks, "first", None, "worker-1").unwrap();
+        assert!(reprioritize_task(&mut tasks, "first", None, false).is_err());
+
+        assert_eq!(
+            delete_task(&mut tasks, "first", Some("api")).unwrap().id,
+            "first"
+        );
+        assert!(tasks.find_task("first", None).is_err());
+    }
+
+    #[test]
+    fn test_priority_orders_backlog_and_picks_next_task() {
+        let yaml = r#"
+api:
+  backlog:
+    - id: unranked
+    - id: cleanup
+      priority: low
+    - id: outage
+      priority: critical
+    - id: waits-on-cleanup
+      priority: 0
+      depends_on: [cleanup]
+    - id: feature
+      priority: P1
+  in_progress: []
+  done: []
+"#;
+        let tasks: TasksFile = serde_yaml::from_str(yaml).unwrap();
+        let lane = tasks.lane("api").unwrap();
+        let order: Vec<&str> = lane
+            .backlog_by_priority()
+            .iter()
+            .map(|t| t.id.as_str())
+            .collect();
+        assert_eq!(
+            order,
+            [
+                "outage",
+                "waits-on-cleanup",
+                "feature",
+                "unranked",
+                "cleanup"
+            ]
+        );
+        assert_eq!(tasks.next_task("api").unwrap().id, "outage");
+
+        // Numbers stay numbers and names stay names when written back
+        let out = serde_yaml::to_string(&tasks).unwrap();
+        assert!(out.contains("priority: 0\n"));
+        assert!(out.contains("priority: P1\n"));
+        assert!(out.contains("priority: critical\n"));
+    }
+
+    #[test]
+    fn test_unknown_priority_loads_and_keeps_spelling() {
+        let yaml = r#"
+api:
+  backlog:
+    - id: someday
+      priority: urgent
+    - id: soon
+      priority: High
+    - id: now
+      priority: P0
+  in_progress: []
+  done: []
+"#;
+        let tasks: TasksFile = serde_yaml::from_str(yaml).unwrap();
+        let lane = tasks.lane("api").unwrap();
+        let ranks: Vec<u32> = lane.backlog.iter().map(|t| t.priority_rank()).collect();
+        assert_eq!(ranks, [2, 1, 0]);
+        let order: Vec<&str> = lane
+            .backlog_by_priority()
+            .iter()
+            .map(|t| t.id.as_str())
+            .collect();
+        assert_eq!(order, ["now", "soon", "someday"]);
+
+        let out = serde_yaml::to_string(&tasks).unwrap();
+        assert!(out.contains("priority: urgent\n"));
+        assert!(out.contains("priority: High\n"));
+        assert!(out.contains("priority: P0\n"));
+    }
+
+    #[test]
+    fn test_concurrent_claims_have_one_winner() {
+        let dir = std::env::temp_dir().join(format!("hive-claims-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("tasks.yaml");
+        std::fs::write(&path, LIFECYCLE_YAML).unwrap();
+
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    update_tasks(&path, |tasks| {
+                        claim_task(tasks, "fix-login", None, &format!("worker-{}", i))
+                    })
+                })
+            })
+            .collect();
+        let results: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+
+        assert_eq!(results.iter().filter(|r| r.is_ok()).count(), 1);
+        for err in results.iter().filter_map(|r| r.as_ref().err()) {
+            assert!(matches!(
+                err.downcast_ref::<ClaimError>(),
+                Some(ClaimError::AlreadyClaimed { .. })
+            ));
+        }
+
+        let tasks = load_tasks_locked(&path).unwrap();
+        assert_eq!(counts_for_lane(&tasks, "backend/fixes").in_progress, 1);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_update_tasks_round_trip_keeps_order_and_unknown_fields() {
+        let dir = std::env::temp_dir().join(format!("hive-tasks-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("tasks.yaml");
+        std::fs::write(&path, LIFECYCLE_YAML).unwrap();
+
+        update_tasks(&path, |tasks| {
+            claim_task(tasks, "bump-sdk", None, "worker-1").map(|_| ())
+        })
+        .unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.find("backend").unwrap() < content.find("android-sdk").unwrap());
+        assert!(content.contains("notes: keep me"));
+        assert!(!content.contains("null"));
+
+        let tasks = load_tasks(&path).unwrap();
+        assert_eq!(counts_for_lane(&tasks, "android-sdk").in_progress, 1);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
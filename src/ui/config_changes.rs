@@ -0,0 +1,66 @@
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
+
+use crate::app::state::App;
+
+/// Edits to the config file the server couldn't apply to running agents,
+/// with the panes a restart would affect
+pub fn render_config_changes(frame: &mut Frame, app: &App) {
+    let area = centered_rect(70, 50, frame.area());
+    frame.render_widget(Clear, area);
+
+    let mut lines = vec![
+        Line::from("These config edits take effect when the hive is restarted:"),
+        Line::from(""),
+    ];
+    for change in &app.config_changes {
+        lines.push(Line::from(format!("- {}", change.change)));
+        if !change.panes.is_empty() {
+            lines.push(Line::styled(
+                format!("    affects {}", change.panes.join(", ")),
+                Style::default().fg(Color::Yellow),
+            ));
+        }
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::styled(
+        "Lanes can also be added and removed without a restart with `hive worker`",
+        Style::default().fg(Color::DarkGray),
+    ));
+    lines.push(Line::styled(
+        "Esc close",
+        Style::default().fg(Color::DarkGray),
+    ));
+
+    let block = Block::default()
+        .title("config changes")
+        .borders(Borders::ALL)
+        .style(Style::default().bg(Color::Black));
+
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .wrap(Wrap { trim: false })
+        .style(Style::default().fg(Color::White));
+
+    frame.render_widget(paragraph, area);
+}
+
+fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
+    let popup_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage((100 - percent_y) / 2),
+            Constraint::Percentage(percent_y),
+            Constraint::Percentage((100 - percent_y) / 2),
+        ])
+        .split(r);
+
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(popup_layout[1])[1]
+}
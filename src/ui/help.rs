@@ -27,6 +27,7 @@ pub fn render_help_overlay(frame: &mut Frame, _app: &App) {
         "  Up/Down or j/k  - Move selection",
         "  Space           - Toggle visibility",
         "  Enter           - Show + focus pane",
+        "  r               - Restart pane's agent",
         "  Left/Right h/l  - Collapse/expand group",
         "  a               - Show all (group/all)",
         "  n               - Hide all (group/all)",
@@ -1,3 +1,4 @@
+pub mod config_changes;
 pub mod help;
 pub mod layout;
 pub mod palette;
@@ -32,6 +33,10 @@ pub fn render(frame: &mut Frame, app: &App) {
         help::render_help_overlay(frame, app);
     }
 
+    if app.show_config_changes {
+        config_changes::render_config_changes(frame, app);
+    }
+
     if app.show_projects {
         projects::render_projects(frame, app);
     }
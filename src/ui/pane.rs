@@ -3,7 +3,7 @@ use ratatui::style::Modifier;
 use ratatui::widgets::{Block, Borders};
 
 use crate::app::state::ClientPane;
-use crate::app::types::PaneType;
+use crate::app::types::{AgentState, PaneType};
 use crate::pty::output::OutputBuffer;
 use crate::ui::terminal::TerminalWidget;
 
@@ -18,7 +18,7 @@ pub fn render_pane(
     let border_color = if focused {
         Color::Yellow
     } else {
-        Color::DarkGray
+        agent_state_color(pane.agent_state)
     };
     let title_color = if focused { Color::Yellow } else { Color::Blue };
 
@@ -51,6 +51,12 @@ pub fn render_pane(
     if scroll_offset > 0 {
         title.push_str(&format!(" [scroll {}]", scroll_offset));
     }
+    if matches!(
+        pane.agent_state,
+        AgentState::AwaitingApproval | AgentState::Errored | AgentState::Exited
+    ) {
+        title.push_str(&format!(" [{}]", pane.agent_state.label()));
+    }
 
     let block = Block::default()
         .borders(Borders::ALL)
@@ -87,3 +93,14 @@ pub fn render_pane(
             .set_style(inner, Style::default().add_modifier(Modifier::DIM));
     }
 }
+
+/// Border and sidebar color for what a pane's agent is doing
+pub fn agent_state_color(state: AgentState) -> Color {
+    match state {
+        AgentState::Busy => Color::Green,
+        AgentState::Idle => Color::DarkGray,
+        AgentState::AwaitingApproval => Color::Magenta,
+        AgentState::Errored => Color::Red,
+        AgentState::Exited => Color::LightRed,
+    }
+}
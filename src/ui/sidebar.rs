@@ -3,6 +3,7 @@ use ratatui::widgets::{Block, Borders, List, ListItem, ListState};
 
 use crate::app::sidebar::SidebarRowKind;
 use crate::app::state::App;
+use crate::ui::pane::agent_state_color;
 
 pub fn render_sidebar(frame: &mut Frame, area: Rect, app: &App) {
     let rows = app.sidebar.rows(&app.panes);
@@ -16,20 +17,26 @@ pub fn render_sidebar(frame: &mut Frame, area: Rect, app: &App) {
     let items: Vec<ListItem> = rows
         .iter()
         .map(|row| {
-            let (prefix, label, focused) = match &row.kind {
+            let (prefix, label, focused, color) = match &row.kind {
                 SidebarRowKind::Group {
                     name,
                     count,
                     expanded,
                 } => {
                     let icon = if *expanded { "v" } else { ">" };
-                    (format!("{} ", icon), format!("{} ({})", name, count), false)
+                    (
+                        format!("{} ", icon),
+                        format!("{} ({})", name, count),
+                        false,
+                        None,
+                    )
                 }
                 SidebarRowKind::Pane { pane_id, group: _ } => {
                     let pane = app.panes.iter().find(|pane| &pane.id == pane_id);
                     let visible = pane.map(|p| p.visible).unwrap_or(false);
                     let lane = pane.and_then(|p| p.lane.as_ref());
                     let icon = if visible { "*" } else { "o" };
+                    let color = pane.map(|p| agent_state_color(p.agent_state));
 
                     // Show lane name for workers (which is repo name for single-worker repos)
                     // Fall back to pane_id for architect or if no lane
@@ -39,13 +46,18 @@ pub fn render_sidebar(frame: &mut Frame, area: Rect, app: &App) {
                         format!("{} ", icon),
                         label,
                         focused_id == Some(pane_id.as_str()),
+                        color,
                     )
                 }
             };
 
             let indent = " ".repeat(row.indent);
             let mut spans = Vec::new();
-            spans.push(Span::raw(format!("{}{}", indent, prefix)));
+            spans.push(Span::raw(indent));
+            match color {
+                Some(color) => spans.push(Span::styled(prefix, Style::default().fg(color))),
+                None => spans.push(Span::raw(prefix)),
+            }
             if focused {
                 spans.push(Span::styled(label, Style::default().fg(Color::Yellow)));
             } else {
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use ratatui::prelude::*;
 use ratatui::widgets::Paragraph;
 
@@ -5,6 +7,9 @@ use crate::app::backend_label;
 use crate::app::state::App;
 use crate::app::types::PaneType;
 
+/// How long the latest task event stays in the status bar
+const TASK_EVENT_DISPLAY: Duration = Duration::from_secs(10);
+
 pub fn render_status_bar(frame: &mut Frame, area: Rect, app: &App, workers_per_page: usize) {
     let mut parts = Vec::new();
 
@@ -23,9 +28,17 @@ pub fn render_status_bar(frame: &mut Frame, area: Rect, app: &App, workers_per_p
     }
 
     for (lane, counts) in &app.task_counts {
-        if counts.backlog > 0 {
+        if counts.blocked > 0 {
+            parts.push(format!(
+                "{}: {} backlog ({} blocked)",
+                lane, counts.backlog, counts.blocked
+            ));
+        } else if counts.backlog > 0 {
             parts.push(format!("{}: {} backlog", lane, counts.backlog));
         }
+        if counts.awaiting_answer > 0 {
+            parts.push(format!("{}: ? awaiting answer", lane));
+        }
     }
 
     let backend = backend_label(app.backend);
@@ -62,7 +75,90 @@ pub fn render_status_bar(frame: &mut Frame, area: Rect, app: &App, workers_per_p
     parts.push(format!("mode: {}", mode));
 
     let status = parts.join(" | ");
-    let paragraph = Paragraph::new(status).style(Style::default().bg(Color::DarkGray));
+    let mut spans = Vec::new();
+    if let Some(first) = app.task_problems.first() {
+        let more = match app.task_problems.len() {
+            1 => String::new(),
+            n => format!(" (+{} more, run `hive task lint`)", n - 1),
+        };
+        spans.push(Span::styled(
+            format!(" tasks.yaml {}{} ", first, more),
+            Style::default()
+                .fg(Color::White)
+                .bg(Color::Red)
+                .add_modifier(Modifier::BOLD),
+        ));
+        spans.push(Span::raw(" "));
+    }
+    if let Some(first) = app.stale_claims.first() {
+        let more = match app.stale_claims.len() {
+            1 => String::new(),
+            n => format!(" (+{} more)", n - 1),
+        };
+        spans.push(Span::styled(
+            format!(
+                " stale claim: {} by {}, {}{} ",
+                first.id, first.worker, first.reason, more
+            ),
+            Style::default().fg(Color::Black).bg(Color::Yellow),
+        ));
+        spans.push(Span::raw(" "));
+    }
+    if let Some(error) = &app.config_error {
+        spans.push(Span::styled(
+            format!(" config not reloaded: {} ", error),
+            Style::default()
+                .fg(Color::White)
+                .bg(Color::Red)
+                .add_modifier(Modifier::BOLD),
+        ));
+        spans.push(Span::raw(" "));
+    }
+    if let Some(first) = app.config_changes.first() {
+        let panes = if first.panes.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", first.panes.join(", "))
+        };
+        let more = match app.config_changes.len() {
+            1 => String::new(),
+            n => format!(" (+{} more in the palette)", n - 1),
+        };
+        spans.push(Span::styled(
+            format!(" restart to apply: {}{}{} ", first.change, panes, more),
+            Style::default().fg(Color::Black).bg(Color::Yellow),
+        ));
+        spans.push(Span::raw(" "));
+    }
+    if let Some((at, message)) = &app.last_error {
+        if at.elapsed() < TASK_EVENT_DISPLAY {
+            spans.push(Span::styled(
+                format!(" {} ", message),
+                Style::default().fg(Color::White).bg(Color::Red),
+            ));
+            spans.push(Span::raw(" "));
+        }
+    }
+    if let Some((at, message)) = &app.last_notice {
+        if at.elapsed() < TASK_EVENT_DISPLAY {
+            spans.push(Span::styled(
+                format!(" {} ", message),
+                Style::default().fg(Color::Black).bg(Color::Green),
+            ));
+            spans.push(Span::raw(" "));
+        }
+    }
+    if let Some((at, event)) = app.task_events.back() {
+        if at.elapsed() < TASK_EVENT_DISPLAY {
+            spans.push(Span::styled(
+                format!(" {} ", event),
+                Style::default().fg(Color::Black).bg(Color::Green),
+            ));
+            spans.push(Span::raw(" "));
+        }
+    }
+    spans.push(Span::raw(status));
+    let paragraph = Paragraph::new(Line::from(spans)).style(Style::default().bg(Color::DarkGray));
 
     frame.render_widget(paragraph, area);
 }
@@ -1,13 +1,95 @@
+use std::collections::{HashMap, HashSet};
+
 use ratatui::prelude::*;
-use ratatui::widgets::{Block, Borders, Clear, List, ListItem, Paragraph};
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap};
 
-use crate::app::state::App;
-use crate::tasks::{load_tasks, LaneTasks, ProjectEntry, Task, TasksFile};
+use crate::app::state::{App, TaskQueueEdit, TASK_FORM_FIELDS};
+use crate::tasks::{LaneTasks, ProjectEntry, Task, TaskState, TasksFile};
 
 /// Represents a lane with its tasks for display
 struct LaneDisplay {
     name: String,
     tasks: LaneTasks,
+    /// Backlog task id -> dependencies that aren't done yet
+    blocked: HashMap<String, Vec<String>>,
+}
+
+/// One line of the overlay: a lane header, a task, or a note under a task
+struct QueueLine {
+    line: Line<'static>,
+    lane: String,
+    header: bool,
+    /// The task this line shows or annotates, with its state
+    task: Option<(Task, TaskState)>,
+}
+
+/// Task queue filter typed after `/`. `l:label`, `s:state` and `w:worker`
+/// narrow by field; other words must all appear in the id, title or
+/// description. Repeating `s:` or `w:` allows any of the values.
+#[derive(Debug, Default, PartialEq)]
+pub struct TaskFilter {
+    labels: Vec<String>,
+    states: Vec<String>,
+    workers: Vec<String>,
+    words: Vec<String>,
+}
+
+impl TaskFilter {
+    pub fn parse(query: &str) -> Self {
+        let mut filter = Self::default();
+        for token in query.split_whitespace() {
+            let token = token.to_lowercase();
+            if let Some(label) = token.strip_prefix("l:") {
+                filter.labels.push(label.to_string());
+            } else if let Some(state) = token.strip_prefix("s:") {
+                filter.states.push(state.replace('-', "_"));
+            } else if let Some(worker) = token.strip_prefix("w:") {
+                filter.workers.push(worker.to_string());
+            } else {
+                filter.words.push(token);
+            }
+        }
+        filter
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn matches(&self, task: &Task, state: TaskState) -> bool {
+        let labels: Vec<String> = task
+            .labels
+            .iter()
+            .flatten()
+            .map(|l| l.to_lowercase())
+            .collect();
+        let worker_matches = |worker: &String| {
+            [&task.claimed_by, &task.assignee].iter().any(|field| {
+                field
+                    .as_deref()
+                    .is_some_and(|w| w.to_lowercase() == *worker)
+            })
+        };
+        let text = [
+            Some(&task.id),
+            task.title.as_ref(),
+            task.description.as_ref(),
+        ]
+        .into_iter()
+        .flatten()
+        .map(|field| field.to_lowercase())
+        .collect::<Vec<_>>()
+        .join("\n");
+
+        self.labels.iter().all(|label| labels.contains(label))
+            && (self.states.is_empty()
+                || self
+                    .states
+                    .iter()
+                    .any(|s| state.as_str().starts_with(s.as_str())))
+            && (self.workers.is_empty() || self.workers.iter().any(worker_matches))
+            && self.words.iter().all(|word| text.contains(word.as_str()))
+    }
 }
 
 pub fn render_task_queue(frame: &mut Frame, app: &App) {
@@ -22,181 +104,461 @@ pub fn render_task_queue(frame: &mut Frame, app: &App) {
 
     let inner = block.inner(area);
 
-    // Load tasks from file
-    let tasks_path = app.project_dir.join("tasks.yaml");
-    let tasks_file = match load_tasks(&tasks_path) {
-        Ok(t) => t,
-        Err(_) => {
-            let error_msg =
-                Paragraph::new("Failed to load tasks.yaml").style(Style::default().fg(Color::Red));
-            frame.render_widget(error_msg, inner);
-            return;
+    // Tasks come from the server's last snapshot of the task source
+    let Some(tasks_file) = &app.tasks else {
+        let waiting = Paragraph::new("Waiting for tasks from the server")
+            .style(Style::default().fg(Color::DarkGray));
+        frame.render_widget(waiting, inner);
+        return;
+    };
+
+    // Split inner area for filter bar, list and details, and help text
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1),
+            Constraint::Min(3),
+            Constraint::Length(2),
+        ])
+        .split(inner);
+
+    render_filter_bar(frame, app, chunks[0]);
+
+    let help = match &app.task_queue_edit {
+        Some(TaskQueueEdit::Add { .. }) => "[Tab/↑↓] Field  [Enter] Add task  [Esc] Cancel",
+        Some(TaskQueueEdit::MoveToLane { .. }) => "[↑↓/jk] Lane  [Enter] Move  [Esc] Cancel",
+        Some(TaskQueueEdit::Delete { .. }) => "[y] Delete  [any other key] Cancel",
+        None => {
+            "[q/Esc] Close  [↑↓/jk] Navigate  [Space] Toggle  [Enter] Jump to lane  [/] Filter  \
+             [a] Add  [←→/hl] State  [J/K] Priority  [m] Lane  [x] Delete"
         }
     };
+    let help = Paragraph::new(help)
+        .style(Style::default().fg(Color::DarkGray))
+        .wrap(Wrap { trim: true });
+    frame.render_widget(help, chunks[2]);
 
-    // Build list of lanes
-    let lanes = collect_lanes(&tasks_file);
+    let body = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+        .split(chunks[1]);
 
-    if lanes.is_empty() {
-        let empty_msg =
-            Paragraph::new("No tasks found").style(Style::default().fg(Color::DarkGray));
-        frame.render_widget(empty_msg, inner);
-        return;
+    let lines = queue_lines(app, tasks_file);
+    if lines.is_empty() {
+        let message = if app.task_queue_filter.trim().is_empty() {
+            "No tasks found"
+        } else {
+            "No tasks match the filter"
+        };
+        let empty_msg = Paragraph::new(message).style(Style::default().fg(Color::DarkGray));
+        frame.render_widget(empty_msg, body[0]);
+    } else {
+        let selected = app.task_queue_selection.min(lines.len() - 1);
+        let items: Vec<ListItem> = lines
+            .iter()
+            .enumerate()
+            .map(|(idx, queue_line)| {
+                let line = queue_line.line.clone();
+                if idx == selected {
+                    ListItem::new(line.patch_style(Style::default().fg(Color::Yellow)))
+                } else {
+                    ListItem::new(line)
+                }
+            })
+            .collect();
+        let mut state = ListState::default();
+        state.select(Some(selected));
+        frame.render_stateful_widget(List::new(items), body[0], &mut state);
+
+        if app.task_queue_edit.is_none() {
+            render_details(frame, &lines[selected], body[1]);
+        }
     }
 
-    // Build display lines
-    let mut items: Vec<ListItem> = Vec::new();
-    let mut line_idx = 0;
+    if let Some(edit) = &app.task_queue_edit {
+        render_edit(frame, edit, body[1]);
+    }
+}
+
+/// The add form, lane picker or delete prompt, in place of the details pane
+fn render_edit(frame: &mut Frame, edit: &TaskQueueEdit, area: Rect) {
+    let dim = Style::default().fg(Color::DarkGray);
+    let active = Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+    let (title, text) = match edit {
+        TaskQueueEdit::Add {
+            lane,
+            fields,
+            focus,
+        } => {
+            let mut text = Vec::new();
+            for (idx, (name, value)) in TASK_FORM_FIELDS.iter().zip(fields).enumerate() {
+                let (style, cursor) = if idx == *focus {
+                    (active, "█")
+                } else {
+                    (dim, "")
+                };
+                text.push(Line::styled(format!("{}:", name), style));
+                text.push(Line::from(format!("  {}{}", value, cursor)));
+            }
+            text.push(Line::default());
+            text.push(Line::styled(
+                "Priority: critical, high, medium, low or a number",
+                dim,
+            ));
+            (format!(" New task in {} ", lane), text)
+        }
+        TaskQueueEdit::MoveToLane {
+            id,
+            lanes,
+            selection,
+            ..
+        } => {
+            let text = lanes
+                .iter()
+                .enumerate()
+                .map(|(idx, lane)| {
+                    if idx == *selection {
+                        Line::styled(format!("> {}", lane), active)
+                    } else {
+                        Line::from(format!("  {}", lane))
+                    }
+                })
+                .collect();
+            (format!(" Move {} to ", id), text)
+        }
+        TaskQueueEdit::Delete { id, lane } => {
+            let text = vec![Line::styled(
+                format!("Delete {} from {}? [y/N]", id, lane),
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            )];
+            (" Delete ".to_string(), text)
+        }
+    };
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::LEFT)
+        .border_style(Style::default().fg(Color::Yellow));
+    frame.render_widget(Clear, area);
+    let paragraph = Paragraph::new(text).wrap(Wrap { trim: false }).block(block);
+    frame.render_widget(paragraph, area);
+}
+
+fn render_filter_bar(frame: &mut Frame, app: &App, area: Rect) {
+    let filter = &app.task_queue_filter;
+    let bar = if app.task_queue_filter_editing {
+        Paragraph::new(format!("/{}█", filter)).style(Style::default().fg(Color::Yellow))
+    } else if !filter.trim().is_empty() {
+        Paragraph::new(format!("Filter: {}  ([/] edit, [Esc] clear)", filter))
+            .style(Style::default().fg(Color::Cyan))
+    } else {
+        Paragraph::new("[/] Filter by text, l:label, s:state, w:worker")
+            .style(Style::default().fg(Color::DarkGray))
+    };
+    frame.render_widget(bar, area);
+}
+
+/// Everything about the selected task that doesn't fit on its line
+fn render_details(frame: &mut Frame, queue_line: &QueueLine, area: Rect) {
+    let block = Block::default()
+        .title(" Details ")
+        .borders(Borders::LEFT)
+        .border_style(Style::default().fg(Color::DarkGray));
+    let Some((task, state)) = &queue_line.task else {
+        let hint = Paragraph::new(format!(
+            "{}\n\nSelect a task to see its details",
+            queue_line.lane
+        ))
+        .style(Style::default().fg(Color::DarkGray))
+        .block(block);
+        frame.render_widget(hint, area);
+        return;
+    };
+
+    let heading = Style::default()
+        .fg(Color::Cyan)
+        .add_modifier(Modifier::BOLD);
+    let dim = Style::default().fg(Color::DarkGray);
+    let mut text = vec![
+        Line::styled(
+            task.title.clone().unwrap_or_else(|| task.id.clone()),
+            Style::default().add_modifier(Modifier::BOLD),
+        ),
+        Line::styled(
+            format!("{} · {} · {}", task.id, state, queue_line.lane),
+            dim,
+        ),
+    ];
+    let mut field = |name: &str, value: Option<String>| {
+        if let Some(value) = value {
+            text.push(Line::from(vec![
+                Span::styled(format!("{}: ", name), dim),
+                Span::raw(value),
+            ]));
+        }
+    };
+    field("Priority", task.priority.as_ref().map(|p| p.to_string()));
+    field("Labels", task.labels.as_ref().map(|l| l.join(", ")));
+    field("Assignee", task.assignee.clone());
+    field("Claimed by", task.claimed_by.clone());
+    field("Depends on", task.depends_on.as_ref().map(|d| d.join(", ")));
+
+    let mut section = |name: &str, lines: Vec<String>| {
+        if lines.is_empty() {
+            return;
+        }
+        text.push(Line::default());
+        text.push(Line::styled(name.to_string(), heading));
+        text.extend(lines.into_iter().map(Line::from));
+    };
+    let lines_of = |value: &Option<String>| {
+        value
+            .as_deref()
+            .map(|v| v.lines().map(String::from).collect())
+            .unwrap_or_default()
+    };
+    section("Description", lines_of(&task.description));
+    section(
+        "Acceptance",
+        task.acceptance
+            .iter()
+            .flatten()
+            .map(|item| format!("- {}", item))
+            .collect(),
+    );
+    section("Summary", lines_of(&task.summary));
+    section("PR", task.pr_url.iter().cloned().collect());
+    section("Branch", task.branch.iter().cloned().collect());
+
+    let details = Paragraph::new(text).wrap(Wrap { trim: false }).block(block);
+    frame.render_widget(details, area);
+}
+
+/// The overlay's lines, with the filter applied. Lanes without a matching
+/// task are left out while a filter is set.
+fn queue_lines(app: &App, tasks: &TasksFile) -> Vec<QueueLine> {
+    let filter = TaskFilter::parse(&app.task_queue_filter);
+    let mut lines = Vec::new();
+
+    for lane in collect_lanes(tasks) {
+        let backlog: Vec<&Task> = lane
+            .tasks
+            .backlog_by_priority()
+            .into_iter()
+            .filter(|task| filter.matches(task, TaskState::Backlog))
+            .collect();
+        let in_progress: Vec<&Task> = lane
+            .tasks
+            .in_progress
+            .iter()
+            .filter(|task| filter.matches(task, TaskState::InProgress))
+            .collect();
+        let done: Vec<&Task> = lane
+            .tasks
+            .done
+            .iter()
+            .filter(|task| filter.matches(task, TaskState::Done))
+            .collect();
+        if !filter.is_empty() && backlog.is_empty() && in_progress.is_empty() && done.is_empty() {
+            continue;
+        }
 
-    for lane in &lanes {
         let expanded = *app.task_queue_expanded.get(&lane.name).unwrap_or(&true);
-        let backlog_count = lane.tasks.backlog.len();
-        let in_progress_count = lane.tasks.in_progress.len();
-        let done_count = lane.tasks.done.len();
+        let mut blocked_note = if lane.blocked.is_empty() {
+            String::new()
+        } else {
+            format!(" [{} blocked]", lane.blocked.len())
+        };
+        let awaiting = lane.tasks.counts().awaiting_answer;
+        if awaiting > 0 {
+            blocked_note.push_str(&format!(" [{} awaiting answer]", awaiting));
+        }
 
         // Lane header
         let arrow = if expanded { "▼" } else { "▶" };
         let header = format!(
-            "{} {} ({} backlog, {} in progress, {} done)",
-            arrow, lane.name, backlog_count, in_progress_count, done_count
+            "{} {} ({} backlog, {} in progress, {} done){}",
+            arrow,
+            lane.name,
+            lane.tasks.backlog.len(),
+            lane.tasks.in_progress.len(),
+            lane.tasks.done.len(),
+            blocked_note
         );
-        let header_style = if line_idx == app.task_queue_selection {
-            Style::default()
-                .fg(Color::Yellow)
-                .add_modifier(Modifier::BOLD)
-        } else {
-            Style::default()
-                .fg(Color::Cyan)
-                .add_modifier(Modifier::BOLD)
+        lines.push(QueueLine {
+            line: Line::from(header).style(
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            lane: lane.name.clone(),
+            header: true,
+            task: None,
+        });
+
+        if !expanded {
+            continue;
+        }
+        let mut push = |line: Line<'static>, task: &Task, state: TaskState| {
+            lines.push(QueueLine {
+                line,
+                lane: lane.name.clone(),
+                header: false,
+                task: Some((task.clone(), state)),
+            });
         };
-        items.push(ListItem::new(Line::from(header).style(header_style)));
-        line_idx += 1;
-
-        if expanded {
-            // Backlog tasks
-            for task in &lane.tasks.backlog {
-                let line = format_task_line(task, "○", "backlog");
-                let style = if line_idx == app.task_queue_selection {
-                    Style::default().fg(Color::Yellow)
-                } else {
-                    Style::default().fg(Color::White)
-                };
-                items.push(ListItem::new(Line::from(line).style(style)));
-                line_idx += 1;
+        let note = |text: String, color: Color| {
+            Line::from(format!("     └─ {}", text)).style(Style::default().fg(color))
+        };
+
+        // Backlog tasks
+        for task in backlog {
+            let blockers = lane.blocked.get(&task.id);
+            let icon = if blockers.is_some() { "⊘" } else { "○" };
+            let color = if blockers.is_some() {
+                Color::DarkGray
+            } else {
+                Color::White
+            };
+            let line = Line::from(format_task_line(task, icon)).style(Style::default().fg(color));
+            push(line, task, TaskState::Backlog);
+
+            // Show what it is waiting on
+            if let Some(blockers) = blockers {
+                let line = note(format!("blocked by {}", blockers.join(", ")), Color::Red);
+                push(line, task, TaskState::Backlog);
             }
+        }
 
-            // In-progress tasks
-            for task in &lane.tasks.in_progress {
-                let line = format_task_line(task, "◐", "in_progress");
-                let style = if line_idx == app.task_queue_selection {
-                    Style::default().fg(Color::Yellow)
+        // In-progress tasks
+        for task in in_progress {
+            let awaiting = task.awaiting_answer();
+            let stale = app
+                .stale_claims
+                .iter()
+                .find(|claim| claim.id == task.id && claim.lane == lane.name);
+            let (icon, color) = if stale.is_some() {
+                ("!", Color::Red)
+            } else if awaiting {
+                ("?", Color::Magenta)
+            } else {
+                ("◐", Color::Blue)
+            };
+            let line = with_verification_badge(
+                format_task_line(task, icon),
+                task,
+                Style::default().fg(color),
+            );
+            push(line, task, TaskState::InProgress);
+
+            // Show claimed_by
+            if let Some(claimed_by) = &task.claimed_by {
+                let mut claimed = format!("claimed by {}", claimed_by);
+                if let Some(claim) = stale {
+                    claimed.push_str(&format!(" (stale: {})", claim.reason));
+                }
+                let color = if stale.is_some() {
+                    Color::Red
                 } else {
-                    Style::default().fg(Color::Blue)
+                    Color::DarkGray
                 };
-                items.push(ListItem::new(Line::from(line).style(style)));
-                line_idx += 1;
-
-                // Show claimed_by
-                if let Some(claimed_by) = &task.claimed_by {
-                    let claimed_line = format!("     └─ claimed by {}", claimed_by);
-                    let claimed_style = if line_idx == app.task_queue_selection {
-                        Style::default().fg(Color::Yellow)
-                    } else {
-                        Style::default().fg(Color::DarkGray)
-                    };
-                    items.push(ListItem::new(Line::from(claimed_line).style(claimed_style)));
-                    line_idx += 1;
-                }
+                push(note(claimed, color), task, TaskState::InProgress);
             }
 
-            // Done tasks
-            for task in &lane.tasks.done {
-                let line = format_task_line(task, "✓", "done");
-                let style = if line_idx == app.task_queue_selection {
-                    Style::default().fg(Color::Yellow)
-                } else {
-                    Style::default().fg(Color::Green)
-                };
-                items.push(ListItem::new(Line::from(line).style(style)));
-                line_idx += 1;
+            // Show the open question
+            if awaiting {
+                let question = format!(
+                    "awaiting answer: {}",
+                    task.question.as_deref().unwrap_or_default()
+                );
+                push(note(question, Color::Magenta), task, TaskState::InProgress);
+            }
+        }
 
-                // Show metadata for done tasks
-                let mut meta_parts = Vec::new();
-                if let Some(pr_url) = &task.pr_url {
-                    meta_parts.push(format!("PR: {}", pr_url));
-                }
-                if let Some(branch) = &task.branch {
-                    meta_parts.push(format!("Branch: {}", branch));
-                }
-                if !meta_parts.is_empty() {
-                    let meta_line = format!("     └─ {}", meta_parts.join("  "));
-                    let meta_style = if line_idx == app.task_queue_selection {
-                        Style::default().fg(Color::Yellow)
-                    } else {
-                        Style::default().fg(Color::DarkGray)
-                    };
-                    items.push(ListItem::new(Line::from(meta_line).style(meta_style)));
-                    line_idx += 1;
-                }
-                if let Some(summary) = &task.summary {
-                    let summary_line = format!("     └─ {}", summary);
-                    let summary_style = if line_idx == app.task_queue_selection {
-                        Style::default().fg(Color::Yellow)
-                    } else {
-                        Style::default().fg(Color::DarkGray)
-                    };
-                    items.push(ListItem::new(Line::from(summary_line).style(summary_style)));
-                    line_idx += 1;
-                }
+        // Done tasks
+        for task in done {
+            let line = with_verification_badge(
+                format_task_line(task, "✓"),
+                task,
+                Style::default().fg(Color::Green),
+            );
+            push(line, task, TaskState::Done);
+
+            // Show metadata for done tasks
+            let mut meta_parts = Vec::new();
+            if let Some(pr_url) = &task.pr_url {
+                meta_parts.push(format!("PR: {}", pr_url));
+            }
+            if let Some(branch) = &task.branch {
+                meta_parts.push(format!("Branch: {}", branch));
+            }
+            if !meta_parts.is_empty() {
+                let meta = note(meta_parts.join("  "), Color::DarkGray);
+                push(meta, task, TaskState::Done);
+            }
+            if let Some(summary) = &task.summary {
+                push(
+                    note(summary.clone(), Color::DarkGray),
+                    task,
+                    TaskState::Done,
+                );
             }
         }
     }
 
-    // Split inner area for list and help text
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([Constraint::Min(3), Constraint::Length(1)])
-        .split(inner);
-
-    let list = List::new(items);
-    frame.render_widget(list, chunks[0]);
-
-    // Help text at bottom
-    let help =
-        Paragraph::new("[q/Esc] Close  [↑↓/jk] Navigate  [Space] Toggle  [Enter] Jump to lane")
-            .style(Style::default().fg(Color::DarkGray));
-    frame.render_widget(help, chunks[1]);
+    lines
 }
 
-fn format_task_line(task: &Task, icon: &str, _status: &str) -> String {
+/// Title plus labels, and the assignee until the task is claimed
+fn format_task_line(task: &Task, icon: &str) -> String {
     let title = task.title.as_deref().unwrap_or(&task.id);
-    format!("   {} {}", icon, title)
+    let mut line = format!("   {} {}", icon, title);
+    if let Some(labels) = task.labels.as_ref().filter(|l| !l.is_empty()) {
+        line.push_str(&format!(" [{}]", labels.join(", ")));
+    }
+    if let (None, Some(assignee)) = (&task.claimed_by, &task.assignee) {
+        line.push_str(&format!(" → {}", assignee));
+    }
+    line
+}
+
+/// A task line followed by a pass/fail badge once its `verify` commands have run
+fn with_verification_badge(line: String, task: &Task, style: Style) -> Line<'static> {
+    let mut spans = vec![Span::raw(line)];
+    match &task.verification {
+        Some(verification) if verification.passed => spans.push(Span::styled(
+            " [verify: pass]",
+            Style::default().fg(Color::Green),
+        )),
+        Some(_) => spans.push(Span::styled(
+            " [verify: fail]",
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        )),
+        None => {}
+    }
+    Line::from(spans).style(style)
 }
 
 fn collect_lanes(tasks: &TasksFile) -> Vec<LaneDisplay> {
     let mut lanes = Vec::new();
+    let done_ids = tasks.done_ids();
 
     for (project_name, entry) in &tasks.projects {
         match entry {
             ProjectEntry::Direct(lane_tasks) => {
                 lanes.push(LaneDisplay {
                     name: project_name.clone(),
-                    tasks: LaneTasks {
-                        backlog: lane_tasks.backlog.clone(),
-                        in_progress: lane_tasks.in_progress.clone(),
-                        done: lane_tasks.done.clone(),
-                    },
+                    tasks: lane_tasks.clone(),
+                    blocked: blocked_tasks(lane_tasks, &done_ids),
                 });
             }
             ProjectEntry::Nested(nested_lanes) => {
                 for (lane_name, lane_tasks) in nested_lanes {
                     lanes.push(LaneDisplay {
                         name: format!("{}/{}", project_name, lane_name),
-                        tasks: LaneTasks {
-                            backlog: lane_tasks.backlog.clone(),
-                            in_progress: lane_tasks.in_progress.clone(),
-                            done: lane_tasks.done.clone(),
-                        },
+                        tasks: lane_tasks.clone(),
+                        blocked: blocked_tasks(lane_tasks, &done_ids),
                     });
                 }
             }
@@ -208,84 +570,72 @@ fn collect_lanes(tasks: &TasksFile) -> Vec<LaneDisplay> {
     lanes
 }
 
+fn blocked_tasks(lane_tasks: &LaneTasks, done_ids: &HashSet<&str>) -> HashMap<String, Vec<String>> {
+    lane_tasks
+        .backlog
+        .iter()
+        .filter_map(|task| {
+            let blockers = task.blockers(done_ids);
+            (!blockers.is_empty()).then(|| {
+                (
+                    task.id.clone(),
+                    blockers.into_iter().map(String::from).collect(),
+                )
+            })
+        })
+        .collect()
+}
+
 /// Count total displayable lines for navigation bounds
 pub fn count_lines(app: &App) -> usize {
-    let tasks_path = app.project_dir.join("tasks.yaml");
-    let tasks_file = match load_tasks(&tasks_path) {
-        Ok(t) => t,
-        Err(_) => return 0,
-    };
-
-    let lanes = collect_lanes(&tasks_file);
-    let mut count = 0;
-
-    for lane in &lanes {
-        count += 1; // Lane header
-        let expanded = *app.task_queue_expanded.get(&lane.name).unwrap_or(&true);
-        if expanded {
-            count += lane.tasks.backlog.len();
-            // In-progress: task + optional claimed_by line
-            for task in &lane.tasks.in_progress {
-                count += 1;
-                if task.claimed_by.is_some() {
-                    count += 1;
-                }
-            }
-            // Done: task + optional metadata lines
-            for task in &lane.tasks.done {
-                count += 1;
-                if task.pr_url.is_some() || task.branch.is_some() {
-                    count += 1;
-                }
-                if task.summary.is_some() {
-                    count += 1;
-                }
-            }
-        }
-    }
-
-    count
+    selection_lines(app).len()
 }
 
 /// Get lane name at the current selection (if it's a lane header)
 pub fn get_selected_lane(app: &App) -> Option<String> {
-    let tasks_path = app.project_dir.join("tasks.yaml");
-    let tasks_file = match load_tasks(&tasks_path) {
-        Ok(t) => t,
-        Err(_) => return None,
-    };
+    selection_lines(app)
+        .into_iter()
+        .nth(app.task_queue_selection)
+        .filter(|line| line.header)
+        .map(|line| line.lane)
+}
 
-    let lanes = collect_lanes(&tasks_file);
-    let mut line_idx = 0;
+/// Lane of the selected line, whether it's a header or a task
+pub fn get_selection_lane(app: &App) -> Option<String> {
+    selection_lines(app)
+        .into_iter()
+        .nth(app.task_queue_selection)
+        .map(|line| line.lane)
+}
 
-    for lane in &lanes {
-        if line_idx == app.task_queue_selection {
-            return Some(lane.name.clone());
-        }
-        line_idx += 1;
+/// Lane, task and state under the selection, if it's on a task or its notes
+pub fn get_selected_task(app: &App) -> Option<(String, Task, TaskState)> {
+    let line = selection_lines(app)
+        .into_iter()
+        .nth(app.task_queue_selection)?;
+    let (task, state) = line.task?;
+    Some((line.lane, task, state))
+}
 
-        let expanded = *app.task_queue_expanded.get(&lane.name).unwrap_or(&true);
-        if expanded {
-            line_idx += lane.tasks.backlog.len();
-            for task in &lane.tasks.in_progress {
-                line_idx += 1;
-                if task.claimed_by.is_some() {
-                    line_idx += 1;
-                }
-            }
-            for task in &lane.tasks.done {
-                line_idx += 1;
-                if task.pr_url.is_some() || task.branch.is_some() {
-                    line_idx += 1;
-                }
-                if task.summary.is_some() {
-                    line_idx += 1;
-                }
-            }
-        }
-    }
+/// Every lane in the tasks file plus the lanes panes work in, sorted
+pub fn known_lanes(app: &App) -> Vec<String> {
+    let mut lanes: Vec<String> = app
+        .tasks
+        .iter()
+        .flat_map(|tasks| tasks.lanes().into_iter().map(|(lane, _)| lane))
+        .collect();
+    lanes.extend(app.panes.iter().filter_map(|pane| pane.lane.clone()));
+    lanes.sort();
+    lanes.dedup();
+    lanes
+}
 
-    None
+/// The overlay's lines for the current snapshot, empty before the first one
+fn selection_lines(app: &App) -> Vec<QueueLine> {
+    app.tasks
+        .as_ref()
+        .map(|tasks| queue_lines(app, tasks))
+        .unwrap_or_default()
 }
 
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
@@ -307,3 +657,33 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
         ])
         .split(popup_layout[1])[1]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_task_filter_matches_fields_and_text() {
+        let task: Task = serde_yaml::from_str(
+            r#"
+id: fix-login
+title: Fix login redirect
+labels: [Bug, auth]
+assignee: worker-2
+"#,
+        )
+        .unwrap();
+        let matches = |query: &str, state| TaskFilter::parse(query).matches(&task, state);
+
+        assert!(TaskFilter::parse("  ").is_empty());
+        assert!(matches("", TaskState::Backlog));
+        assert!(matches("l:bug login", TaskState::Backlog));
+        assert!(!matches("l:bug l:ui", TaskState::Backlog));
+        assert!(matches("s:in-progress", TaskState::InProgress));
+        assert!(matches("s:done s:backlog", TaskState::Backlog));
+        assert!(!matches("s:done", TaskState::Backlog));
+        assert!(matches("w:worker-2", TaskState::Backlog));
+        assert!(!matches("w:worker-1", TaskState::Backlog));
+        assert!(!matches("logout", TaskState::Backlog));
+    }
+}
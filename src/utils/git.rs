@@ -27,6 +27,28 @@ pub fn git_common_dir(repo_dir: &Path) -> Result<PathBuf> {
     Ok(path)
 }
 
+/// Paths of the files git tracks in `repo_dir`, relative to it
+pub fn tracked_files(repo_dir: &Path) -> Result<Vec<PathBuf>> {
+    let output = std::process::Command::new("git")
+        .args(["ls-files", "-z"])
+        .current_dir(repo_dir)
+        .output()
+        .context("Failed to run git ls-files")?;
+
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "git ls-files failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .split('\0')
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+        .collect())
+}
+
 pub fn ensure_git_exclude(repo_dir: &Path) -> Result<()> {
     let git_dir = git_common_dir(repo_dir)?;
     let exclude_path = git_dir.join("info").join("exclude");
@@ -1,12 +1,16 @@
 use std::path::Path;
+use std::process::{Command, Output, Stdio};
 
 use anyhow::{Context, Result};
 
+fn shell_command(command: &str, cwd: &Path) -> Command {
+    let mut cmd = Command::new("sh");
+    cmd.arg("-lc").arg(command).current_dir(cwd);
+    cmd
+}
+
 pub fn run_shell_command(command: &str, cwd: &Path) -> Result<()> {
-    let status = std::process::Command::new("sh")
-        .arg("-lc")
-        .arg(command)
-        .current_dir(cwd)
+    let status = shell_command(command, cwd)
         .status()
         .with_context(|| format!("Failed running setup command: {}", command))?;
 
@@ -17,6 +21,15 @@ pub fn run_shell_command(command: &str, cwd: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Run a command the same way as `run_shell_command`, capturing its output
+/// instead of passing it through. A non-zero exit is not an error here.
+pub fn run_shell_command_output(command: &str, cwd: &Path) -> Result<Output> {
+    shell_command(command, cwd)
+        .stdin(Stdio::null())
+        .output()
+        .with_context(|| format!("Failed running command: {}", command))
+}
+
 pub fn command_available(command: &str) -> bool {
     let Some(paths) = std::env::var_os("PATH") else {
         return false;
@@ -1,9 +1,14 @@
 use std::path::{Path, PathBuf};
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 
-use crate::config::{ArchitectConfig, Backend, WorkersConfig, WorkflowConfig};
+use crate::config::{
+    ArchitectConfig, Backend, MessagesConfig, TaskSource, TasksConfig, WorkersConfig,
+    WorkflowConfig,
+};
+use crate::tasks::{parse_duration, Schedule, TaskTemplate};
 
 /// Layout configuration for pane sizing
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -33,6 +38,132 @@ impl Default for LayoutConfig {
     }
 }
 
+/// Timing for the tasks.yaml watcher
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchConfig {
+    /// Minimum seconds between two rounds of nudges (default: 10)
+    #[serde(default = "default_debounce_secs")]
+    pub debounce_secs: u64,
+    /// Seconds to wait after a change before reading the file, so a burst of
+    /// writes is read once (default: 5)
+    #[serde(default = "default_settle_secs")]
+    pub settle_secs: u64,
+}
+
+fn default_debounce_secs() -> u64 {
+    10
+}
+
+fn default_settle_secs() -> u64 {
+    5
+}
+
+impl Default for WatchConfig {
+    fn default() -> Self {
+        Self {
+            debounce_secs: default_debounce_secs(),
+            settle_secs: default_settle_secs(),
+        }
+    }
+}
+
+/// Automatic archiving of done tasks
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ArchiveConfig {
+    /// Archive done tasks completed longer ago than this (e.g. "7d"); unset disables it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub older_than: Option<String>,
+}
+
+/// When a claimed task counts as stale, and whether to requeue it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StaleClaimsConfig {
+    /// Flag a claim once its worker has printed nothing for this long (default: "30m").
+    /// Claims whose worker pane exited are flagged right away.
+    #[serde(default = "default_stale_after")]
+    pub after: String,
+    /// Move stale claims back to backlog once inactive this long (e.g. "2h");
+    /// unset only flags them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requeue_after: Option<String>,
+}
+
+fn default_stale_after() -> String {
+    "30m".to_string()
+}
+
+impl Default for StaleClaimsConfig {
+    fn default() -> Self {
+        Self {
+            after: default_stale_after(),
+            requeue_after: None,
+        }
+    }
+}
+
+/// When the server restarts an agent pane whose process exited
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RestartMode {
+    #[default]
+    Never,
+    /// Only when the process exited with a non-zero status
+    OnFailure,
+    Always,
+}
+
+/// Automatic restarts of exited agent panes, for unattended runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestartPolicyConfig {
+    #[serde(default)]
+    pub mode: RestartMode,
+    /// Wait before the first restart, doubled after each one (default: "30s")
+    #[serde(default = "default_restart_backoff")]
+    pub backoff: String,
+    /// Longest wait between restarts (default: "10m"). A pane that stayed up
+    /// this long starts again from `backoff`.
+    #[serde(default = "default_restart_max_backoff")]
+    pub max_backoff: String,
+    /// Stop restarting a pane after this many restarts in a row; unset never stops
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_restarts: Option<u32>,
+}
+
+fn default_restart_backoff() -> String {
+    "30s".to_string()
+}
+
+fn default_restart_max_backoff() -> String {
+    "10m".to_string()
+}
+
+impl Default for RestartPolicyConfig {
+    fn default() -> Self {
+        Self {
+            mode: RestartMode::default(),
+            backoff: default_restart_backoff(),
+            max_backoff: default_restart_max_backoff(),
+            max_restarts: None,
+        }
+    }
+}
+
+/// A task the server adds to a lane's backlog on a schedule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecurringTask {
+    /// Prefix of each added task's id, which ends in the local date and time
+    pub name: String,
+    /// Name of the entry in `task_templates` to expand
+    pub template: String,
+    pub lane: String,
+    /// Cron schedule in local time: "minute hour day month weekday", or
+    /// @hourly, @daily, @weekly, @monthly
+    pub schedule: String,
+    /// Values for the template's placeholders
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub vars: IndexMap<String, String>,
+}
+
 /// Configuration for a workspace stored in ~/.hive/workspaces/{name}/workspace.yaml
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkspaceConfig {
@@ -49,6 +180,35 @@ pub struct WorkspaceConfig {
     /// Workflow configuration for workers
     #[serde(default)]
     pub workflow: WorkflowConfig,
+    /// Templates for the messages typed into agent panes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub messages: Option<MessagesConfig>,
+    /// Tasks file watcher timing
+    #[serde(default)]
+    pub watch: WatchConfig,
+    /// Automatic archiving of done tasks
+    #[serde(default)]
+    pub archive: ArchiveConfig,
+    /// Detection and requeueing of abandoned claims
+    #[serde(default)]
+    pub stale_claims: StaleClaimsConfig,
+    /// Named task shapes for `hive task add --template`
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub task_templates: IndexMap<String, TaskTemplate>,
+    /// Tasks added to lanes on a schedule
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub recurring: Vec<RecurringTask>,
+    /// Automatic restarts of exited agent panes
+    #[serde(default)]
+    pub restart_policy: RestartPolicyConfig,
+    /// Worker id of each lane, by its name in the tasks file. Lanes without
+    /// one get the lowest free `worker-<n>`. Filled in when workers are added
+    /// or removed at runtime so the others keep their ids across restarts.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub worker_ids: IndexMap<String, String>,
+    /// Where tasks come from; unset keeps them in the workspace's tasks.yaml
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tasks: Option<TasksConfig>,
 }
 
 /// A project within a workspace
@@ -67,6 +227,19 @@ fn default_workers() -> usize {
     1
 }
 
+impl WorkspaceProject {
+    /// The name one of the project's lanes goes by in the tasks file:
+    /// - Multi-lane projects: "project/lane" (e.g., "backend/fixes")
+    /// - Single-lane projects: lane as-is (e.g., "android-sdk")
+    pub fn lane_name(&self, lane: &str) -> String {
+        if self.lanes.len() > 1 {
+            format!("{}/{}", slug_from_path(&self.path), lane)
+        } else {
+            lane.to_string()
+        }
+    }
+}
+
 impl WorkspaceConfig {
     /// Load workspace config from a workspace directory
     pub fn load(workspace_dir: &Path) -> Result<Self> {
@@ -87,6 +260,82 @@ impl WorkspaceConfig {
         Ok(())
     }
 
+    /// The tasks file of a file-backed source: `tasks.file` relative to the
+    /// workspace directory, else tasks.yaml (TODO.md for markdown)
+    pub fn tasks_file(&self, workspace_dir: &Path) -> PathBuf {
+        let file = match &self.tasks {
+            Some(tasks) => match (&tasks.file, &tasks.source) {
+                (Some(file), _) => file.as_str(),
+                (None, TaskSource::Markdown) => "TODO.md",
+                (None, _) => "tasks.yaml",
+            },
+            None => "tasks.yaml",
+        };
+        workspace_dir.join(file)
+    }
+
+    /// Check what serde can't: lane names, durations and recurring entries
+    pub fn validate(&self) -> Result<()> {
+        for project in &self.projects {
+            let mut seen = std::collections::HashSet::new();
+            for lane in &project.lanes {
+                if lane.is_empty() || lane.contains('/') || lane.contains(char::is_whitespace) {
+                    bail!("lane '{}' should be a single word without '/'", lane);
+                }
+                if !seen.insert(lane) {
+                    bail!(
+                        "lane '{}' appears twice in {}",
+                        lane,
+                        project.path.display()
+                    );
+                }
+            }
+        }
+
+        let mut ids = std::collections::HashSet::new();
+        for (lane, id) in &self.worker_ids {
+            if id == "architect" || !ids.insert(id) {
+                bail!(
+                    "worker_ids gives lane '{}' the id '{}', which is taken",
+                    lane,
+                    id
+                );
+            }
+        }
+
+        let durations = [
+            ("stale_claims.after", Some(&self.stale_claims.after)),
+            (
+                "stale_claims.requeue_after",
+                self.stale_claims.requeue_after.as_ref(),
+            ),
+            ("archive.older_than", self.archive.older_than.as_ref()),
+            ("restart_policy.backoff", Some(&self.restart_policy.backoff)),
+            (
+                "restart_policy.max_backoff",
+                Some(&self.restart_policy.max_backoff),
+            ),
+        ];
+        for (name, value) in durations {
+            if let Some(value) = value {
+                parse_duration(value).with_context(|| format!("{} is invalid", name))?;
+            }
+        }
+
+        for entry in &self.recurring {
+            Schedule::parse(&entry.schedule)
+                .with_context(|| format!("recurring {} has a bad schedule", entry.name))?;
+            if !self.task_templates.contains_key(&entry.template) {
+                bail!(
+                    "recurring {} uses unknown task template '{}'",
+                    entry.name,
+                    entry.template
+                );
+            }
+        }
+        Ok(())
+    }
+
     /// Get all unique lanes across all projects
     pub fn all_lanes(&self) -> Vec<String> {
         let mut lanes: Vec<String> = self
@@ -103,6 +352,139 @@ impl WorkspaceConfig {
     pub fn total_workers(&self) -> usize {
         self.projects.iter().map(|p| p.workers).sum()
     }
+
+    /// Each lane's name in the tasks file with the id of the worker on it, in
+    /// config order
+    pub fn lane_worker_ids(&self) -> Vec<(String, String)> {
+        let lanes: Vec<String> = self
+            .projects
+            .iter()
+            .flat_map(|p| p.lanes.iter().map(|lane| p.lane_name(lane)))
+            .collect();
+        let pinned: std::collections::HashSet<&String> = lanes
+            .iter()
+            .filter_map(|lane| self.worker_ids.get(lane))
+            .collect();
+        let mut next = 1;
+        lanes
+            .iter()
+            .map(|lane| {
+                let id = match self.worker_ids.get(lane) {
+                    Some(id) => id.clone(),
+                    None => loop {
+                        let id = format!("worker-{}", next);
+                        next += 1;
+                        if !pinned.contains(&id) {
+                            break id;
+                        }
+                    },
+                };
+                (lane.clone(), id)
+            })
+            .collect()
+    }
+
+    /// Save every lane's current worker id in `worker_ids`, dropping lanes
+    /// that are gone
+    fn pin_worker_ids(&mut self) {
+        self.worker_ids = self.lane_worker_ids().into_iter().collect();
+    }
+
+    /// Give the project named `project` (its path or slug) another lane,
+    /// worked by a new worker. Returns the project's index.
+    pub fn add_lane(&mut self, project: &str, lane: &str) -> Result<usize> {
+        let index = self
+            .projects
+            .iter()
+            .position(|p| p.path == Path::new(project) || slug_from_path(&p.path) == project)
+            .with_context(|| {
+                let names: Vec<String> = self
+                    .projects
+                    .iter()
+                    .map(|p| slug_from_path(&p.path))
+                    .collect();
+                format!(
+                    "No project named '{}'. Projects: {}",
+                    project,
+                    names.join(", ")
+                )
+            })?;
+        let target = &self.projects[index];
+        if lane.is_empty() || lane.contains('/') || lane.contains(char::is_whitespace) {
+            bail!("Lane '{}' should be a single word without '/'", lane);
+        }
+        if target.lanes.iter().any(|l| l == lane) {
+            bail!("Project '{}' already has a lane '{}'", project, lane);
+        }
+        // Lanes of multi-lane projects are prefixed with the project, so a
+        // second lane would move the first one's tasks to a new lane name
+        if let [only] = target.lanes.as_slice() {
+            bail!(
+                "Project '{}' has a single lane, '{}'; a second lane would rename it to '{}/{}'. \
+                 Add the lane to workspace.yaml and restart the hive instead",
+                project,
+                only,
+                slug_from_path(&target.path),
+                only
+            );
+        }
+        // The new worker gets an id past every other so the rest keep theirs
+        self.pin_worker_ids();
+        let highest = self
+            .worker_ids
+            .values()
+            .filter_map(|id| id.strip_prefix("worker-")?.parse::<usize>().ok())
+            .max()
+            .unwrap_or(0);
+        let target = &mut self.projects[index];
+        target.lanes.push(lane.to_string());
+        target.workers = target.lanes.len();
+        let full_lane = target.lane_name(lane);
+        self.worker_ids
+            .insert(full_lane, format!("worker-{}", highest + 1));
+        Ok(index)
+    }
+
+    /// Remove the lane that a worker works as `full_lane` (the lane name used
+    /// in the tasks file). Returns the project's index and the lane's name
+    /// within the project.
+    pub fn remove_lane(&mut self, full_lane: &str) -> Result<(usize, String)> {
+        let (index, position) = self
+            .projects
+            .iter()
+            .enumerate()
+            .find_map(|(index, project)| {
+                project
+                    .lanes
+                    .iter()
+                    .position(|lane| project.lane_name(lane) == full_lane)
+                    .map(|position| (index, position))
+            })
+            .with_context(|| format!("No lane '{}' in the workspace", full_lane))?;
+        let project = &mut self.projects[index];
+        if project.lanes.len() > 1 && position == 0 {
+            bail!(
+                "Lane '{}' works in the original repo at {}; only lanes with their own worktree can be removed",
+                full_lane,
+                project.path.display()
+            );
+        }
+        if project.lanes.len() == 2 {
+            bail!(
+                "Removing '{}' would leave '{}' as the project's only lane and rename it to '{}'. \
+                 Remove the lane from workspace.yaml and restart the hive instead",
+                full_lane,
+                project.lane_name(&project.lanes[0]),
+                project.lanes[0]
+            );
+        }
+        self.pin_worker_ids();
+        let project = &mut self.projects[index];
+        let lane = project.lanes.remove(position);
+        project.workers = project.lanes.len();
+        self.worker_ids.shift_remove(full_lane);
+        Ok((index, lane))
+    }
 }
 
 impl Default for WorkspaceConfig {
@@ -122,6 +504,15 @@ impl Default for WorkspaceConfig {
             },
             layout: LayoutConfig::default(),
             workflow: WorkflowConfig::default(),
+            messages: None,
+            watch: WatchConfig::default(),
+            archive: ArchiveConfig::default(),
+            stale_claims: StaleClaimsConfig::default(),
+            task_templates: IndexMap::new(),
+            recurring: Vec::new(),
+            restart_policy: RestartPolicyConfig::default(),
+            worker_ids: IndexMap::new(),
+            tasks: None,
         }
     }
 }
@@ -150,7 +541,7 @@ pub struct RuntimeWorker {
 /// Expand a WorkspaceConfig into runtime workers with resolved directories
 pub fn expand_workers(config: &WorkspaceConfig, workspace_dir: &Path) -> Vec<RuntimeWorker> {
     let mut workers = Vec::new();
-    let mut worker_idx = 0;
+    let mut worker_ids = config.lane_worker_ids().into_iter();
 
     for project in &config.projects {
         let project_slug = slug_from_path(&project.path);
@@ -173,25 +564,15 @@ pub fn expand_workers(config: &WorkspaceConfig, workspace_dir: &Path) -> Vec<Run
                 remote: lane.clone(),
             });
 
-            // Lane format:
-            // - Multi-lane projects: "project/lane" (e.g., "backend/fixes")
-            // - Single-lane projects: lane as-is (e.g., "android-sdk")
-            let full_lane = if project.lanes.len() > 1 {
-                format!("{}/{}", project_slug, lane)
-            } else {
-                lane.clone()
-            };
-
+            let (_, id) = worker_ids.next().unwrap_or_default();
             workers.push(RuntimeWorker {
-                id: format!("worker-{}", worker_idx + 1),
+                id,
                 working_dir,
-                lane: full_lane,
+                lane: project.lane_name(lane),
                 project_path: project.path.clone(),
                 is_worktree,
                 branch,
             });
-
-            worker_idx += 1;
         }
     }
 
@@ -269,4 +650,92 @@ mod tests {
         assert!(workers[1].is_worktree);
         assert!(workers[2].is_worktree);
     }
+
+    #[test]
+    fn test_add_and_remove_lane_keep_lane_names() {
+        let mut config = WorkspaceConfig {
+            name: "test".to_string(),
+            projects: vec![
+                WorkspaceProject {
+                    path: PathBuf::from("/code/api"),
+                    workers: 2,
+                    lanes: vec!["core".to_string(), "auth".to_string()],
+                },
+                WorkspaceProject {
+                    path: PathBuf::from("/code/web"),
+                    workers: 1,
+                    lanes: vec!["ui".to_string()],
+                },
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(config.add_lane("api", "billing").unwrap(), 0);
+        assert_eq!(config.projects[0].workers, 3);
+        let workers = expand_workers(&config, Path::new("/ws"));
+        assert_eq!(workers[2].lane, "api/billing");
+        assert!(workers[2].is_worktree);
+
+        assert!(config.add_lane("api", "auth").is_err());
+        assert!(config.add_lane("/code/web", "forms").is_err());
+        assert!(config.add_lane("mobile", "ios").is_err());
+
+        assert!(config.remove_lane("api/core").is_err());
+        assert_eq!(
+            config.remove_lane("api/auth").unwrap(),
+            (0, "auth".to_string())
+        );
+        assert!(config.remove_lane("api/billing").is_err());
+        assert_eq!(config.remove_lane("ui").unwrap(), (1, "ui".to_string()));
+    }
+
+    #[test]
+    fn test_worker_ids_survive_adding_and_removing_lanes() {
+        let mut config = WorkspaceConfig {
+            name: "test".to_string(),
+            projects: vec![
+                WorkspaceProject {
+                    path: PathBuf::from("/code/api"),
+                    workers: 3,
+                    lanes: vec!["core".to_string(), "auth".to_string(), "jobs".to_string()],
+                },
+                WorkspaceProject {
+                    path: PathBuf::from("/code/web"),
+                    workers: 1,
+                    lanes: vec!["ui".to_string()],
+                },
+            ],
+            ..Default::default()
+        };
+        let ids = |config: &WorkspaceConfig| -> Vec<(String, String)> {
+            expand_workers(config, Path::new("/ws"))
+                .into_iter()
+                .map(|worker| (worker.lane, worker.id))
+                .collect()
+        };
+        let pair = |lane: &str, id: &str| (lane.to_string(), id.to_string());
+        assert_eq!(config.lane_worker_ids(), ids(&config));
+        assert_eq!(ids(&config)[3], pair("ui", "worker-4"));
+
+        config.remove_lane("api/auth").unwrap();
+        config.add_lane("api", "billing").unwrap();
+        // As the server would see it after a restart
+        let reloaded: WorkspaceConfig =
+            serde_yaml::from_str(&serde_yaml::to_string(&config).unwrap()).unwrap();
+        assert_eq!(
+            ids(&reloaded),
+            vec![
+                pair("api/core", "worker-1"),
+                pair("api/jobs", "worker-3"),
+                pair("api/billing", "worker-5"),
+                pair("ui", "worker-4"),
+            ]
+        );
+
+        // A lane added to the file by hand takes the lowest free id
+        let mut edited = reloaded.clone();
+        edited.projects[0].lanes.push("search".to_string());
+        assert_eq!(ids(&edited)[3], pair("api/search", "worker-2"));
+        assert_eq!(ids(&edited)[4], pair("ui", "worker-4"));
+    }
 }
@@ -3,11 +3,13 @@ pub mod resolve;
 pub mod worktree;
 
 pub use config::{
-    expand_workers, slug_from_path, RuntimeWorker, WorkerBranch, WorkspaceConfig, WorkspaceProject,
+    expand_workers, slug_from_path, RecurringTask, RestartMode, RestartPolicyConfig, RuntimeWorker,
+    WorkerBranch, WorkspaceConfig, WorkspaceProject,
 };
 pub use resolve::{
     find_workspace_for_path, list_workspaces, workspace_dir, workspaces_dir, WorkspaceMeta,
 };
 pub use worktree::{
-    create_worktrees, create_worktrees_with_symlinks, remove_worktrees, worker_directory,
+    create_worktrees, create_worktrees_with_symlinks, remove_worktree, remove_worktrees,
+    worker_directory,
 };
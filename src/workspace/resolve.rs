@@ -85,7 +85,16 @@ pub fn find_workspace_for_path(path: &Path) -> Result<Option<WorkspaceMeta>> {
     let workspaces = list_workspaces()?;
 
     for workspace in workspaces {
-        // First check if path matches or is inside the workspace root
+        // Worktrees live inside the workspace directory itself
+        let dir_canonical = workspace
+            .dir
+            .canonicalize()
+            .unwrap_or_else(|_| workspace.dir.clone());
+        if canonical.starts_with(&dir_canonical) {
+            return Ok(Some(workspace));
+        }
+
+        // Then check if path matches or is inside the workspace root
         if let Some(ref root) = workspace.config.root {
             let root_canonical = if root.exists() {
                 root.canonicalize().unwrap_or_else(|_| root.clone())
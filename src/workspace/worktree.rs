@@ -134,6 +134,16 @@ pub fn remove_worktrees(workspace_dir: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Remove one worker's worktree of `repo`, leaving its branch in place
+pub fn remove_worktree(repo: &Path, worktree: &Path) -> Result<()> {
+    git_remove_worktree(repo, worktree)?;
+    if worktree.exists() {
+        std::fs::remove_dir_all(worktree)
+            .with_context(|| format!("Failed removing {}", worktree.display()))?;
+    }
+    Ok(())
+}
+
 /// Get the working directory for a specific worker
 pub fn worker_directory(
     workspace_dir: &Path,
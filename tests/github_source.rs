@@ -0,0 +1,102 @@
+use std::os::unix::fs::PermissionsExt;
+
+use hive::config::{TaskSource, TasksConfig};
+use hive::tasks::{counts_for_lane, GithubTasks, NudgeRequest, TaskBackend, TaskState};
+
+const PAGE_ONE: &str = r#"{"data":{"organization":{"projectV2":{"items":{
+  "pageInfo":{"hasNextPage":true,"endCursor":"c1"},
+  "nodes":[
+    {"id":"PVTI_1","content":{"__typename":"Issue","number":12,"title":"Fix login","body":"Users get logged out","url":"https://github.com/acme/app/issues/12","assignees":{"nodes":[]}},
+     "fieldValues":{"nodes":[{},{"name":"Todo","field":{"id":"F_status","name":"Status"}},{"name":"backend/fixes","field":{"id":"F_lane","name":"Lane"}}]}},
+    {"id":"PVTI_2","content":{"__typename":"PullRequest","number":15,"title":"Add search","body":"","url":"https://github.com/acme/app/pull/15","assignees":{"nodes":[{"login":"worker-1"}]}},
+     "fieldValues":{"nodes":[{"name":"In Progress","field":{"id":"F_status","name":"Status"}},{"name":"backend/fixes","field":{"id":"F_lane","name":"Lane"}}]}},
+    {"id":"PVTI_3","content":{"__typename":"DraftIssue","title":"No lane yet","body":"","assignees":{"nodes":[]}},
+     "fieldValues":{"nodes":[{"name":"Todo","field":{"id":"F_status","name":"Status"}}]}}
+  ]}}}}}"#;
+
+const PAGE_TWO: &str = r#"{"data":{"organization":{"projectV2":{"items":{
+  "pageInfo":{"hasNextPage":false,"endCursor":null},
+  "nodes":[
+    {"id":"PVTI_4","content":{"__typename":"DraftIssue","title":"Update docs","body":"","assignees":{"nodes":[]}},
+     "fieldValues":{"nodes":[{"name":"Done","field":{"id":"F_status","name":"Status"}},{"name":"docs","field":{"id":"F_lane","name":"Lane"}}]}},
+    {"id":"PVTI_5","content":{"__typename":"DraftIssue","title":"API reference","body":"","assignees":{"nodes":[]}},
+     "fieldValues":{"nodes":[{"name":"Todo","field":{"id":"F_status","name":"Status"}},{"name":"docs/api","field":{"id":"F_lane","name":"Lane"}}]}}
+  ]}}}}}"#;
+
+const USER_PAGE: &str = r#"{"data":{"user":{"projectV2":{"items":{
+  "pageInfo":{"hasNextPage":false,"endCursor":null},
+  "nodes":[
+    {"id":"PVTI_6","content":{"__typename":"DraftIssue","title":"Side project","body":"","assignees":{"nodes":[]}},
+     "fieldValues":{"nodes":[{"name":"Todo","field":{"id":"F_status","name":"Status"}},{"name":"app","field":{"id":"F_lane","name":"Lane"}}]}}
+  ]}}}}}"#;
+
+#[test]
+fn github_source_reads_project_items_through_gh() {
+    let base = std::env::temp_dir().join(format!("hive-github-{}", std::process::id()));
+    let bin_dir = base.join("bin");
+    std::fs::create_dir_all(&bin_dir).unwrap();
+    std::fs::write(base.join("page1.json"), PAGE_ONE).unwrap();
+    std::fs::write(base.join("page2.json"), PAGE_TWO).unwrap();
+    std::fs::write(base.join("user.json"), USER_PAGE).unwrap();
+
+    // Fake `gh` that replays the recorded pages, picking page two once a
+    // cursor is passed. `octo` is a user, so only its user lookup works.
+    let gh = bin_dir.join("gh");
+    std::fs::write(
+        &gh,
+        format!(
+            "#!/bin/sh\n[ \"$1 $2\" = \"api graphql\" ] || exit 1\ncase \"$*\" in\n  *cursor=c1*) cat '{dir}/page2.json' ;;\n  *org=acme*) cat '{dir}/page1.json' ;;\n  *\"user(login\"*org=octo*) cat '{dir}/user.json' ;;\n  *org=octo*) echo 'Could not resolve to an Organization' >&2; exit 1 ;;\n  *) exit 1 ;;\nesac\n",
+            dir = base.display()
+        ),
+    )
+    .unwrap();
+    std::fs::set_permissions(&gh, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let config = |owner: &str| TasksConfig {
+        source: TaskSource::Github,
+        file: None,
+        github_org: Some(owner.to_string()),
+        github_project: Some(3),
+        github_project_id: None,
+        github_status_field_id: None,
+        github_lane_field_id: Some("F_lane".to_string()),
+        github_poll_secs: None,
+    };
+    let source = GithubTasks::from_config(&config("acme"))
+        .unwrap()
+        .with_gh(&gh);
+
+    let tasks = source.load().unwrap();
+    // `docs/api` can't sit beside the single lane `docs`; only that item is left out
+    let conflicts = source.lane_conflicts();
+    assert_eq!(conflicts.len(), 1);
+    assert!(conflicts[0].contains("PVTI_5") && conflicts[0].contains("docs/api"));
+
+    let fixes = counts_for_lane(&tasks, "backend/fixes");
+    assert_eq!((fixes.backlog, fixes.in_progress, fixes.done), (1, 1, 0));
+    assert_eq!(counts_for_lane(&tasks, "docs").done, 1);
+
+    let lane = tasks.lane("backend/fixes").unwrap();
+    assert_eq!(lane.tasks(TaskState::Backlog)[0].id, "12");
+    let in_progress = &lane.tasks(TaskState::InProgress)[0];
+    assert_eq!(in_progress.claimed_by.as_deref(), Some("worker-1"));
+    assert_eq!(
+        in_progress.pr_url.as_deref(),
+        Some("https://github.com/acme/app/pull/15")
+    );
+
+    // Once watched, `load` only serves what the poller fetched
+    let user_source = GithubTasks::from_config(&config("octo"))
+        .unwrap()
+        .with_gh(&gh);
+    let (nudge_tx, nudge_rx) = std::sync::mpsc::channel();
+    user_source.watch(nudge_tx, base.join("hive.log")).unwrap();
+    assert!(matches!(
+        nudge_rx.recv_timeout(std::time::Duration::from_secs(10)),
+        Ok(NudgeRequest::Refresh)
+    ));
+    let user_tasks = user_source.load().unwrap();
+    assert_eq!(counts_for_lane(&user_tasks, "app").backlog, 1);
+
+    let _ = std::fs::remove_dir_all(&base);
+}